@@ -1,20 +1,33 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Generate gRPC code from protobuf definitions
-    tonic_build::configure()
-        .build_server(true)
-        .build_client(true)
-        .out_dir("src/generated")
-        .compile(
-            &[
-                "proto/agent_core.proto",
-                "proto/health.proto",
-            ],
-            &["proto"],
-        )?;
-
     println!("cargo:rerun-if-changed=proto/agent_core.proto");
     println!("cargo:rerun-if-changed=proto/health.proto");
+    println!("cargo:rerun-if-changed=proto/ext_authz.proto");
     println!("cargo:rerun-if-changed=build.rs");
 
+    // The generated client/server code under `src/generated` is committed
+    // so a plain `cargo build` doesn't need `protoc` on PATH. Only
+    // regenerate it when explicitly asked via the `gen-proto` feature,
+    // e.g. `cargo build --features gen-proto` after editing a `.proto`
+    // file, and then commit the resulting diff under `src/generated`.
+    #[cfg(feature = "gen-proto")]
+    {
+        tonic_build::configure()
+            .build_server(true)
+            .build_client(true)
+            .out_dir("src/generated")
+            // Also commit an encoded FileDescriptorSet so `grpc.rs` can back
+            // the reflection service with `include_bytes!` at a normal
+            // `cargo build`, instead of requiring `protoc` at every build.
+            .file_descriptor_set_path("src/generated/agent_core_descriptor.bin")
+            .compile(
+                &[
+                    "proto/agent_core.proto",
+                    "proto/health.proto",
+                    "proto/ext_authz.proto",
+                ],
+                &["proto"],
+            )?;
+    }
+
     Ok(())
 }
\ No newline at end of file
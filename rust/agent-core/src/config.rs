@@ -1,9 +1,45 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// `serde(with = "duration_seconds")` representation for [`Duration`]
+/// fields: a plain integer number of seconds, rather than serde's default
+/// `{secs, nanos}` struct. Used throughout this module so a hand-written
+/// `defaults.toml`/`config.yaml` layer (see [`Config::from_layered`]) can
+/// write `request_timeout = 30` instead of a nested table.
+mod duration_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let secs = u64::deserialize(deserializer)?;
+        Ok(Duration::from_secs(secs))
+    }
+}
+
+/// `serde(with = "duration_millis")` representation for [`Duration`] fields
+/// too fine-grained for `duration_seconds` to usefully express, such as a
+/// profiler sample interval.
+mod duration_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let millis = u64::deserialize(deserializer)?;
+        Ok(Duration::from_millis(millis))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
@@ -11,8 +47,13 @@ pub struct Config {
     pub enforcement: EnforcementConfig,
     pub security: SecurityConfig,
     pub fsm: FSMConfig,
+    pub execution: ExecutionConfig,
     pub metrics: MetricsConfig,
     pub logging: LoggingConfig,
+    /// Optional; absent in older config files/documents means no privilege
+    /// drop is requested.
+    #[serde(default)]
+    pub privilege: PrivilegeConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,19 +61,125 @@ pub struct ServerConfig {
     pub grpc_addr: String,
     pub metrics_addr: String,
     pub max_connections: usize,
+    #[serde(with = "duration_seconds")]
     pub request_timeout: Duration,
+    /// Enable the `console-subscriber` tokio-console layer for diagnosing
+    /// stuck/leaked async tasks. Only takes effect when built with
+    /// `RUSTFLAGS="--cfg tokio_unstable"`.
+    pub enable_tokio_console: bool,
+    /// Address the tokio-console server binds to when enabled
+    pub console_bind_addr: SocketAddr,
+    /// gRPC endpoint to advertise to peers/service registries. Defaults to
+    /// `grpc_addr` (with a wildcard bind host substituted for a detected
+    /// outbound interface address) — only set this explicitly when the
+    /// process runs behind NAT, a container port-map, or a load balancer
+    /// and the bind address isn't what peers should actually dial.
+    #[serde(default)]
+    pub advertised_grpc_addr: Option<String>,
+    /// Metrics endpoint to advertise, e.g. to a scrape-target registry.
+    /// Same defaulting behavior as [`Self::advertised_grpc_addr`].
+    #[serde(default)]
+    pub advertised_metrics_addr: Option<String>,
+}
+
+impl ServerConfig {
+    /// The gRPC endpoint peers/registries should be told to connect to:
+    /// `advertised_grpc_addr` if set explicitly, otherwise `grpc_addr` with
+    /// a wildcard bind host (`0.0.0.0`/`::`) replaced by a detected outbound
+    /// interface address, preserving the configured port.
+    pub fn advertised_grpc_addr(&self) -> Result<String> {
+        resolve_advertised_addr(&self.grpc_addr, self.advertised_grpc_addr.as_deref())
+    }
+
+    /// Same as [`Self::advertised_grpc_addr`], for the metrics endpoint.
+    pub fn advertised_metrics_addr(&self) -> Result<String> {
+        resolve_advertised_addr(&self.metrics_addr, self.advertised_metrics_addr.as_deref())
+    }
+}
+
+/// Resolve the address to advertise for a bound socket: `advertised` if the
+/// operator set one explicitly, otherwise `bind_addr` unless its host is a
+/// wildcard, in which case a detected outbound interface address is
+/// substituted while the configured port is kept as-is.
+fn resolve_advertised_addr(bind_addr: &str, advertised: Option<&str>) -> Result<String> {
+    if let Some(addr) = advertised {
+        return Ok(addr.to_string());
+    }
+
+    let parsed: SocketAddr = bind_addr
+        .parse()
+        .with_context(|| format!("invalid bind address: {}", bind_addr))?;
+    if !parsed.ip().is_unspecified() {
+        return Ok(bind_addr.to_string());
+    }
+
+    let outbound_ip = detect_outbound_addr()
+        .context("failed to detect outbound interface address for advertised endpoint")?;
+    Ok(SocketAddr::new(outbound_ip, parsed.port()).to_string())
+}
+
+/// Determine the local address the kernel would route outbound traffic
+/// through, by "connecting" a UDP socket to a public address — no packets
+/// are sent; `connect` on a `UdpSocket` just asks the kernel to pick a
+/// route and local address, which `local_addr` then reads back.
+fn detect_outbound_addr() -> Result<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").context("failed to bind probe socket")?;
+    socket.connect("8.8.8.8:80").context("failed to select outbound route")?;
+    Ok(socket.local_addr().context("failed to read outbound local address")?.ip())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxConfig {
     pub memory_limit: u64,        // Memory limit in bytes
     pub cpu_limit: u64,           // CPU time limit in nanoseconds
+    #[serde(with = "duration_seconds")]
     pub execution_timeout: Duration,
     pub max_file_size: u64,       // Maximum file size in bytes
     pub allowed_hosts: Vec<String>,
     pub blocked_syscalls: Vec<String>,
     pub temp_dir: PathBuf,
     pub max_instances: usize,
+    /// When enabled, `WASISandbox::execute_in_instance` logs an
+    /// instructions/memory/duration line after every execution — off by
+    /// default since it runs on the hot path for every call.
+    #[serde(default)]
+    pub trace_execution: bool,
+    /// Which engine `WASISandbox::execute_wasm`/`execute_wasm_sliced` run
+    /// user-supplied modules on. Defaults to `Wasmtime`.
+    #[serde(default)]
+    pub backend: Backend,
+    /// When enabled, `WASISandbox` attaches a `wasmtime::GuestProfiler` to
+    /// every Wasmtime-backed execution and writes a Firefox-profiler-format
+    /// flamegraph to the execution's sandbox temp dir. Off by default: a
+    /// profiler adds an epoch-driven sampling callback to every call, which
+    /// is unwanted overhead on the hot path when nobody's debugging.
+    #[serde(default)]
+    pub enable_profiling: bool,
+    /// How often the guest call stack is sampled while profiling is
+    /// enabled, driven by the same epoch thread that backstops
+    /// `execution_timeout`.
+    #[serde(with = "duration_millis")]
+    pub profiling_sample_interval: Duration,
+}
+
+/// Execution engine for user-supplied WebAssembly modules. `Wasmtime` JIT
+/// compiles and is the default — fastest for longer-running code, but needs
+/// a W^X-capable host and a non-trivial per-engine memory footprint. `Wasmi`
+/// interprets the module directly: slower per instruction, but runs on
+/// locked-down hosts that can't grant a JIT executable memory, and has
+/// near-zero per-call startup cost, which matters more than throughput for
+/// short snippets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    Wasmtime,
+    Wasmi,
+}
+
+impl Default for Backend {
+    fn default() -> Self {
+        Backend::Wasmtime
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,25 +188,118 @@ pub struct EnforcementConfig {
     pub rate_limit_config: RateLimitConfig,
     pub circuit_breaker_config: CircuitBreakerConfig,
     pub token_validator_config: TokenValidatorConfig,
+    pub resource_limits_config: ResourceLimitsConfig,
+    /// How often the background GC sweep runs, evicting idle rate-limiter
+    /// buckets and circuit-breaker entries. Also used as the staleness
+    /// cutoff: entries touched more recently than this are skipped without
+    /// recomputing their refill, so a sweep over a mostly-active map stays
+    /// cheap.
+    #[serde(with = "duration_seconds")]
+    pub gc_sweep_interval: Duration,
+    /// Optional Envoy `ext_authz` gRPC server, letting this enforcement
+    /// pipeline sit in front of any Envoy-fronted service instead of only
+    /// this crate's own gRPC API.
+    #[serde(default)]
+    pub ext_authz: ExtAuthzConfig,
+}
+
+/// Envoy external-authorization v3 `Authorization.Check` server. Disabled
+/// (the default) means `main` never binds `listen_addr`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtAuthzConfig {
+    pub enabled: bool,
+    pub listen_addr: String,
+    /// Whether to return `OkHttpResponse` (instead of a 503 `DeniedHttpResponse`)
+    /// when the enforcement pipeline itself errors out (as opposed to denying
+    /// the request), so an enforcement-side bug or outage can't take down
+    /// every request through the Envoy-fronted service.
+    pub fail_open: bool,
+}
+
+impl Default for ExtAuthzConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: "0.0.0.0:9191".to_string(),
+            fail_open: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeoutConfig {
+    #[serde(with = "duration_seconds")]
     pub max_duration: Duration,
+    #[serde(with = "duration_seconds")]
     pub warning_threshold: Duration,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitConfig {
-    pub requests_per_second: u32,
-    pub burst_size: u32,
-    pub window_size: Duration,
+    /// Meters one request per `enforce_request` call, regardless of its size
+    pub ops: TokenBucketConfig,
+    /// Meters `ExecuteTaskRequest::estimated_tokens`, so large-token tasks
+    /// are throttled independently of how many requests they make
+    pub tokens: TokenBucketConfig,
+    /// Per-`TaskPriority` scaling of how fast a request drains the shared
+    /// per-key buckets, so a flood of low-priority work can't starve a
+    /// critical one under the same bucket
+    pub priority_factors: PriorityFactors,
+    /// Cap on how long `EnforcementGateway::enforce_request_blocking` will
+    /// sleep and retry a rate-limited request before hard-rejecting it
+    /// instead. A shortfall whose computed `retry_after` exceeds this is
+    /// returned to the caller immediately rather than smoothed.
+    #[serde(with = "duration_millis")]
+    pub max_blocking_wait: Duration,
+}
+
+/// Per-priority rate-limit scaling, one [`PriorityFactor`] per
+/// `enforcement::TaskPriority` variant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityFactors {
+    pub low: PriorityFactor,
+    pub normal: PriorityFactor,
+    pub high: PriorityFactor,
+    pub critical: PriorityFactor,
+}
+
+/// How much headroom a given priority gets in the shared rate-limit buckets
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PriorityFactor {
+    /// Multiplies the token cost charged against the shared buckets;
+    /// below 1.0 is cheaper (more headroom), above 1.0 is more expensive
+    pub usage_factor: f64,
+    /// How far this priority may momentarily overdraw a bucket below zero,
+    /// borrowing against the next refill instead of being rejected outright:
+    /// the allowed overdraw is `(burst_multiplier - 1.0)` times the bucket's
+    /// steady-state `size`, so `1.0` or less allows none
+    pub burst_multiplier: f64,
+}
+
+impl Default for PriorityFactor {
+    fn default() -> Self {
+        Self { usage_factor: 1.0, burst_multiplier: 1.0 }
+    }
+}
+
+/// Configuration for a single token bucket in the dual-bucket rate limiter,
+/// mirroring the Firecracker/cloud-hypervisor rate limiter design.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBucketConfig {
+    /// Steady-state capacity the bucket refills up to
+    pub size: u64,
+    /// Time to go from empty to `size` at the steady-state refill rate
+    #[serde(with = "duration_seconds")]
+    pub complete_refill_time: Duration,
+    /// Extra one-time allotment above `size`, spent first and never refilled
+    pub one_time_burst: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CircuitBreakerConfig {
     pub failure_threshold: u32,
     pub success_threshold: u32,
+    #[serde(with = "duration_seconds")]
     pub timeout: Duration,
 }
 
@@ -69,23 +309,85 @@ pub struct TokenValidatorConfig {
     pub cost_per_token: f64,
 }
 
+/// Ceilings `EnforcementGateway::validate_resources` checks a task's
+/// `ResourceRequirements` against
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLimitsConfig {
+    pub max_memory_mb: u64,
+    pub max_cpu_cores: f32,
+    pub max_network_bandwidth_mbps: u32,
+    pub max_storage_mb: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
     pub opa_policy_path: PathBuf,
+    /// Rego query evaluated to decide whether code is allowed, e.g. `data.agent.allow`
+    pub opa_allow_query: String,
+    /// Rego query evaluated for the list of human-readable violation messages
+    pub opa_violations_query: String,
     pub encryption_key_path: PathBuf,
+    /// Id of the KEK (found as `<id>.key` alongside `encryption_key_path`)
+    /// that newly encrypted payloads are sealed under. Older KEKs stay
+    /// loaded so payloads sealed under them can still be decrypted.
+    pub encryption_key_id: String,
+    /// Shared secret used to verify bearer tokens presented to the gRPC
+    /// `AuthInterceptor`. Falls back to an ephemeral, process-local secret
+    /// when unset, so unconfigured dev environments still start up.
+    pub auth_token_secret_path: PathBuf,
     pub tls_cert_path: Option<PathBuf>,
     pub tls_key_path: Option<PathBuf>,
     pub enable_audit_log: bool,
     pub audit_log_path: PathBuf,
+    /// Roll over to a new audit log segment once the current one reaches this size
+    pub audit_log_rotate_max_bytes: u64,
+    /// Roll over to a new audit log segment once the current one reaches this age
+    #[serde(with = "duration_seconds")]
+    pub audit_log_rotate_max_age: Duration,
+    /// Exact-suffix matched domains outbound code may connect to
+    pub allowed_egress_domains: Vec<String>,
+    /// CIDRs (e.g. "10.0.5.0/24") explicitly allowed even if they fall in a
+    /// private range that would otherwise be blocked
+    pub allowed_egress_cidrs: Vec<String>,
+    /// CIDRs always denied, checked before the allow lists
+    pub denied_egress_cidrs: Vec<String>,
+    /// Allowed destination ports, as single values or "lo-hi" ranges
+    pub allowed_egress_ports: Vec<String>,
+    /// `Content-Security-Policy` value applied to outgoing HTTP responses
+    pub response_content_security_policy: String,
+    /// `X-Frame-Options` value applied to outgoing HTTP responses
+    pub response_x_frame_options: String,
+    /// `X-Content-Type-Options` value applied to outgoing HTTP responses
+    pub response_x_content_type_options: String,
+    /// `Referrer-Policy` value applied to outgoing HTTP responses
+    pub response_referrer_policy: String,
+    /// `Permissions-Policy` value applied to outgoing HTTP responses
+    pub response_permissions_policy: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FSMConfig {
     pub max_states: usize,
     pub max_transitions: usize,
+    #[serde(with = "duration_seconds")]
     pub state_timeout: Duration,
     pub persistence_enabled: bool,
     pub persistence_path: PathBuf,
+    /// How often the background timeout monitor scans `active_instances`
+    /// for one whose current state has exceeded its timeout
+    #[serde(with = "duration_seconds")]
+    pub timeout_check_interval: Duration,
+}
+
+/// Configuration for `ExecutionEngine`'s content-addressed result cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionConfig {
+    /// Skips the cache lookup/store entirely when `false`, so every request
+    /// pays the full sandbox cost regardless of `AgentExecutionRequest::cacheable`
+    pub result_cache_enabled: bool,
+    /// Maximum number of completed results the LRU cache retains. A value of
+    /// `0` behaves the same as `result_cache_enabled: false`.
+    pub result_cache_capacity: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,7 +395,36 @@ pub struct MetricsConfig {
     pub enabled: bool,
     pub addr: SocketAddr,
     pub path: String,
+    #[serde(with = "duration_seconds")]
     pub collection_interval: Duration,
+    pub otel: OtelExporterConfig,
+    pub pushgateway: PushGatewayConfig,
+    /// Maximum number of distinct label-value tuples a single labeled metric
+    /// may track before further values collapse into an "other" series
+    pub max_label_series: usize,
+}
+
+/// Configuration for pushing metrics to a Prometheus Pushgateway, used by
+/// short-lived/batch agent runs that would otherwise exit before a scrape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushGatewayConfig {
+    pub enabled: bool,
+    pub url: String,
+    pub job: String,
+    #[serde(with = "duration_seconds")]
+    pub push_interval: Duration,
+    pub auth_header: Option<String>,
+}
+
+/// Configuration for the push-based OpenTelemetry OTLP exporter that runs
+/// alongside the pull-based Prometheus endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtelExporterConfig {
+    pub enabled: bool,
+    pub collector_endpoint: String,
+    #[serde(with = "duration_seconds")]
+    pub export_interval: Duration,
+    pub service_name: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +434,23 @@ pub struct LoggingConfig {
     pub output: String, // stdout, stderr, or file path
 }
 
+/// Privilege drop applied once by [`crate::privilege::drop_privileges`]
+/// after the gRPC/metrics sockets are bound. Unset (the default) leaves the
+/// process running as whatever user started it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivilegeConfig {
+    /// User to `setuid` into. Only supported on Unix; `Config::validate`
+    /// rejects it on any other platform and if the user doesn't exist.
+    pub run_as_user: Option<String>,
+    /// Group to `setgid` into. Defaults to `run_as_user`'s primary group
+    /// when unset.
+    pub run_as_group: Option<String>,
+    /// Directory to `chroot` into before dropping privileges, confining the
+    /// unprivileged process to the sandbox's own filesystem view. Typically
+    /// `sandbox.temp_dir`.
+    pub chroot_dir: Option<PathBuf>,
+}
+
 impl Config {
     pub fn from_env() -> Result<Self> {
         let config = Config {
@@ -119,6 +467,15 @@ impl Config {
                         .parse()
                         .unwrap_or(30),
                 ),
+                enable_tokio_console: std::env::var("ENABLE_TOKIO_CONSOLE")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                console_bind_addr: std::env::var("TOKIO_CONSOLE_ADDR")
+                    .unwrap_or_else(|_| "127.0.0.1:6669".to_string())
+                    .parse()
+                    .unwrap_or_else(|_| "127.0.0.1:6669".parse().unwrap()),
+                advertised_grpc_addr: std::env::var("ADVERTISED_GRPC_ADDR").ok(),
+                advertised_metrics_addr: std::env::var("ADVERTISED_METRICS_ADDR").ok(),
             },
             sandbox: SandboxConfig {
                 memory_limit: std::env::var("SANDBOX_MEMORY_LIMIT")
@@ -156,6 +513,22 @@ impl Config {
                     .unwrap_or_else(|_| "100".to_string())
                     .parse()
                     .unwrap_or(100),
+                trace_execution: std::env::var("SANDBOX_TRACE_EXECUTION")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                backend: match std::env::var("SANDBOX_BACKEND").as_deref() {
+                    Ok("wasmi") => Backend::Wasmi,
+                    _ => Backend::Wasmtime,
+                },
+                enable_profiling: std::env::var("SANDBOX_ENABLE_PROFILING")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                profiling_sample_interval: Duration::from_millis(
+                    std::env::var("SANDBOX_PROFILING_SAMPLE_INTERVAL_MS")
+                        .unwrap_or_else(|_| "1".to_string())
+                        .parse()
+                        .unwrap_or(1),
+                ),
             },
             enforcement: EnforcementConfig {
                 timeout_config: TimeoutConfig {
@@ -173,19 +546,69 @@ impl Config {
                     ),
                 },
                 rate_limit_config: RateLimitConfig {
-                    requests_per_second: std::env::var("RATE_LIMIT_RPS")
-                        .unwrap_or_else(|_| "100".to_string())
-                        .parse()
-                        .unwrap_or(100),
-                    burst_size: std::env::var("RATE_LIMIT_BURST")
-                        .unwrap_or_else(|_| "200".to_string())
-                        .parse()
-                        .unwrap_or(200),
-                    window_size: Duration::from_secs(
-                        std::env::var("RATE_LIMIT_WINDOW")
-                            .unwrap_or_else(|_| "60".to_string())
+                    ops: TokenBucketConfig {
+                        size: std::env::var("RATE_LIMIT_OPS_SIZE")
+                            .unwrap_or_else(|_| "100".to_string())
                             .parse()
-                            .unwrap_or(60),
+                            .unwrap_or(100),
+                        complete_refill_time: Duration::from_secs(
+                            std::env::var("RATE_LIMIT_OPS_REFILL_SECS")
+                                .unwrap_or_else(|_| "1".to_string())
+                                .parse()
+                                .unwrap_or(1),
+                        ),
+                        one_time_burst: std::env::var("RATE_LIMIT_OPS_BURST")
+                            .ok()
+                            .and_then(|v| v.parse().ok())
+                            .or(Some(100)),
+                    },
+                    tokens: TokenBucketConfig {
+                        size: std::env::var("RATE_LIMIT_TOKENS_SIZE")
+                            .unwrap_or_else(|_| "100000".to_string())
+                            .parse()
+                            .unwrap_or(100_000),
+                        complete_refill_time: Duration::from_secs(
+                            std::env::var("RATE_LIMIT_TOKENS_REFILL_SECS")
+                                .unwrap_or_else(|_| "60".to_string())
+                                .parse()
+                                .unwrap_or(60),
+                        ),
+                        one_time_burst: std::env::var("RATE_LIMIT_TOKENS_BURST")
+                            .ok()
+                            .and_then(|v| v.parse().ok())
+                            .or(Some(50_000)),
+                    },
+                    priority_factors: PriorityFactors {
+                        low: PriorityFactor {
+                            usage_factor: std::env::var("RATE_LIMIT_PRIORITY_LOW_USAGE_FACTOR")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(2.0),
+                            burst_multiplier: std::env::var("RATE_LIMIT_PRIORITY_LOW_BURST_MULTIPLIER")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(1.0),
+                        },
+                        normal: PriorityFactor {
+                            usage_factor: std::env::var("RATE_LIMIT_PRIORITY_NORMAL_USAGE_FACTOR")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(1.0),
+                            burst_multiplier: std::env::var("RATE_LIMIT_PRIORITY_NORMAL_BURST_MULTIPLIER")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(1.0),
+                        },
+                        high: PriorityFactor {
+                            usage_factor: std::env::var("RATE_LIMIT_PRIORITY_HIGH_USAGE_FACTOR")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.5),
+                            burst_multiplier: std::env::var("RATE_LIMIT_PRIORITY_HIGH_BURST_MULTIPLIER")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(1.5),
+                        },
+                        critical: PriorityFactor {
+                            usage_factor: std::env::var("RATE_LIMIT_PRIORITY_CRITICAL_USAGE_FACTOR")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(0.25),
+                            burst_multiplier: std::env::var("RATE_LIMIT_PRIORITY_CRITICAL_BURST_MULTIPLIER")
+                                .ok().and_then(|v| v.parse().ok()).unwrap_or(2.0),
+                        },
+                    },
+                    max_blocking_wait: Duration::from_millis(
+                        std::env::var("RATE_LIMIT_MAX_BLOCKING_WAIT_MS")
+                            .unwrap_or_else(|_| "5000".to_string())
+                            .parse()
+                            .unwrap_or(5000),
                     ),
                 },
                 circuit_breaker_config: CircuitBreakerConfig {
@@ -214,14 +637,57 @@ impl Config {
                         .parse()
                         .unwrap_or(0.002),
                 },
+                resource_limits_config: ResourceLimitsConfig {
+                    max_memory_mb: std::env::var("ENFORCEMENT_MAX_MEMORY_MB")
+                        .unwrap_or_else(|_| "2048".to_string())
+                        .parse()
+                        .unwrap_or(2048),
+                    max_cpu_cores: std::env::var("ENFORCEMENT_MAX_CPU_CORES")
+                        .unwrap_or_else(|_| "4.0".to_string())
+                        .parse()
+                        .unwrap_or(4.0),
+                    max_network_bandwidth_mbps: std::env::var("ENFORCEMENT_MAX_BANDWIDTH_MBPS")
+                        .unwrap_or_else(|_| "100".to_string())
+                        .parse()
+                        .unwrap_or(100),
+                    max_storage_mb: std::env::var("ENFORCEMENT_MAX_STORAGE_MB")
+                        .unwrap_or_else(|_| "1024".to_string())
+                        .parse()
+                        .unwrap_or(1024),
+                },
+                gc_sweep_interval: Duration::from_secs(
+                    std::env::var("ENFORCEMENT_GC_SWEEP_INTERVAL_SECS")
+                        .unwrap_or_else(|_| "300".to_string())
+                        .parse()
+                        .unwrap_or(300),
+                ),
+                ext_authz: ExtAuthzConfig {
+                    enabled: std::env::var("EXT_AUTHZ_ENABLED")
+                        .map(|v| v == "true")
+                        .unwrap_or(false),
+                    listen_addr: std::env::var("EXT_AUTHZ_LISTEN_ADDR")
+                        .unwrap_or_else(|_| "0.0.0.0:9191".to_string()),
+                    fail_open: std::env::var("EXT_AUTHZ_FAIL_OPEN")
+                        .map(|v| v == "true")
+                        .unwrap_or(false),
+                },
             },
             security: SecurityConfig {
                 opa_policy_path: PathBuf::from(
                     std::env::var("OPA_POLICY_PATH").unwrap_or_else(|_| "/app/policies".to_string())
                 ),
+                opa_allow_query: std::env::var("OPA_ALLOW_QUERY")
+                    .unwrap_or_else(|_| "data.agent.allow".to_string()),
+                opa_violations_query: std::env::var("OPA_VIOLATIONS_QUERY")
+                    .unwrap_or_else(|_| "data.agent.violations".to_string()),
                 encryption_key_path: PathBuf::from(
                     std::env::var("ENCRYPTION_KEY_PATH").unwrap_or_else(|_| "/app/keys/encryption.key".to_string())
                 ),
+                encryption_key_id: std::env::var("ENCRYPTION_KEY_ID").unwrap_or_else(|_| "default".to_string()),
+                auth_token_secret_path: PathBuf::from(
+                    std::env::var("AUTH_TOKEN_SECRET_PATH")
+                        .unwrap_or_else(|_| "/app/keys/auth_token.secret".to_string())
+                ),
                 tls_cert_path: std::env::var("TLS_CERT_PATH").ok().map(PathBuf::from),
                 tls_key_path: std::env::var("TLS_KEY_PATH").ok().map(PathBuf::from),
                 enable_audit_log: std::env::var("ENABLE_AUDIT_LOG")
@@ -231,6 +697,50 @@ impl Config {
                 audit_log_path: PathBuf::from(
                     std::env::var("AUDIT_LOG_PATH").unwrap_or_else(|_| "/var/log/agent-audit.log".to_string())
                 ),
+                audit_log_rotate_max_bytes: std::env::var("AUDIT_LOG_ROTATE_MAX_BYTES")
+                    .unwrap_or_else(|_| "104857600".to_string()) // 100MB
+                    .parse()
+                    .unwrap_or(104_857_600),
+                audit_log_rotate_max_age: Duration::from_secs(
+                    std::env::var("AUDIT_LOG_ROTATE_MAX_AGE_SECONDS")
+                        .unwrap_or_else(|_| "86400".to_string()) // 24h
+                        .parse()
+                        .unwrap_or(86_400),
+                ),
+                allowed_egress_domains: std::env::var("ALLOWED_EGRESS_DOMAINS")
+                    .unwrap_or_else(|_| "api.openai.com,api.anthropic.com,api.cohere.ai,httpbin.org".to_string())
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                allowed_egress_cidrs: std::env::var("ALLOWED_EGRESS_CIDRS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                denied_egress_cidrs: std::env::var("DENIED_EGRESS_CIDRS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                allowed_egress_ports: std::env::var("ALLOWED_EGRESS_PORTS")
+                    .unwrap_or_else(|_| "80,443,8000-8999".to_string())
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                response_content_security_policy: std::env::var("RESPONSE_CSP")
+                    .unwrap_or_else(|_| "default-src 'none'; frame-ancestors 'none'".to_string()),
+                response_x_frame_options: std::env::var("RESPONSE_X_FRAME_OPTIONS")
+                    .unwrap_or_else(|_| "DENY".to_string()),
+                response_x_content_type_options: std::env::var("RESPONSE_X_CONTENT_TYPE_OPTIONS")
+                    .unwrap_or_else(|_| "nosniff".to_string()),
+                response_referrer_policy: std::env::var("RESPONSE_REFERRER_POLICY")
+                    .unwrap_or_else(|_| "no-referrer".to_string()),
+                response_permissions_policy: std::env::var("RESPONSE_PERMISSIONS_POLICY")
+                    .unwrap_or_else(|_| "geolocation=(), microphone=(), camera=()".to_string()),
             },
             fsm: FSMConfig {
                 max_states: std::env::var("FSM_MAX_STATES")
@@ -254,6 +764,22 @@ impl Config {
                 persistence_path: PathBuf::from(
                     std::env::var("FSM_PERSISTENCE_PATH").unwrap_or_else(|_| "/var/lib/agent-fsm".to_string())
                 ),
+                timeout_check_interval: Duration::from_secs(
+                    std::env::var("FSM_TIMEOUT_CHECK_INTERVAL")
+                        .unwrap_or_else(|_| "5".to_string())
+                        .parse()
+                        .unwrap_or(5),
+                ),
+            },
+            execution: ExecutionConfig {
+                result_cache_enabled: std::env::var("RESULT_CACHE_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                result_cache_capacity: std::env::var("RESULT_CACHE_CAPACITY")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .unwrap_or(1000),
             },
             metrics: MetricsConfig {
                 enabled: std::env::var("METRICS_ENABLED")
@@ -271,17 +797,103 @@ impl Config {
                         .parse()
                         .unwrap_or(15),
                 ),
+                otel: OtelExporterConfig {
+                    enabled: std::env::var("OTEL_EXPORTER_ENABLED")
+                        .unwrap_or_else(|_| "false".to_string())
+                        .parse()
+                        .unwrap_or(false),
+                    collector_endpoint: std::env::var("OTEL_COLLECTOR_ENDPOINT")
+                        .unwrap_or_else(|_| "http://localhost:4317".to_string()),
+                    export_interval: Duration::from_secs(
+                        std::env::var("OTEL_EXPORT_INTERVAL_SECONDS")
+                            .unwrap_or_else(|_| "15".to_string())
+                            .parse()
+                            .unwrap_or(15),
+                    ),
+                    service_name: std::env::var("OTEL_SERVICE_NAME")
+                        .unwrap_or_else(|_| "agent-core".to_string()),
+                },
+                pushgateway: PushGatewayConfig {
+                    enabled: std::env::var("PUSHGATEWAY_ENABLED")
+                        .unwrap_or_else(|_| "false".to_string())
+                        .parse()
+                        .unwrap_or(false),
+                    url: std::env::var("PUSHGATEWAY_URL")
+                        .unwrap_or_else(|_| "http://localhost:9091".to_string()),
+                    job: std::env::var("PUSHGATEWAY_JOB")
+                        .unwrap_or_else(|_| "agent-core".to_string()),
+                    push_interval: Duration::from_secs(
+                        std::env::var("PUSHGATEWAY_PUSH_INTERVAL_SECONDS")
+                            .unwrap_or_else(|_| "10".to_string())
+                            .parse()
+                            .unwrap_or(10),
+                    ),
+                    auth_header: std::env::var("PUSHGATEWAY_AUTH_HEADER").ok(),
+                },
+                max_label_series: std::env::var("METRICS_MAX_LABEL_SERIES")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()
+                    .unwrap_or(200),
             },
             logging: LoggingConfig {
                 level: std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
                 format: std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string()),
                 output: std::env::var("LOG_OUTPUT").unwrap_or_else(|_| "stdout".to_string()),
             },
+            privilege: PrivilegeConfig {
+                run_as_user: std::env::var("RUN_AS_USER").ok(),
+                run_as_group: std::env::var("RUN_AS_GROUP").ok(),
+                chroot_dir: std::env::var("CHROOT_DIR").ok().map(PathBuf::from),
+            },
         };
 
         Ok(config)
     }
 
+    /// Load a complete [`Config`] from a TOML file on disk instead of the
+    /// environment. Used by [`crate::config_manager::ConfigManager`] to load
+    /// the initial configuration and to re-parse it on every file change;
+    /// the result still goes through [`Config::validate`] before a caller
+    /// should treat it as live.
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read config file {:?}: {}", path, e))?;
+        let config: Config = toml::from_str(&raw)
+            .map_err(|e| anyhow::anyhow!("Failed to parse config file {:?}: {}", path, e))?;
+        Ok(config)
+    }
+
+    /// Load configuration from three merged layers, each taking priority
+    /// over the last: a `defaults.toml` sitting next to `path`, `path`
+    /// itself (a per-deployment `config.toml`/`config.yaml`), and finally
+    /// the same environment variables [`Config::from_env`] reads — but
+    /// applied only where actually set, so an operator can override a
+    /// single nested field (e.g. `RATE_LIMIT_OPS_SIZE`) without restating
+    /// the rest of the tree. Each layer is deep-merged as JSON before being
+    /// deserialized into a complete [`Config`], which then still goes
+    /// through [`Config::validate`].
+    pub fn from_layered(path: &std::path::Path) -> Result<Self> {
+        let mut merged = serde_json::json!({});
+
+        if let Some(dir) = path.parent() {
+            let defaults_path = dir.join("defaults.toml");
+            if defaults_path.exists() {
+                deep_merge(&mut merged, load_layer(&defaults_path)?);
+            }
+        }
+
+        if path.exists() {
+            deep_merge(&mut merged, load_layer(path)?);
+        }
+
+        deep_merge(&mut merged, env_overlay());
+
+        let config: Config = serde_json::from_value(merged)
+            .with_context(|| format!("Failed to assemble layered configuration from {:?}", path))?;
+        config.validate()?;
+        Ok(config)
+    }
+
     pub fn validate(&self) -> Result<()> {
         // Validate server configuration
         if self.server.max_connections == 0 {
@@ -307,6 +919,269 @@ impl Config {
             return Err(anyhow::anyhow!("OPA policy path does not exist: {:?}", self.security.opa_policy_path));
         }
 
+        // Validate privilege-drop configuration
+        if let Some(user) = &self.privilege.run_as_user {
+            #[cfg(not(unix))]
+            {
+                return Err(anyhow::anyhow!(
+                    "privilege.run_as_user is set but privilege drop is only supported on Unix"
+                ));
+            }
+            #[cfg(unix)]
+            {
+                if !crate::privilege::user_exists(user) {
+                    return Err(anyhow::anyhow!("privilege.run_as_user refers to an unknown user: {}", user));
+                }
+                if let Some(group) = &self.privilege.run_as_group {
+                    if !crate::privilege::group_exists(group) {
+                        return Err(anyhow::anyhow!(
+                            "privilege.run_as_group refers to an unknown group: {}",
+                            group
+                        ));
+                    }
+                }
+            }
+        } else if self.privilege.run_as_group.is_some() || self.privilege.chroot_dir.is_some() {
+            return Err(anyhow::anyhow!(
+                "privilege.run_as_group/chroot_dir require privilege.run_as_user to also be set"
+            ));
+        }
+
+        // An advertised address that parses but has a zero port is almost
+        // always a copy-paste of a bind address like "0.0.0.0:0" — reject it
+        // so nothing registers or reports an unreachable endpoint.
+        for (name, addr) in [
+            ("server.advertised_grpc_addr", &self.server.advertised_grpc_addr),
+            ("server.advertised_metrics_addr", &self.server.advertised_metrics_addr),
+        ] {
+            if let Some(addr) = addr {
+                if let Ok(parsed) = addr.parse::<SocketAddr>() {
+                    if parsed.port() == 0 {
+                        return Err(anyhow::anyhow!("{} has a zero port: {}", name, addr));
+                    }
+                }
+            }
+        }
+
+        // Validate ext_authz configuration
+        if self.enforcement.ext_authz.enabled
+            && self.enforcement.ext_authz.listen_addr.parse::<SocketAddr>().is_err()
+        {
+            return Err(anyhow::anyhow!(
+                "enforcement.ext_authz.listen_addr is not a valid socket address: {}",
+                self.enforcement.ext_authz.listen_addr
+            ));
+        }
+
         Ok(())
     }
+}
+
+/// Parse a single layer file (TOML or YAML, by extension) into a generic
+/// [`serde_json::Value`] so [`deep_merge`] can combine it with the other
+/// layers before the whole tree is deserialized into a [`Config`] at once.
+fn load_layer(path: &std::path::Path) -> Result<serde_json::Value> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config layer {:?}", path))?;
+
+    let is_yaml = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml") | Some("yml")
+    );
+
+    if is_yaml {
+        let value: serde_yaml::Value = serde_yaml::from_str(&raw)
+            .with_context(|| format!("Failed to parse YAML config layer {:?}", path))?;
+        serde_json::to_value(value)
+            .with_context(|| format!("Failed to normalize YAML config layer {:?}", path))
+    } else {
+        let value: toml::Value = toml::from_str(&raw)
+            .with_context(|| format!("Failed to parse TOML config layer {:?}", path))?;
+        serde_json::to_value(value)
+            .with_context(|| format!("Failed to normalize TOML config layer {:?}", path))
+    }
+}
+
+/// Recursively merge `overlay` into `base`, with `overlay` taking priority.
+/// Objects are merged key-by-key; any other value (array, scalar, or a type
+/// mismatch between layers) is replaced wholesale, matching how every other
+/// layered-config tool (Helm values, `config-rs`, figment) treats non-map
+/// values.
+fn deep_merge(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    use serde_json::Value;
+
+    match (base, overlay) {
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, overlay_value) in overlay_map {
+                match base_map.get_mut(&key) {
+                    Some(base_value) => deep_merge(base_value, overlay_value),
+                    None => {
+                        base_map.insert(key, overlay_value);
+                    }
+                }
+            }
+        }
+        (base_slot, overlay_value) => *base_slot = overlay_value,
+    }
+}
+
+/// Build the highest-priority overlay layer from the same environment
+/// variables [`Config::from_env`] reads, inserting a key only when the
+/// variable is actually set so an unset var can't clobber a value supplied
+/// by `defaults.toml` or the per-deployment file underneath it.
+fn env_overlay() -> serde_json::Value {
+    use serde_json::Value;
+
+    let mut root = serde_json::json!({});
+
+    fn set(root: &mut Value, path: &[&str], value: Value) {
+        let mut cur = root;
+        for (i, key) in path.iter().enumerate() {
+            if i == path.len() - 1 {
+                if let Value::Object(map) = cur {
+                    map.insert((*key).to_string(), value);
+                }
+                return;
+            }
+            if !matches!(cur, Value::Object(_)) {
+                *cur = serde_json::json!({});
+            }
+            cur = cur
+                .as_object_mut()
+                .unwrap()
+                .entry((*key).to_string())
+                .or_insert_with(|| serde_json::json!({}));
+        }
+    }
+
+    fn str_var(root: &mut Value, path: &[&str], env: &str) {
+        if let Ok(v) = std::env::var(env) {
+            set(root, path, Value::String(v));
+        }
+    }
+
+    fn parsed_var<T: std::str::FromStr + Serialize>(root: &mut Value, path: &[&str], env: &str) {
+        if let Some(v) = std::env::var(env).ok().and_then(|v| v.parse::<T>().ok()) {
+            if let Ok(v) = serde_json::to_value(v) {
+                set(root, path, v);
+            }
+        }
+    }
+
+    fn list_var(root: &mut Value, path: &[&str], env: &str) {
+        if let Ok(v) = std::env::var(env) {
+            let items: Vec<String> = v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+            set(root, path, serde_json::json!(items));
+        }
+    }
+
+    str_var(&mut root, &["server", "grpc_addr"], "GRPC_ADDR");
+    str_var(&mut root, &["server", "metrics_addr"], "METRICS_ADDR");
+    parsed_var::<usize>(&mut root, &["server", "max_connections"], "MAX_CONNECTIONS");
+    parsed_var::<u64>(&mut root, &["server", "request_timeout"], "REQUEST_TIMEOUT_SECONDS");
+    parsed_var::<bool>(&mut root, &["server", "enable_tokio_console"], "ENABLE_TOKIO_CONSOLE");
+    str_var(&mut root, &["server", "console_bind_addr"], "TOKIO_CONSOLE_ADDR");
+    str_var(&mut root, &["server", "advertised_grpc_addr"], "ADVERTISED_GRPC_ADDR");
+    str_var(&mut root, &["server", "advertised_metrics_addr"], "ADVERTISED_METRICS_ADDR");
+
+    parsed_var::<u64>(&mut root, &["sandbox", "memory_limit"], "SANDBOX_MEMORY_LIMIT");
+    parsed_var::<u64>(&mut root, &["sandbox", "cpu_limit"], "SANDBOX_CPU_LIMIT");
+    parsed_var::<u64>(&mut root, &["sandbox", "execution_timeout"], "SANDBOX_EXECUTION_TIMEOUT");
+    parsed_var::<u64>(&mut root, &["sandbox", "max_file_size"], "SANDBOX_MAX_FILE_SIZE");
+    list_var(&mut root, &["sandbox", "allowed_hosts"], "SANDBOX_ALLOWED_HOSTS");
+    list_var(&mut root, &["sandbox", "blocked_syscalls"], "SANDBOX_BLOCKED_SYSCALLS");
+    str_var(&mut root, &["sandbox", "temp_dir"], "SANDBOX_TEMP_DIR");
+    parsed_var::<usize>(&mut root, &["sandbox", "max_instances"], "SANDBOX_MAX_INSTANCES");
+    parsed_var::<bool>(&mut root, &["sandbox", "trace_execution"], "SANDBOX_TRACE_EXECUTION");
+    str_var(&mut root, &["sandbox", "backend"], "SANDBOX_BACKEND");
+    parsed_var::<bool>(&mut root, &["sandbox", "enable_profiling"], "SANDBOX_ENABLE_PROFILING");
+    parsed_var::<u64>(&mut root, &["sandbox", "profiling_sample_interval"], "SANDBOX_PROFILING_SAMPLE_INTERVAL_MS");
+
+    parsed_var::<u64>(&mut root, &["enforcement", "timeout_config", "max_duration"], "ENFORCEMENT_MAX_DURATION");
+    parsed_var::<u64>(&mut root, &["enforcement", "timeout_config", "warning_threshold"], "ENFORCEMENT_WARNING_THRESHOLD");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "ops", "size"], "RATE_LIMIT_OPS_SIZE");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "ops", "complete_refill_time"], "RATE_LIMIT_OPS_REFILL_SECS");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "ops", "one_time_burst"], "RATE_LIMIT_OPS_BURST");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "tokens", "size"], "RATE_LIMIT_TOKENS_SIZE");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "tokens", "complete_refill_time"], "RATE_LIMIT_TOKENS_REFILL_SECS");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "tokens", "one_time_burst"], "RATE_LIMIT_TOKENS_BURST");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "low", "usage_factor"], "RATE_LIMIT_PRIORITY_LOW_USAGE_FACTOR");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "low", "burst_multiplier"], "RATE_LIMIT_PRIORITY_LOW_BURST_MULTIPLIER");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "normal", "usage_factor"], "RATE_LIMIT_PRIORITY_NORMAL_USAGE_FACTOR");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "normal", "burst_multiplier"], "RATE_LIMIT_PRIORITY_NORMAL_BURST_MULTIPLIER");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "high", "usage_factor"], "RATE_LIMIT_PRIORITY_HIGH_USAGE_FACTOR");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "high", "burst_multiplier"], "RATE_LIMIT_PRIORITY_HIGH_BURST_MULTIPLIER");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "critical", "usage_factor"], "RATE_LIMIT_PRIORITY_CRITICAL_USAGE_FACTOR");
+    parsed_var::<f64>(&mut root, &["enforcement", "rate_limit_config", "priority_factors", "critical", "burst_multiplier"], "RATE_LIMIT_PRIORITY_CRITICAL_BURST_MULTIPLIER");
+    parsed_var::<u64>(&mut root, &["enforcement", "rate_limit_config", "max_blocking_wait"], "RATE_LIMIT_MAX_BLOCKING_WAIT_MS");
+    parsed_var::<u32>(&mut root, &["enforcement", "circuit_breaker_config", "failure_threshold"], "CIRCUIT_BREAKER_FAILURE_THRESHOLD");
+    parsed_var::<u32>(&mut root, &["enforcement", "circuit_breaker_config", "success_threshold"], "CIRCUIT_BREAKER_SUCCESS_THRESHOLD");
+    parsed_var::<u64>(&mut root, &["enforcement", "circuit_breaker_config", "timeout"], "CIRCUIT_BREAKER_TIMEOUT");
+    parsed_var::<u32>(&mut root, &["enforcement", "token_validator_config", "max_tokens"], "TOKEN_VALIDATOR_MAX_TOKENS");
+    parsed_var::<f64>(&mut root, &["enforcement", "token_validator_config", "cost_per_token"], "TOKEN_VALIDATOR_COST_PER_TOKEN");
+    parsed_var::<u64>(&mut root, &["enforcement", "resource_limits_config", "max_memory_mb"], "ENFORCEMENT_MAX_MEMORY_MB");
+    parsed_var::<f32>(&mut root, &["enforcement", "resource_limits_config", "max_cpu_cores"], "ENFORCEMENT_MAX_CPU_CORES");
+    parsed_var::<u32>(&mut root, &["enforcement", "resource_limits_config", "max_network_bandwidth_mbps"], "ENFORCEMENT_MAX_BANDWIDTH_MBPS");
+    parsed_var::<u64>(&mut root, &["enforcement", "resource_limits_config", "max_storage_mb"], "ENFORCEMENT_MAX_STORAGE_MB");
+    parsed_var::<u64>(&mut root, &["enforcement", "gc_sweep_interval"], "ENFORCEMENT_GC_SWEEP_INTERVAL_SECS");
+    parsed_var::<bool>(&mut root, &["enforcement", "ext_authz", "enabled"], "EXT_AUTHZ_ENABLED");
+    str_var(&mut root, &["enforcement", "ext_authz", "listen_addr"], "EXT_AUTHZ_LISTEN_ADDR");
+    parsed_var::<bool>(&mut root, &["enforcement", "ext_authz", "fail_open"], "EXT_AUTHZ_FAIL_OPEN");
+
+    str_var(&mut root, &["security", "opa_policy_path"], "OPA_POLICY_PATH");
+    str_var(&mut root, &["security", "opa_allow_query"], "OPA_ALLOW_QUERY");
+    str_var(&mut root, &["security", "opa_violations_query"], "OPA_VIOLATIONS_QUERY");
+    str_var(&mut root, &["security", "encryption_key_path"], "ENCRYPTION_KEY_PATH");
+    str_var(&mut root, &["security", "encryption_key_id"], "ENCRYPTION_KEY_ID");
+    str_var(&mut root, &["security", "auth_token_secret_path"], "AUTH_TOKEN_SECRET_PATH");
+    str_var(&mut root, &["security", "tls_cert_path"], "TLS_CERT_PATH");
+    str_var(&mut root, &["security", "tls_key_path"], "TLS_KEY_PATH");
+    parsed_var::<bool>(&mut root, &["security", "enable_audit_log"], "ENABLE_AUDIT_LOG");
+    str_var(&mut root, &["security", "audit_log_path"], "AUDIT_LOG_PATH");
+    parsed_var::<u64>(&mut root, &["security", "audit_log_rotate_max_bytes"], "AUDIT_LOG_ROTATE_MAX_BYTES");
+    parsed_var::<u64>(&mut root, &["security", "audit_log_rotate_max_age"], "AUDIT_LOG_ROTATE_MAX_AGE_SECONDS");
+    list_var(&mut root, &["security", "allowed_egress_domains"], "ALLOWED_EGRESS_DOMAINS");
+    list_var(&mut root, &["security", "allowed_egress_cidrs"], "ALLOWED_EGRESS_CIDRS");
+    list_var(&mut root, &["security", "denied_egress_cidrs"], "DENIED_EGRESS_CIDRS");
+    list_var(&mut root, &["security", "allowed_egress_ports"], "ALLOWED_EGRESS_PORTS");
+    str_var(&mut root, &["security", "response_content_security_policy"], "RESPONSE_CSP");
+    str_var(&mut root, &["security", "response_x_frame_options"], "RESPONSE_X_FRAME_OPTIONS");
+    str_var(&mut root, &["security", "response_x_content_type_options"], "RESPONSE_X_CONTENT_TYPE_OPTIONS");
+    str_var(&mut root, &["security", "response_referrer_policy"], "RESPONSE_REFERRER_POLICY");
+    str_var(&mut root, &["security", "response_permissions_policy"], "RESPONSE_PERMISSIONS_POLICY");
+
+    parsed_var::<usize>(&mut root, &["fsm", "max_states"], "FSM_MAX_STATES");
+    parsed_var::<usize>(&mut root, &["fsm", "max_transitions"], "FSM_MAX_TRANSITIONS");
+    parsed_var::<u64>(&mut root, &["fsm", "state_timeout"], "FSM_STATE_TIMEOUT");
+    parsed_var::<bool>(&mut root, &["fsm", "persistence_enabled"], "FSM_PERSISTENCE_ENABLED");
+    str_var(&mut root, &["fsm", "persistence_path"], "FSM_PERSISTENCE_PATH");
+    parsed_var::<u64>(&mut root, &["fsm", "timeout_check_interval"], "FSM_TIMEOUT_CHECK_INTERVAL");
+
+    parsed_var::<bool>(&mut root, &["execution", "result_cache_enabled"], "RESULT_CACHE_ENABLED");
+    parsed_var::<usize>(&mut root, &["execution", "result_cache_capacity"], "RESULT_CACHE_CAPACITY");
+
+    parsed_var::<bool>(&mut root, &["metrics", "enabled"], "METRICS_ENABLED");
+    str_var(&mut root, &["metrics", "addr"], "METRICS_ADDR");
+    str_var(&mut root, &["metrics", "path"], "METRICS_PATH");
+    parsed_var::<u64>(&mut root, &["metrics", "collection_interval"], "METRICS_COLLECTION_INTERVAL");
+    parsed_var::<bool>(&mut root, &["metrics", "otel", "enabled"], "OTEL_EXPORTER_ENABLED");
+    str_var(&mut root, &["metrics", "otel", "collector_endpoint"], "OTEL_COLLECTOR_ENDPOINT");
+    parsed_var::<u64>(&mut root, &["metrics", "otel", "export_interval"], "OTEL_EXPORT_INTERVAL_SECONDS");
+    str_var(&mut root, &["metrics", "otel", "service_name"], "OTEL_SERVICE_NAME");
+    parsed_var::<bool>(&mut root, &["metrics", "pushgateway", "enabled"], "PUSHGATEWAY_ENABLED");
+    str_var(&mut root, &["metrics", "pushgateway", "url"], "PUSHGATEWAY_URL");
+    str_var(&mut root, &["metrics", "pushgateway", "job"], "PUSHGATEWAY_JOB");
+    parsed_var::<u64>(&mut root, &["metrics", "pushgateway", "push_interval"], "PUSHGATEWAY_PUSH_INTERVAL_SECONDS");
+    str_var(&mut root, &["metrics", "pushgateway", "auth_header"], "PUSHGATEWAY_AUTH_HEADER");
+    parsed_var::<usize>(&mut root, &["metrics", "max_label_series"], "METRICS_MAX_LABEL_SERIES");
+
+    str_var(&mut root, &["logging", "level"], "LOG_LEVEL");
+    str_var(&mut root, &["logging", "format"], "LOG_FORMAT");
+    str_var(&mut root, &["logging", "output"], "LOG_OUTPUT");
+
+    str_var(&mut root, &["privilege", "run_as_user"], "RUN_AS_USER");
+    str_var(&mut root, &["privilege", "run_as_group"], "RUN_AS_GROUP");
+    str_var(&mut root, &["privilege", "chroot_dir"], "CHROOT_DIR");
+
+    root
 }
\ No newline at end of file
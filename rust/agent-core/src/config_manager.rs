@@ -0,0 +1,143 @@
+//! Hot-reload wrapper around [`Config`]. Loads the backing file once at
+//! startup, then watches it with `notify` and atomically swaps in a
+//! revalidated [`Config`] behind an `arc_swap::ArcSwap`, so a rejected
+//! (invalid) edit can't disturb the config subsystems are already running
+//! with.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use notify::{RecursiveMode, Watcher};
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+/// Fields that are only read once, at process start, to build a subsystem
+/// (the gRPC/metrics listen sockets, the FSM's persistence directory). A
+/// change to one of these in the watched file is still accepted into the
+/// in-memory snapshot — rejecting the whole reload over it would block
+/// unrelated live fields in the same file — but it is warned about, since
+/// the running subsystem was already constructed with the old value.
+fn warn_on_restart_only_changes(old: &Config, new: &Config) {
+    if old.server.grpc_addr != new.server.grpc_addr {
+        warn!(
+            old = %old.server.grpc_addr,
+            new = %new.server.grpc_addr,
+            "server.grpc_addr changed but is restart-only; restart the process to bind the new address"
+        );
+    }
+    if old.server.metrics_addr != new.server.metrics_addr {
+        warn!(
+            old = %old.server.metrics_addr,
+            new = %new.server.metrics_addr,
+            "server.metrics_addr changed but is restart-only; restart the process to bind the new address"
+        );
+    }
+    if old.server.advertised_grpc_addr != new.server.advertised_grpc_addr {
+        warn!(
+            old = ?old.server.advertised_grpc_addr,
+            new = ?new.server.advertised_grpc_addr,
+            "server.advertised_grpc_addr changed but is restart-only; restart the process to re-resolve it"
+        );
+    }
+    if old.server.advertised_metrics_addr != new.server.advertised_metrics_addr {
+        warn!(
+            old = ?old.server.advertised_metrics_addr,
+            new = ?new.server.advertised_metrics_addr,
+            "server.advertised_metrics_addr changed but is restart-only; restart the process to re-resolve it"
+        );
+    }
+    if old.fsm.persistence_path != new.fsm.persistence_path {
+        warn!(
+            old = ?old.fsm.persistence_path,
+            new = ?new.fsm.persistence_path,
+            "fsm.persistence_path changed but is restart-only; restart the process to use the new path"
+        );
+    }
+}
+
+/// Hot-reloadable handle to the process configuration. Cheap to clone; the
+/// `notify` watcher and its background thread stay alive as long as any
+/// clone does.
+#[derive(Clone)]
+pub struct ConfigManager {
+    path: PathBuf,
+    live: Arc<ArcSwap<Config>>,
+    _watcher: Arc<notify::RecommendedWatcher>,
+}
+
+impl ConfigManager {
+    /// Load `path` once, validate it, and start watching it for changes.
+    /// `on_reload(old, new)` runs after each accepted reload, once the new
+    /// config is already live, so callers can push the relevant section
+    /// down into a reader subsystem (e.g. `EnforcementGateway::reload_config`).
+    /// A reload that fails to parse or fails [`Config::validate`] is logged
+    /// and discarded; the previously-live config is left untouched.
+    pub fn new(
+        path: impl Into<PathBuf>,
+        on_reload: impl Fn(&Config, &Config) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let path = path.into();
+        let initial = load_and_validate(&path)
+            .with_context(|| format!("Failed to load initial configuration from {:?}", path))?;
+        let live = Arc::new(ArcSwap::from_pointee(initial));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(tx).context("Failed to create config file watcher")?;
+        watcher
+            .watch(&path, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch config file {:?}", path))?;
+
+        let watch_path = path.clone();
+        let watch_live = live.clone();
+        std::thread::spawn(move || {
+            for event in rx {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        warn!("Config file watch error: {}", e);
+                        continue;
+                    }
+                };
+                if !(event.kind.is_modify() || event.kind.is_create()) {
+                    continue;
+                }
+
+                match load_and_validate(&watch_path) {
+                    Ok(new_config) => {
+                        let old_config = watch_live.load_full();
+                        warn_on_restart_only_changes(&old_config, &new_config);
+                        watch_live.store(Arc::new(new_config.clone()));
+                        info!("Reloaded configuration from {:?}", watch_path);
+                        on_reload(&old_config, &new_config);
+                    }
+                    Err(e) => {
+                        warn!("Rejected config reload from {:?}: {}", watch_path, e);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { path, live, _watcher: Arc::new(watcher) })
+    }
+
+    /// Current configuration snapshot. Cheap to call repeatedly; the
+    /// returned `Arc` stays valid even if a reload happens concurrently.
+    pub fn current(&self) -> Arc<Config> {
+        self.live.load_full()
+    }
+
+    /// Path backing this manager, for logging.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn load_and_validate(path: &Path) -> Result<Config> {
+    let config = Config::from_file(path)?;
+    config.validate()?;
+    Ok(config)
+}
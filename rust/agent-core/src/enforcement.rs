@@ -1,22 +1,34 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock as StdRwLock};
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
 use crate::config::EnforcementConfig;
+use crate::execution::ResourceAccounting;
 use crate::metrics::MetricsCollector;
 
-/// Enforcement Gateway - Unified policy execution point
+/// Enforcement Gateway - Unified policy execution point. All of the limits
+/// it enforces live behind `std::sync::RwLock` cells inside its components
+/// so [`EnforcementGateway::reload_config`] can swap them in atomically
+/// without restarting the gateway or losing in-flight bucket/circuit state.
 pub struct EnforcementGateway {
-    config: EnforcementConfig,
+    config: StdRwLock<EnforcementConfig>,
     timeout_enforcer: TimeoutEnforcer,
     rate_limiter: RateLimiter,
     circuit_breaker: CircuitBreaker,
     token_validator: TokenValidator,
+    resource_limits: StdRwLock<ResourceLimits>,
     metrics: Arc<MetricsCollector>,
+    gc_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for EnforcementGateway {
+    fn drop(&mut self) {
+        self.gc_handle.abort();
+    }
 }
 
 /// Request to execute a task
@@ -59,8 +71,16 @@ pub enum EnforcementError {
     #[error("Token limit exceeded: {current} > {limit}")]
     TokenLimitExceeded { current: u32, limit: u32 },
     
-    #[error("Rate limit exceeded for key: {0}")]
-    RateLimitExceeded(String),
+    #[error("Rate limit exceeded for key: {key} ({token_type:?} budget exhausted, priority={priority:?} usage_factor={usage_factor}, retry after {retry_after:?})")]
+    RateLimitExceeded {
+        key: String,
+        token_type: TokenType,
+        priority: TaskPriority,
+        usage_factor: f64,
+        /// Computed time until the bucket refills enough to admit this
+        /// request, so callers can back off instead of polling blindly
+        retry_after: Duration,
+    },
     
     #[error("Circuit breaker open for key: {0}")]
     CircuitBreakerOpen(String),
@@ -85,16 +105,43 @@ impl EnforcementGateway {
         let circuit_breaker = CircuitBreaker::new(&config.circuit_breaker_config);
         let token_validator = TokenValidator::new(&config.token_validator_config);
 
+        let gc_handle = spawn_idle_state_gc(
+            rate_limiter.buckets.clone(),
+            circuit_breaker.circuits.clone(),
+            config.gc_sweep_interval,
+            metrics.clone(),
+        );
+
         Ok(Self {
-            config: config.clone(),
+            config: StdRwLock::new(config.clone()),
             timeout_enforcer,
             rate_limiter,
             circuit_breaker,
             token_validator,
+            resource_limits: StdRwLock::new(ResourceLimits::from_config(&config.resource_limits_config)),
             metrics,
+            gc_handle,
         })
     }
 
+    /// Atomically swap in a new set of limits — timeouts, rate-limit bucket
+    /// sizes/refill rates, circuit-breaker thresholds, token limits, and
+    /// resource ceilings — without restarting the gateway. In-flight token
+    /// buckets and circuit state are preserved: a bucket re-clamps its
+    /// `size`/`refill_rate` to the new values on its next `consume` rather
+    /// than being reset, so a config push can't hand out a free burst.
+    /// The GC sweep interval is fixed at startup and is not affected.
+    pub fn reload_config(&self, new: &EnforcementConfig) {
+        info!("Reloading enforcement configuration");
+
+        self.timeout_enforcer.reload(&new.timeout_config);
+        self.rate_limiter.reload(&new.rate_limit_config);
+        self.circuit_breaker.reload(&new.circuit_breaker_config);
+        self.token_validator.reload(&new.token_validator_config);
+        *self.resource_limits.write().unwrap() = ResourceLimits::from_config(&new.resource_limits_config);
+        *self.config.write().unwrap() = new.clone();
+    }
+
     /// Enforce request policies before execution
     pub async fn enforce_request(&self, request: &ExecuteTaskRequest) -> Result<(), EnforcementError> {
         debug!("Enforcing request for task: {}", request.task_id);
@@ -107,7 +154,9 @@ impl EnforcementGateway {
 
         // 3. Rate limiting
         let rate_limit_key = format!("user:{}", request.user_id);
-        self.rate_limiter.check_rate_limit(&rate_limit_key).await?;
+        self.rate_limiter
+            .check_rate_limit(&rate_limit_key, request.estimated_tokens, &request.priority)
+            .await?;
 
         // 4. Circuit breaker check
         let circuit_key = format!("tenant:{}", request.tenant_id);
@@ -123,52 +172,92 @@ impl EnforcementGateway {
         Ok(())
     }
 
-    /// Record execution result for circuit breaker and metrics
+    /// Like [`Self::enforce_request`], but gives well-behaved callers
+    /// automatic smoothing instead of a bare rejection: if the only failure
+    /// is a rate limit whose `retry_after` is within `max_blocking_wait`,
+    /// sleep for that interval and retry exactly once. A shortfall whose
+    /// wait would exceed the cap, or a second rate-limit failure after the
+    /// retry, is still hard-rejected.
+    pub async fn enforce_request_blocking(&self, request: &ExecuteTaskRequest) -> Result<(), EnforcementError> {
+        match self.enforce_request(request).await {
+            Ok(()) => Ok(()),
+            Err(EnforcementError::RateLimitExceeded { retry_after, .. })
+                if retry_after <= self.rate_limiter.max_blocking_wait() =>
+            {
+                debug!("Smoothing rate-limited task {} for {:?}", request.task_id, retry_after);
+                self.metrics.record_rate_limit_wait(retry_after);
+                sleep(retry_after).await;
+
+                let retried = self.enforce_request(request).await;
+                if retried.is_err() {
+                    self.metrics.record_enforcement_failure("rate_limit");
+                }
+                retried
+            }
+            Err(err @ EnforcementError::RateLimitExceeded { .. }) => {
+                self.metrics.record_enforcement_failure("rate_limit");
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Record execution result for circuit breaker and metrics. `accounting`
+    /// carries the full per-resource breakdown behind `tokens_used`/`duration`
+    /// so a future resource-aware circuit breaker or billing pipeline isn't
+    /// limited to the two collapsed scalars.
     pub async fn record_execution_result(
         &self,
         request: &ExecuteTaskRequest,
         success: bool,
         duration: Duration,
         tokens_used: u32,
+        accounting: &ResourceAccounting,
     ) {
         let circuit_key = format!("tenant:{}", request.tenant_id);
-        
+
         if success {
             self.circuit_breaker.record_success(&circuit_key);
-            self.metrics.record_task_success(&request.task_id, duration, tokens_used);
+            self.metrics.record_task_success(&request.task_id, duration, tokens_used, accounting);
         } else {
             self.circuit_breaker.record_failure(&circuit_key);
-            self.metrics.record_task_failure(&request.task_id, duration);
+            self.metrics.record_task_failure(&request.task_id, duration, accounting);
         }
     }
 
-    /// Validate resource requirements
+    /// Validate resource requirements against the current (possibly
+    /// hot-reloaded) resource ceilings
     fn validate_resources(&self, requirements: &ResourceRequirements) -> Result<(), EnforcementError> {
+        let limits = self.resource_limits.read().unwrap().clone();
+
         // Memory validation
-        if requirements.memory_mb > 2048 {
+        if requirements.memory_mb > limits.max_memory_mb {
             return Err(EnforcementError::ResourceLimitExceeded {
-                resource: format!("memory: {}MB > 2048MB", requirements.memory_mb),
+                resource: format!("memory: {}MB > {}MB", requirements.memory_mb, limits.max_memory_mb),
             });
         }
 
         // CPU validation
-        if requirements.cpu_cores > 4.0 {
+        if requirements.cpu_cores > limits.max_cpu_cores {
             return Err(EnforcementError::ResourceLimitExceeded {
-                resource: format!("cpu: {} cores > 4.0 cores", requirements.cpu_cores),
+                resource: format!("cpu: {} cores > {} cores", requirements.cpu_cores, limits.max_cpu_cores),
             });
         }
 
         // Network bandwidth validation
-        if requirements.network_bandwidth_mbps > 100 {
+        if requirements.network_bandwidth_mbps > limits.max_network_bandwidth_mbps {
             return Err(EnforcementError::ResourceLimitExceeded {
-                resource: format!("bandwidth: {}Mbps > 100Mbps", requirements.network_bandwidth_mbps),
+                resource: format!(
+                    "bandwidth: {}Mbps > {}Mbps",
+                    requirements.network_bandwidth_mbps, limits.max_network_bandwidth_mbps
+                ),
             });
         }
 
         // Storage validation
-        if requirements.storage_mb > 1024 {
+        if requirements.storage_mb > limits.max_storage_mb {
             return Err(EnforcementError::ResourceLimitExceeded {
-                resource: format!("storage: {}MB > 1024MB", requirements.storage_mb),
+                resource: format!("storage: {}MB > {}MB", requirements.storage_mb, limits.max_storage_mb),
             });
         }
 
@@ -176,94 +265,377 @@ impl EnforcementGateway {
     }
 }
 
-/// Timeout enforcement component
+/// Hot-reloadable mirror of [`crate::config::ResourceLimitsConfig`]
+#[derive(Debug, Clone, Copy)]
+struct ResourceLimits {
+    max_memory_mb: u64,
+    max_cpu_cores: f32,
+    max_network_bandwidth_mbps: u32,
+    max_storage_mb: u64,
+}
+
+impl ResourceLimits {
+    fn from_config(config: &crate::config::ResourceLimitsConfig) -> Self {
+        Self {
+            max_memory_mb: config.max_memory_mb,
+            max_cpu_cores: config.max_cpu_cores,
+            max_network_bandwidth_mbps: config.max_network_bandwidth_mbps,
+            max_storage_mb: config.max_storage_mb,
+        }
+    }
+}
+
+/// Spawn the background GC task that periodically sweeps idle entries out of
+/// the rate limiter's and circuit breaker's per-key maps, which would
+/// otherwise grow without bound as user/tenant IDs churn over the gateway's
+/// lifetime. Aborted when the owning `EnforcementGateway` is dropped.
+fn spawn_idle_state_gc(
+    buckets: Arc<RwLock<HashMap<String, KeyBuckets>>>,
+    circuits: Arc<Mutex<HashMap<String, CircuitState>>>,
+    sweep_interval: Duration,
+    metrics: Arc<MetricsCollector>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(sweep_interval);
+
+        loop {
+            ticker.tick().await;
+
+            let evicted_buckets = RateLimiter::sweep_idle(&buckets, sweep_interval).await;
+            if evicted_buckets > 0 {
+                debug!("GC swept {} idle rate-limiter bucket(s)", evicted_buckets);
+                metrics.record_gc_eviction("rate_limiter", evicted_buckets as u64);
+            }
+
+            let evicted_circuits = CircuitBreaker::sweep_idle(&circuits, sweep_interval).await;
+            if evicted_circuits > 0 {
+                debug!("GC swept {} idle circuit breaker entr(ies)", evicted_circuits);
+                metrics.record_gc_eviction("circuit_breaker", evicted_circuits as u64);
+            }
+        }
+    })
+}
+
+/// Timeout enforcement component. Limits live behind a lock so they can be
+/// hot-reloaded via [`EnforcementGateway::reload_config`].
 pub struct TimeoutEnforcer {
-    max_duration: Duration,
-    warning_threshold: Duration,
+    max_duration: StdRwLock<Duration>,
+    warning_threshold: StdRwLock<Duration>,
 }
 
 impl TimeoutEnforcer {
     pub fn new(config: &crate::config::TimeoutConfig) -> Self {
         Self {
-            max_duration: config.max_duration,
-            warning_threshold: config.warning_threshold,
+            max_duration: StdRwLock::new(config.max_duration),
+            warning_threshold: StdRwLock::new(config.warning_threshold),
         }
     }
 
     pub fn check_timeout(&self, estimated_duration: Duration) -> Result<(), EnforcementError> {
-        if estimated_duration > self.max_duration {
+        let max_duration = *self.max_duration.read().unwrap();
+        let warning_threshold = *self.warning_threshold.read().unwrap();
+
+        if estimated_duration > max_duration {
             return Err(EnforcementError::TimeoutExceeded(estimated_duration));
         }
 
-        if estimated_duration > self.warning_threshold {
+        if estimated_duration > warning_threshold {
             warn!("Task duration approaching limit: {:?}", estimated_duration);
         }
 
         Ok(())
     }
+
+    fn reload(&self, config: &crate::config::TimeoutConfig) {
+        *self.max_duration.write().unwrap() = config.max_duration;
+        *self.warning_threshold.write().unwrap() = config.warning_threshold;
+    }
+}
+
+/// Which of a key's two independent budgets a rate-limit check draws from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    /// One consumed per `enforce_request` call, regardless of its size
+    Ops,
+    /// `ExecuteTaskRequest::estimated_tokens` consumed per call
+    Tokens,
 }
 
-/// Rate limiting component
+/// Dual token-bucket rate limiter, one bucket per `TokenType` per key, so a
+/// handful of huge-token-estimate requests can't starve everyone else's
+/// request quota and vice versa. Follows the Firecracker/cloud-hypervisor
+/// rate limiter design: each bucket refills at `size / complete_refill_time`
+/// and can additionally front-load an `one_time_burst` that is spent first
+/// and never replenished.
 pub struct RateLimiter {
-    requests_per_second: u32,
-    burst_size: u32,
-    window_size: Duration,
-    buckets: Arc<RwLock<HashMap<String, TokenBucket>>>,
+    ops_template: StdRwLock<TokenBucketTemplate>,
+    tokens_template: StdRwLock<TokenBucketTemplate>,
+    priority_factors: StdRwLock<crate::config::PriorityFactors>,
+    max_blocking_wait: StdRwLock<Duration>,
+    buckets: Arc<RwLock<HashMap<String, KeyBuckets>>>,
 }
 
-/// Token bucket for rate limiting
+struct KeyBuckets {
+    ops: TokenBucket,
+    tokens: TokenBucket,
+}
+
+/// Immutable parameters used to spin up a fresh `TokenBucket` the first
+/// time a key is seen
+#[derive(Debug, Clone, Copy)]
+struct TokenBucketTemplate {
+    size: f64,
+    refill_rate: f64,
+    one_time_burst: f64,
+}
+
+impl TokenBucketTemplate {
+    fn from_config(config: &crate::config::TokenBucketConfig) -> Self {
+        let refill_seconds = config.complete_refill_time.as_secs_f64().max(f64::EPSILON);
+        Self {
+            size: config.size as f64,
+            refill_rate: config.size as f64 / refill_seconds,
+            one_time_burst: config.one_time_burst.unwrap_or(0) as f64,
+        }
+    }
+
+    fn new_bucket(&self) -> TokenBucket {
+        TokenBucket {
+            budget: self.size + self.one_time_burst,
+            size: self.size,
+            refill_rate: self.refill_rate,
+            last_refill: Instant::now(),
+        }
+    }
+}
+
+/// A single token bucket. `budget` can briefly exceed `size` while the
+/// initial `one_time_burst` is still being spent; once it drops back to or
+/// below `size`, only the steady-state `refill_rate` tops it up.
 #[derive(Debug, Clone)]
 struct TokenBucket {
-    tokens: f64,
-    last_refill: Instant,
-    capacity: f64,
+    budget: f64,
+    size: f64,
     refill_rate: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn auto_replenish(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+
+        // Still spending the one-time burst above `size`; steady-state
+        // refill doesn't kick back in until that's exhausted.
+        if self.budget >= self.size {
+            return;
+        }
+
+        self.budget = (self.budget + elapsed * self.refill_rate).min(self.size);
+    }
+
+    /// Re-clamp `size`/`refill_rate` to whatever the caller currently has
+    /// configured before consuming, so a config reload takes effect on a
+    /// bucket's next use instead of requiring it to be reset. If `size`
+    /// shrank, `budget` is pulled down to the new ceiling rather than left
+    /// sitting above it as a free burst.
+    ///
+    /// `burst_allowance` lets the caller momentarily overdraw `budget` below
+    /// zero, up to that amount, borrowing against the next refill instead of
+    /// being rejected outright — used to give higher-priority requests
+    /// headroom to exceed the steady-state rate.
+    ///
+    /// On success returns `Ok(())`. On failure returns `Err(retry_after)`,
+    /// the time until the bucket would have refilled enough (beyond
+    /// `burst_allowance`) to admit this request, per the Firecracker
+    /// rate-limiter "blocked state + wake-up" contract.
+    fn consume(&mut self, amount: f64, size: f64, refill_rate: f64, burst_allowance: f64) -> Result<(), Duration> {
+        let old_size = self.size;
+        self.size = size;
+        self.refill_rate = refill_rate;
+        self.auto_replenish();
+        if self.size < old_size && self.budget > self.size {
+            self.budget = self.size;
+        }
+
+        if self.budget >= amount || self.budget - amount >= -burst_allowance {
+            self.budget -= amount;
+            return Ok(());
+        }
+
+        let shortfall = amount - self.budget - burst_allowance;
+        let retry_after = if refill_rate > 0.0 {
+            Duration::from_secs_f64((shortfall / refill_rate).max(0.0))
+        } else {
+            Duration::MAX
+        };
+        Err(retry_after)
+    }
+
+    /// Undo a previously successful `consume` of `amount`. Used by
+    /// `check_rate_limit` to keep its Ops+Tokens check atomic from the
+    /// caller's point of view: the two buckets can't be consumed in a single
+    /// step, so a later bucket's rejection refunds the earlier one instead of
+    /// leaving the caller permanently charged for a request that was never
+    /// admitted.
+    fn refund(&mut self, amount: f64) {
+        self.budget += amount;
+    }
 }
 
 impl RateLimiter {
     pub async fn new(config: &crate::config::RateLimitConfig) -> Result<Self> {
         Ok(Self {
-            requests_per_second: config.requests_per_second,
-            burst_size: config.burst_size,
-            window_size: config.window_size,
+            ops_template: StdRwLock::new(TokenBucketTemplate::from_config(&config.ops)),
+            tokens_template: StdRwLock::new(TokenBucketTemplate::from_config(&config.tokens)),
+            priority_factors: StdRwLock::new(config.priority_factors.clone()),
+            max_blocking_wait: StdRwLock::new(config.max_blocking_wait),
             buckets: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
-    pub async fn check_rate_limit(&self, key: &str) -> Result<(), EnforcementError> {
+    /// Cap on how long a caller should block-and-retry a rate-limited
+    /// request before giving up, per the current (possibly hot-reloaded)
+    /// config
+    fn max_blocking_wait(&self) -> Duration {
+        *self.max_blocking_wait.read().unwrap()
+    }
+
+    /// Consume `usage_factor` from `key`'s `Ops` bucket and
+    /// `estimated_tokens * usage_factor` from its `Tokens` bucket, where
+    /// `usage_factor` comes from `request.priority` — so low-priority work
+    /// drains the shared buckets faster than high-priority work doing the
+    /// same job, and higher priorities get a `burst_multiplier` worth of
+    /// headroom to momentarily exceed the steady-state rate. Fails with
+    /// whichever budget ran dry first.
+    pub async fn check_rate_limit(
+        &self,
+        key: &str,
+        estimated_tokens: u32,
+        priority: &TaskPriority,
+    ) -> Result<(), EnforcementError> {
+        let ops_template = *self.ops_template.read().unwrap();
+        let tokens_template = *self.tokens_template.read().unwrap();
+        let factor = self.priority_factor(priority);
+
+        let ops_amount = 1.0 * factor.usage_factor;
+        let tokens_amount = estimated_tokens as f64 * factor.usage_factor;
+        let ops_burst = ops_template.size * (factor.burst_multiplier - 1.0).max(0.0);
+        let tokens_burst = tokens_template.size * (factor.burst_multiplier - 1.0).max(0.0);
+
         let mut buckets = self.buckets.write().await;
-        
-        let bucket = buckets.entry(key.to_string()).or_insert_with(|| TokenBucket {
-            tokens: self.burst_size as f64,
-            last_refill: Instant::now(),
-            capacity: self.burst_size as f64,
-            refill_rate: self.requests_per_second as f64,
+
+        let key_buckets = buckets.entry(key.to_string()).or_insert_with(|| KeyBuckets {
+            ops: ops_template.new_bucket(),
+            tokens: tokens_template.new_bucket(),
         });
 
-        // Refill tokens based on elapsed time
-        let now = Instant::now();
-        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
-        let tokens_to_add = elapsed * bucket.refill_rate;
-        
-        bucket.tokens = (bucket.tokens + tokens_to_add).min(bucket.capacity);
-        bucket.last_refill = now;
-
-        // Check if we can consume a token
-        if bucket.tokens >= 1.0 {
-            bucket.tokens -= 1.0;
-            Ok(())
-        } else {
-            Err(EnforcementError::RateLimitExceeded(key.to_string()))
+        if let Err(retry_after) = key_buckets.ops.consume(ops_amount, ops_template.size, ops_template.refill_rate, ops_burst) {
+            return Err(EnforcementError::RateLimitExceeded {
+                key: key.to_string(),
+                token_type: TokenType::Ops,
+                priority: priority.clone(),
+                usage_factor: factor.usage_factor,
+                retry_after,
+            });
+        }
+
+        if let Err(retry_after) =
+            key_buckets.tokens.consume(tokens_amount, tokens_template.size, tokens_template.refill_rate, tokens_burst)
+        {
+            // The Ops bucket was already debited above; since the request as
+            // a whole is being rejected, give that charge back rather than
+            // leaving the caller permanently short one Ops unit for a
+            // request that was never admitted.
+            key_buckets.ops.refund(ops_amount);
+            return Err(EnforcementError::RateLimitExceeded {
+                key: key.to_string(),
+                token_type: TokenType::Tokens,
+                priority: priority.clone(),
+                usage_factor: factor.usage_factor,
+                retry_after,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn priority_factor(&self, priority: &TaskPriority) -> crate::config::PriorityFactor {
+        let factors = self.priority_factors.read().unwrap();
+        match priority {
+            TaskPriority::Low => factors.low,
+            TaskPriority::Normal => factors.normal,
+            TaskPriority::High => factors.high,
+            TaskPriority::Critical => factors.critical,
         }
     }
+
+    /// Swap in new bucket sizes/refill rates. Existing per-key buckets keep
+    /// their current `budget`; they re-clamp to these values the next time
+    /// they're consumed from.
+    fn reload(&self, config: &crate::config::RateLimitConfig) {
+        *self.ops_template.write().unwrap() = TokenBucketTemplate::from_config(&config.ops);
+        *self.tokens_template.write().unwrap() = TokenBucketTemplate::from_config(&config.tokens);
+        *self.priority_factors.write().unwrap() = config.priority_factors.clone();
+        *self.max_blocking_wait.write().unwrap() = config.max_blocking_wait;
+    }
+
+    /// Drop keys whose buckets have sat untouched for at least `stale_after`
+    /// and have refilled all the way back to full `size` on both the `ops`
+    /// and `tokens` dimensions, i.e. have seen no recent traffic. Entries
+    /// touched more recently than `stale_after` are skipped without
+    /// recomputing their refill, so a sweep over a mostly-active map only
+    /// pays for the (hopefully few) idle ones. Returns the number evicted.
+    async fn sweep_idle(buckets: &RwLock<HashMap<String, KeyBuckets>>, stale_after: Duration) -> usize {
+        let mut buckets = buckets.write().await;
+        let now = Instant::now();
+        let mut evicted = 0;
+
+        buckets.retain(|_key, kb| {
+            if now.duration_since(kb.ops.last_refill) < stale_after
+                || now.duration_since(kb.tokens.last_refill) < stale_after
+            {
+                return true;
+            }
+
+            kb.ops.auto_replenish();
+            kb.tokens.auto_replenish();
+
+            let idle = kb.ops.budget >= kb.ops.size && kb.tokens.budget >= kb.tokens.size;
+            if idle {
+                evicted += 1;
+            }
+            !idle
+        });
+
+        evicted
+    }
 }
 
-/// Circuit breaker component
+/// Circuit breaker component. Thresholds live behind a lock so they can be
+/// hot-reloaded via [`EnforcementGateway::reload_config`] without disturbing
+/// already-tracked circuit state.
 pub struct CircuitBreaker {
+    limits: StdRwLock<CircuitLimits>,
+    circuits: Arc<Mutex<HashMap<String, CircuitState>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CircuitLimits {
     failure_threshold: u32,
     success_threshold: u32,
     timeout: Duration,
-    circuits: Arc<Mutex<HashMap<String, CircuitState>>>,
+}
+
+impl CircuitLimits {
+    fn from_config(config: &crate::config::CircuitBreakerConfig) -> Self {
+        Self {
+            failure_threshold: config.failure_threshold,
+            success_threshold: config.success_threshold,
+            timeout: config.timeout,
+        }
+    }
 }
 
 /// Circuit breaker state
@@ -273,6 +645,10 @@ struct CircuitState {
     failure_count: u32,
     success_count: u32,
     last_failure: Option<Instant>,
+    /// Last time this circuit recorded a success or failure, used by the
+    /// idle-state GC sweep to decide whether a closed, failure-free circuit
+    /// has seen no recent traffic and is safe to drop
+    last_updated: Instant,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -285,25 +661,25 @@ enum CircuitStatus {
 impl CircuitBreaker {
     pub fn new(config: &crate::config::CircuitBreakerConfig) -> Self {
         Self {
-            failure_threshold: config.failure_threshold,
-            success_threshold: config.success_threshold,
-            timeout: config.timeout,
+            limits: StdRwLock::new(CircuitLimits::from_config(config)),
             circuits: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     pub fn check_circuit(&self, key: &str) -> Result<(), EnforcementError> {
+        let timeout = self.limits.read().unwrap().timeout;
+
         let circuits = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(self.circuits.lock())
         });
-        
+
         let circuit = circuits.get(key);
-        
+
         match circuit {
             Some(circuit) if circuit.state == CircuitStatus::Open => {
                 // Check if timeout has elapsed
                 if let Some(last_failure) = circuit.last_failure {
-                    if last_failure.elapsed() > self.timeout {
+                    if last_failure.elapsed() > timeout {
                         // Transition to half-open
                         drop(circuits);
                         self.transition_to_half_open(key);
@@ -323,8 +699,8 @@ impl CircuitBreaker {
         tokio::task::spawn({
             let key = key.to_string();
             let circuits = self.circuits.clone();
-            let success_threshold = self.success_threshold;
-            
+            let success_threshold = self.limits.read().unwrap().success_threshold;
+
             async move {
                 let mut circuits = circuits.lock().await;
                 let circuit = circuits.entry(key).or_insert_with(|| CircuitState {
@@ -332,10 +708,12 @@ impl CircuitBreaker {
                     failure_count: 0,
                     success_count: 0,
                     last_failure: None,
+                    last_updated: Instant::now(),
                 });
 
                 circuit.success_count += 1;
                 circuit.failure_count = 0;
+                circuit.last_updated = Instant::now();
 
                 // Transition from half-open to closed if enough successes
                 if circuit.state == CircuitStatus::HalfOpen && circuit.success_count >= success_threshold {
@@ -350,8 +728,8 @@ impl CircuitBreaker {
         tokio::task::spawn({
             let key = key.to_string();
             let circuits = self.circuits.clone();
-            let failure_threshold = self.failure_threshold;
-            
+            let failure_threshold = self.limits.read().unwrap().failure_threshold;
+
             async move {
                 let mut circuits = circuits.lock().await;
                 let circuit = circuits.entry(key).or_insert_with(|| CircuitState {
@@ -359,11 +737,13 @@ impl CircuitBreaker {
                     failure_count: 0,
                     success_count: 0,
                     last_failure: None,
+                    last_updated: Instant::now(),
                 });
 
                 circuit.failure_count += 1;
                 circuit.success_count = 0;
                 circuit.last_failure = Some(Instant::now());
+                circuit.last_updated = Instant::now();
 
                 // Transition to open if failure threshold exceeded
                 if circuit.failure_count >= failure_threshold {
@@ -387,31 +767,76 @@ impl CircuitBreaker {
             }
         });
     }
+
+    /// Drop circuits that are `Closed` with no recorded failures and have
+    /// gone untouched for at least `stale_after` — open or half-open
+    /// circuits, and ones still counting failures, are left alone since
+    /// dropping them would lose state a future check depends on. Returns
+    /// the number evicted.
+    async fn sweep_idle(circuits: &Mutex<HashMap<String, CircuitState>>, stale_after: Duration) -> usize {
+        let mut circuits = circuits.lock().await;
+        let now = Instant::now();
+        let mut evicted = 0;
+
+        circuits.retain(|_key, circuit| {
+            let idle = circuit.state == CircuitStatus::Closed
+                && circuit.failure_count == 0
+                && now.duration_since(circuit.last_updated) >= stale_after;
+            if idle {
+                evicted += 1;
+            }
+            !idle
+        });
+
+        evicted
+    }
+
+    /// Swap in new failure/success thresholds and open-circuit timeout.
+    /// Already-tracked circuits keep their current state and counts.
+    fn reload(&self, config: &crate::config::CircuitBreakerConfig) {
+        *self.limits.write().unwrap() = CircuitLimits::from_config(config);
+    }
 }
 
-/// Token validation component
+/// Token validation component. Limits live behind a lock so they can be
+/// hot-reloaded via [`EnforcementGateway::reload_config`].
 pub struct TokenValidator {
+    limits: StdRwLock<TokenValidatorLimits>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenValidatorLimits {
     max_tokens: u32,
     cost_per_token: f64,
 }
 
-impl TokenValidator {
-    pub fn new(config: &crate::config::TokenValidatorConfig) -> Self {
+impl TokenValidatorLimits {
+    fn from_config(config: &crate::config::TokenValidatorConfig) -> Self {
         Self {
             max_tokens: config.max_tokens,
             cost_per_token: config.cost_per_token,
         }
     }
+}
+
+impl TokenValidator {
+    pub fn new(config: &crate::config::TokenValidatorConfig) -> Self {
+        Self {
+            limits: StdRwLock::new(TokenValidatorLimits::from_config(config)),
+        }
+    }
 
     pub fn validate_tokens(&self, estimated_tokens: u32) -> Result<(), EnforcementError> {
-        if estimated_tokens > self.max_tokens {
+        let limits = *self.limits.read().unwrap();
+
+        if estimated_tokens > limits.max_tokens {
             return Err(EnforcementError::TokenLimitExceeded {
                 current: estimated_tokens,
-                limit: self.max_tokens,
+                limit: limits.max_tokens,
             });
         }
 
-        let estimated_cost = estimated_tokens as f64 * self.cost_per_token;
+        let estimated_cost = estimated_tokens as f64 * limits.cost_per_token;
         if estimated_cost > 10.0 {
             warn!("High token cost estimated: ${:.2}", estimated_cost);
         }
@@ -420,6 +845,10 @@ impl TokenValidator {
     }
 
     pub fn calculate_cost(&self, tokens_used: u32) -> f64 {
-        tokens_used as f64 * self.cost_per_token
+        tokens_used as f64 * self.limits.read().unwrap().cost_per_token
+    }
+
+    fn reload(&self, config: &crate::config::TokenValidatorConfig) {
+        *self.limits.write().unwrap() = TokenValidatorLimits::from_config(config);
     }
 }
\ No newline at end of file
@@ -1,24 +1,37 @@
 use anyhow::{Context, Result};
+use ring::digest;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, watch, Mutex, Semaphore};
+use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::config::ExecutionConfig;
 use crate::enforcement::{EnforcementGateway, ExecuteTaskRequest, TaskPriority, ResourceRequirements};
 use crate::fsm::{StateMachine, StateMachineContext, Event};
 use crate::metrics::MetricsCollector;
-use crate::sandbox::{WASISandbox, ExecutionContext, ExecutionResult, ExecutionStatus};
+use crate::sandbox::{ExecutionContext, ExecutionMetrics, ExecutionResult, ExecutionStatus, SandboxBackend};
 use crate::security::SecurityManager;
 
 /// Main execution engine that coordinates all components
 pub struct ExecutionEngine {
-    sandbox: Arc<WASISandbox>,
+    /// `Arc<dyn SandboxBackend>` rather than a concrete `WASISandbox` so
+    /// tests can swap in a `MockSandbox` without spinning up a real
+    /// Wasmtime engine; production callers still construct this from a real
+    /// `WASISandbox`, unboxed through the trait object at the call site.
+    sandbox: Arc<dyn SandboxBackend>,
     enforcement: Arc<EnforcementGateway>,
     security: Arc<SecurityManager>,
     state_machine: Arc<StateMachine>,
     metrics: Arc<MetricsCollector>,
     active_executions: Arc<Mutex<std::collections::HashMap<String, ActiveExecution>>>,
+    /// Content-addressed cache of completed results, keyed by a hash of the
+    /// request's code/language/environment/allowed_hosts. `None` when
+    /// `ExecutionConfig::result_cache_enabled` is false or capacity is zero.
+    result_cache: Option<Mutex<ResultCache>>,
 }
 
 /// Represents an active execution
@@ -31,17 +44,79 @@ struct ActiveExecution {
     fsm_instance_id: String,
     started_at: Instant,
     status: ExecutionEngineStatus,
+    /// Flips to `true` to signal the sandbox running this execution to
+    /// abort, polled from the WASI epoch-interruption timer task
+    cancel_tx: watch::Sender<bool>,
 }
 
 /// Status of execution in the engine
 #[derive(Debug, Clone, PartialEq)]
-enum ExecutionEngineStatus {
+pub(crate) enum ExecutionEngineStatus {
     Initializing,
     PolicyCheck,
     Executing,
     Validating,
     Completed,
     Failed,
+    /// Driven to completion by a `cancel_execution` call rather than the
+    /// sandbox finishing on its own
+    Aborted,
+}
+
+impl ExecutionEngineStatus {
+    /// Coarse completion fraction for this state, used to give streaming
+    /// callers a progress indicator without instrumenting the sandbox itself
+    pub(crate) fn progress(&self) -> f32 {
+        match self {
+            ExecutionEngineStatus::Initializing => 0.1,
+            ExecutionEngineStatus::PolicyCheck => 0.2,
+            ExecutionEngineStatus::Executing => 0.6,
+            ExecutionEngineStatus::Validating => 0.9,
+            ExecutionEngineStatus::Completed => 1.0,
+            ExecutionEngineStatus::Failed => 1.0,
+            ExecutionEngineStatus::Aborted => 1.0,
+        }
+    }
+}
+
+/// How long `cancel_execution` waits for the owning `execute_with_monitoring`
+/// call to notice a cancellation and remove its own `active_executions`
+/// record before giving up and force-removing it itself
+const ABORT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Outcome of a `cancel_execution` call
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CancelOutcome {
+    /// No matching active execution — it had already finished or never existed
+    NotFound,
+    /// The sandbox acknowledged the abort and tore down within the grace window
+    Aborted,
+    /// The sandbox didn't confirm teardown within `ABORT_GRACE_PERIOD`; the
+    /// active-execution record was force-removed so engine-level bookkeeping
+    /// (active count, `get_status`) doesn't leak the run forever, though the
+    /// guest may still be unwinding in the background
+    ForcedRemoval,
+}
+
+/// Which output stream a chunk of `ExecutionEvent::Output` was captured from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Incremental event emitted while an execution driven by
+/// `execute_agent_code_streaming` is in flight, so callers can surface
+/// progress instead of blocking until `AgentExecutionResult` is ready
+#[derive(Debug, Clone)]
+pub enum ExecutionEvent {
+    /// The engine moved to a new `ExecutionEngineStatus`
+    StateChanged {
+        status: ExecutionEngineStatus,
+        progress: f32,
+    },
+    /// A chunk of sandboxed stdout/stderr became available
+    Output { stream: OutputStream, chunk: String },
 }
 
 /// Request to execute agent code
@@ -57,6 +132,15 @@ pub struct AgentExecutionRequest {
     pub cpu_limit: u64,
     pub environment: std::collections::HashMap<String, String>,
     pub allowed_hosts: Vec<String>,
+    /// Opt-out for nondeterministic code (e.g. code that reads the clock or
+    /// makes network calls with side effects): when `false`, this request is
+    /// never served from or stored in the result cache
+    pub cacheable: bool,
+    /// Forwarded into `ExecuteTaskRequest::priority` so
+    /// `EnforcementGateway::check_rate_limit`'s priority-scaled usage factors
+    /// are actually reachable from a real caller instead of always being
+    /// `TaskPriority::Normal`.
+    pub priority: TaskPriority,
 }
 
 /// Supported code languages
@@ -79,19 +163,129 @@ pub struct AgentExecutionResult {
     pub cost_usd: f64,
     pub security_violations: Vec<String>,
     pub fsm_result: Option<crate::fsm::StateMachineResult>,
+    /// `true` if this result was served from the result cache instead of a
+    /// fresh sandbox run
+    pub cached: bool,
+    /// Per-resource breakdown of what the sandbox actually consumed. `None`
+    /// for results that never reached the sandbox (security/policy
+    /// rejections, result-cache hits) — there's nothing to account for.
+    pub resource_accounting: Option<ResourceAccounting>,
+}
+
+/// Structured resource-consumption report for a single execution, broken out
+/// by dimension instead of collapsed into `tokens_used`/`cost_usd`, so
+/// enforcement and billing can tell a CPU-bound run from a memory-bound or
+/// network-heavy one. Populated from `ExecutionResult::metrics` once the
+/// sandbox run finishes.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceAccounting {
+    pub peak_memory_bytes: u64,
+    pub cpu_time: Duration,
+    /// Execution units consumed against the injected gas meter; `0` for the
+    /// Python/JS interpreter paths, which aren't instrumented. See
+    /// `ExecutionMetrics::gas_consumed`.
+    pub execution_units: u64,
+    pub syscalls_count: u64,
+    pub network_bytes_sent: u64,
+    pub network_bytes_received: u64,
+    /// Total time the request spent in the sandbox, including scheduling
+    /// delay not reflected in `cpu_time`
+    pub wall_time: Duration,
+}
+
+impl ResourceAccounting {
+    fn from_metrics(metrics: &ExecutionMetrics, wall_time: Duration) -> Self {
+        Self {
+            peak_memory_bytes: metrics.memory_used,
+            cpu_time: metrics.cpu_time,
+            execution_units: metrics.gas_consumed,
+            syscalls_count: metrics.syscalls_count,
+            network_bytes_sent: metrics.network_bytes_sent,
+            network_bytes_received: metrics.network_bytes_received,
+            wall_time,
+        }
+    }
+
+    /// Flatten into the string-keyed payload shape `fsm::Event` carries, so
+    /// the `success`/`error` transitions recording this run expose the full
+    /// breakdown to downstream consumers instead of a single scalar
+    fn to_event_payload(&self) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([
+            ("resource_peak_memory_bytes".to_string(), self.peak_memory_bytes.to_string()),
+            ("resource_cpu_time_ms".to_string(), self.cpu_time.as_millis().to_string()),
+            ("resource_execution_units".to_string(), self.execution_units.to_string()),
+            ("resource_syscalls_count".to_string(), self.syscalls_count.to_string()),
+            ("resource_network_bytes_sent".to_string(), self.network_bytes_sent.to_string()),
+            ("resource_network_bytes_received".to_string(), self.network_bytes_received.to_string()),
+            ("resource_wall_time_ms".to_string(), self.wall_time.as_millis().to_string()),
+        ])
+    }
+}
+
+/// Governs how `execute_agent_batch` reacts once one request in the batch
+/// fails or the batch's cumulative cost crosses a ceiling. Requests already
+/// finished are left untouched either way; only requests still running or
+/// still queued behind the concurrency limit are affected.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchPolicy {
+    /// Cancel every other outstanding request as soon as one finishes with
+    /// anything other than `ExecutionStatus::Success`
+    pub fail_fast: bool,
+    /// Cancel every other outstanding request once the batch's running
+    /// `cost_usd` total reaches this value
+    pub cost_ceiling_usd: Option<f64>,
+}
+
+/// Aggregate outcome of `execute_agent_batch`
+#[derive(Debug, Clone)]
+pub struct BatchExecutionResult {
+    /// One result per submitted request, in the order the requests were
+    /// submitted; a request whose task panicked has no entry
+    pub results: Vec<AgentExecutionResult>,
+    pub total_tokens: u64,
+    pub total_cost_usd: f64,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Requests cut short by `BatchPolicy`, whether already running or still
+    /// queued behind `max_concurrency`
+    pub cancelled: usize,
+    pub wall_clock: Duration,
+}
+
+/// Unified submission accepted by `ExecutionEngine::submit`, so a caller can
+/// route either a single request or a whole batch through one entrypoint
+/// instead of special-casing the two shapes
+pub enum ExecutionSubmission {
+    Single(AgentExecutionRequest),
+    Batch {
+        requests: Vec<AgentExecutionRequest>,
+        max_concurrency: usize,
+        policy: BatchPolicy,
+    },
+}
+
+/// Result of an `ExecutionSubmission`, mirroring its shape
+pub enum SubmissionResult {
+    Single(AgentExecutionResult),
+    Batch(BatchExecutionResult),
 }
 
 impl ExecutionEngine {
     /// Create a new execution engine
     pub fn new(
-        sandbox: Arc<WASISandbox>,
+        sandbox: Arc<dyn SandboxBackend>,
         enforcement: Arc<EnforcementGateway>,
         security: Arc<SecurityManager>,
         state_machine: Arc<StateMachine>,
         metrics: Arc<MetricsCollector>,
+        execution_config: &ExecutionConfig,
     ) -> Result<Self> {
         info!("Initializing execution engine");
 
+        let result_cache = (execution_config.result_cache_enabled
+            && execution_config.result_cache_capacity > 0)
+            .then(|| Mutex::new(ResultCache::new(execution_config.result_cache_capacity)));
+
         Ok(Self {
             sandbox,
             enforcement,
@@ -99,6 +293,7 @@ impl ExecutionEngine {
             state_machine,
             metrics,
             active_executions: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            result_cache,
         })
     }
 
@@ -108,11 +303,39 @@ impl ExecutionEngine {
         request: AgentExecutionRequest,
     ) -> Result<AgentExecutionResult> {
         let execution_id = Uuid::new_v4().to_string();
-        let start_time = Instant::now();
-        
         info!("Starting agent execution: {}", execution_id);
+        self.run_tracked(execution_id, request, None).await
+    }
+
+    /// Execute agent code exactly like `execute_agent_code`, but additionally
+    /// publish `ExecutionEvent`s on `events` as the run progresses, so a
+    /// streaming RPC caller can surface state transitions and output chunks
+    /// before the final `AgentExecutionResult` is available
+    pub async fn execute_agent_code_streaming(
+        &self,
+        request: AgentExecutionRequest,
+        events: mpsc::Sender<ExecutionEvent>,
+    ) -> Result<AgentExecutionResult> {
+        let execution_id = Uuid::new_v4().to_string();
+        info!("Starting streamed agent execution: {}", execution_id);
+        self.run_tracked(execution_id, request, Some(&events)).await
+    }
+
+    /// Register `execution_id` in `active_executions`, drive it through
+    /// `execute_with_monitoring`, then remove the bookkeeping record
+    /// regardless of outcome. Shared by the single-shot entrypoints above
+    /// and by each task `execute_agent_batch` schedules, so every execution
+    /// — solo or batched — is cancellable and visible to `get_status`/
+    /// `get_active_executions` the same way.
+    async fn run_tracked(
+        &self,
+        execution_id: String,
+        request: AgentExecutionRequest,
+        events: Option<&mpsc::Sender<ExecutionEvent>>,
+    ) -> Result<AgentExecutionResult> {
+        let start_time = Instant::now();
+        let (cancel_tx, cancel_rx) = watch::channel(false);
 
-        // Create active execution record
         let active_execution = ActiveExecution {
             execution_id: execution_id.clone(),
             user_id: request.user_id.clone(),
@@ -121,6 +344,7 @@ impl ExecutionEngine {
             fsm_instance_id: String::new(), // Will be set later
             started_at: start_time,
             status: ExecutionEngineStatus::Initializing,
+            cancel_tx,
         };
 
         {
@@ -128,10 +352,8 @@ impl ExecutionEngine {
             executions.insert(execution_id.clone(), active_execution);
         }
 
-        // Execute with comprehensive error handling
-        let result = self.execute_with_monitoring(&execution_id, request).await;
-        
-        // Clean up active execution
+        let result = self.execute_with_monitoring(&execution_id, request, events, cancel_rx).await;
+
         {
             let mut executions = self.active_executions.lock().await;
             executions.remove(&execution_id);
@@ -140,16 +362,166 @@ impl ExecutionEngine {
         result
     }
 
-    /// Execute with full monitoring and state management
+    /// Schedule `requests` through a `Semaphore`-bounded pool of at most
+    /// `max_concurrency` concurrent executions, so a multi-agent caller can
+    /// fan out a batch of work without managing its own task scheduling.
+    /// Each request is tracked exactly like a solo `execute_agent_code` call
+    /// — including participating in the result cache and being cancellable
+    /// by execution id — so `policy.fail_fast`/`policy.cost_ceiling_usd` can
+    /// cancel the rest of the batch (via the same cancellation subsystem
+    /// used by `cancel_execution`) as soon as the condition is met. Requests
+    /// still queued behind the semaphore when that happens are skipped
+    /// before they ever start.
+    pub async fn execute_agent_batch(
+        self: &Arc<Self>,
+        requests: Vec<AgentExecutionRequest>,
+        max_concurrency: usize,
+        policy: BatchPolicy,
+    ) -> BatchExecutionResult {
+        let start_time = Instant::now();
+        let execution_ids: Vec<String> = requests.iter().map(|_| Uuid::new_v4().to_string()).collect();
+        let semaphore = Arc::new(Semaphore::new(max_concurrency.max(1)));
+        let aborted = Arc::new(AtomicBool::new(false));
+        let spent_cost = Arc::new(std::sync::Mutex::new(0.0f64));
+
+        info!(
+            "Starting agent batch of {} requests (max_concurrency={})",
+            requests.len(), max_concurrency
+        );
+
+        let mut join_set = tokio::task::JoinSet::new();
+        for (execution_id, request) in execution_ids.iter().cloned().zip(requests) {
+            let engine = self.clone();
+            let permit = semaphore.clone();
+            let aborted = aborted.clone();
+            let spent_cost = spent_cost.clone();
+            let batch_ids = execution_ids.clone();
+
+            join_set.spawn(async move {
+                let _permit = permit.acquire_owned().await.expect("batch semaphore never closed");
+
+                if aborted.load(Ordering::SeqCst) {
+                    return AgentExecutionResult {
+                        execution_id: execution_id.clone(),
+                        status: ExecutionStatus::Cancelled,
+                        output: String::new(),
+                        error_message: Some("Batch aborted before this request started".to_string()),
+                        execution_time: Duration::from_secs(0),
+                        tokens_used: 0,
+                        cost_usd: 0.0,
+                        security_violations: Vec::new(),
+                        fsm_result: None,
+                        cached: false,
+                        resource_accounting: None,
+                    };
+                }
+
+                let result = match engine.run_tracked(execution_id.clone(), request, None).await {
+                    Ok(result) => result,
+                    Err(e) => AgentExecutionResult {
+                        execution_id: execution_id.clone(),
+                        status: ExecutionStatus::RuntimeError,
+                        output: String::new(),
+                        error_message: Some(e.to_string()),
+                        execution_time: Duration::from_secs(0),
+                        tokens_used: 0,
+                        cost_usd: 0.0,
+                        security_violations: Vec::new(),
+                        fsm_result: None,
+                        cached: false,
+                        resource_accounting: None,
+                    },
+                };
+
+                let should_abort = {
+                    let mut spent = spent_cost.lock().unwrap();
+                    *spent += result.cost_usd;
+                    (policy.fail_fast && result.status != ExecutionStatus::Success)
+                        || policy.cost_ceiling_usd.is_some_and(|ceiling| *spent >= ceiling)
+                };
+
+                // Only the task that actually trips the policy cancels the
+                // rest of the batch, so a flood of simultaneous failures
+                // doesn't redundantly call cancel_execution for every peer
+                if should_abort && !aborted.swap(true, Ordering::SeqCst) {
+                    for other_id in &batch_ids {
+                        if *other_id != execution_id {
+                            engine.cancel_execution(other_id).await;
+                        }
+                    }
+                }
+
+                result
+            });
+        }
+
+        let mut results_by_id = std::collections::HashMap::with_capacity(execution_ids.len());
+        while let Some(joined) = join_set.join_next().await {
+            if let Ok(result) = joined {
+                results_by_id.insert(result.execution_id.clone(), result);
+            }
+        }
+
+        let mut summary = BatchExecutionResult {
+            results: Vec::with_capacity(execution_ids.len()),
+            total_tokens: 0,
+            total_cost_usd: 0.0,
+            succeeded: 0,
+            failed: 0,
+            cancelled: 0,
+            wall_clock: start_time.elapsed(),
+        };
+
+        for execution_id in &execution_ids {
+            let Some(result) = results_by_id.remove(execution_id) else {
+                continue;
+            };
+            summary.total_tokens += result.tokens_used as u64;
+            summary.total_cost_usd += result.cost_usd;
+            match result.status {
+                ExecutionStatus::Success => summary.succeeded += 1,
+                ExecutionStatus::Cancelled => summary.cancelled += 1,
+                _ => summary.failed += 1,
+            }
+            summary.results.push(result);
+        }
+
+        summary
+    }
+
+    /// Route a single request or a whole batch through one entrypoint, so
+    /// callers (e.g. a future gRPC submission RPC) don't need to special-case
+    /// either shape
+    pub async fn submit(
+        self: &Arc<Self>,
+        submission: ExecutionSubmission,
+    ) -> Result<SubmissionResult> {
+        match submission {
+            ExecutionSubmission::Single(request) => {
+                self.execute_agent_code(request).await.map(SubmissionResult::Single)
+            }
+            ExecutionSubmission::Batch { requests, max_concurrency, policy } => {
+                Ok(SubmissionResult::Batch(
+                    self.execute_agent_batch(requests, max_concurrency, policy).await,
+                ))
+            }
+        }
+    }
+
+    /// Execute with full monitoring and state management. `events`, when
+    /// present, receives an `ExecutionEvent` for every state transition and
+    /// for the sandboxed output once it becomes available
     async fn execute_with_monitoring(
         &self,
         execution_id: &str,
         request: AgentExecutionRequest,
+        events: Option<&mpsc::Sender<ExecutionEvent>>,
+        cancel_rx: watch::Receiver<bool>,
     ) -> Result<AgentExecutionResult> {
         let start_time = Instant::now();
 
         // Step 1: Security validation
-        self.update_execution_status(execution_id, ExecutionEngineStatus::PolicyCheck).await;
+        self.update_execution_status(execution_id, ExecutionEngineStatus::PolicyCheck, events).await;
         
         let security_result = self.security.validate_code(&request.code, &request.user_id).await?;
         if !security_result.is_safe {
@@ -163,9 +535,21 @@ impl ExecutionEngine {
                 cost_usd: 0.0,
                 security_violations: security_result.violations,
                 fsm_result: None,
+                cached: false,
+                resource_accounting: None,
             });
         }
 
+        // Look up the result cache before paying for enforcement/sandbox.
+        // `enforce_request` still runs below even on a hit, so cached
+        // executions keep charging quota/cost like a real one.
+        let cache_key = request.cacheable.then(|| Self::cache_key(&request));
+        let cached_result = match &cache_key {
+            Some(key) if self.result_cache.is_some() => self.cache_get(key).await,
+            _ => None,
+        };
+        self.metrics.record_cache_lookup(cached_result.is_some());
+
         // Step 2: Create enforcement request
         let enforcement_request = ExecuteTaskRequest {
             user_id: request.user_id.clone(),
@@ -174,7 +558,7 @@ impl ExecutionEngine {
             task_id: execution_id.to_string(),
             estimated_duration: request.timeout,
             estimated_tokens: self.estimate_tokens(&request.code),
-            priority: TaskPriority::Normal,
+            priority: request.priority.clone(),
             resource_requirements: ResourceRequirements {
                 memory_mb: request.memory_limit / 1024 / 1024,
                 cpu_cores: 1.0,
@@ -195,6 +579,27 @@ impl ExecutionEngine {
                 cost_usd: 0.0,
                 security_violations: vec![format!("Policy violation: {}", e)],
                 fsm_result: None,
+                cached: false,
+                resource_accounting: None,
+            });
+        }
+
+        // On a cache hit, quota was just charged above but the sandbox run
+        // and FSM tracking it exists to observe are skipped entirely
+        if let Some(cached) = cached_result {
+            self.update_execution_status(execution_id, ExecutionEngineStatus::Completed, events).await;
+            return Ok(AgentExecutionResult {
+                execution_id: execution_id.to_string(),
+                status: cached.status,
+                output: cached.output,
+                error_message: cached.error_message,
+                execution_time: start_time.elapsed(),
+                tokens_used: cached.tokens_used,
+                cost_usd: cached.cost_usd,
+                security_violations: security_result.violations,
+                fsm_result: None,
+                cached: true,
+                resource_accounting: None,
             });
         }
 
@@ -221,7 +626,7 @@ impl ExecutionEngine {
         }
 
         // Step 5: Execute code in sandbox
-        self.update_execution_status(execution_id, ExecutionEngineStatus::Executing).await;
+        self.update_execution_status(execution_id, ExecutionEngineStatus::Executing, events).await;
         
         // Trigger FSM transition to analyzing state
         let analyzing_event = Event {
@@ -243,6 +648,12 @@ impl ExecutionEngine {
             timeout: request.timeout,
             allowed_hosts: request.allowed_hosts,
             environment: request.environment,
+            language: match &request.language {
+                CodeLanguage::Python => "python".to_string(),
+                CodeLanguage::JavaScript => "javascript".to_string(),
+                CodeLanguage::WebAssembly => "webassembly".to_string(),
+            },
+            cancel: cancel_rx,
         };
 
         // Execute based on language
@@ -254,47 +665,76 @@ impl ExecutionEngine {
                 self.sandbox.execute_javascript(&request.code, execution_context).await?
             }
             CodeLanguage::WebAssembly => {
-                // Would implement WASM execution
-                return Err(anyhow::anyhow!("WebAssembly execution not yet implemented"));
+                let wasm_bytes = base64::decode(&request.code)
+                    .context("WebAssembly code must be base64-encoded module bytes")?;
+                self.sandbox.execute_wasm(&wasm_bytes, execution_context).await?
             }
         };
 
+        // Sandboxed output is only available once the process has finished,
+        // so stream it as output chunks now rather than incrementally
+        if let Some(tx) = events {
+            let stream = if sandbox_result.status == ExecutionStatus::Success {
+                OutputStream::Stdout
+            } else {
+                OutputStream::Stderr
+            };
+            for line in sandbox_result.output.lines() {
+                let _ = tx
+                    .send(ExecutionEvent::Output {
+                        stream,
+                        chunk: line.to_string(),
+                    })
+                    .await;
+            }
+        }
+
         // Step 6: Validate results
-        self.update_execution_status(execution_id, ExecutionEngineStatus::Validating).await;
-        
+        self.update_execution_status(execution_id, ExecutionEngineStatus::Validating, events).await;
+
+        let resource_accounting = ResourceAccounting::from_metrics(&sandbox_result.metrics, sandbox_result.duration);
+
         // Trigger FSM events based on execution result
         let result_event = if sandbox_result.status == ExecutionStatus::Success {
+            let mut payload = resource_accounting.to_event_payload();
+            payload.insert("output_length".to_string(), sandbox_result.output.len().to_string());
             Event {
                 id: Uuid::new_v4().to_string(),
                 event_type: "success".to_string(),
-                payload: std::collections::HashMap::from([
-                    ("output_length".to_string(), sandbox_result.output.len().to_string()),
-                ]),
+                payload,
+                timestamp: chrono::Utc::now(),
+            }
+        } else if sandbox_result.status == ExecutionStatus::Cancelled {
+            Event {
+                id: Uuid::new_v4().to_string(),
+                event_type: "abort".to_string(),
+                payload: std::collections::HashMap::new(),
                 timestamp: chrono::Utc::now(),
             }
         } else {
+            let mut payload = resource_accounting.to_event_payload();
+            payload.insert("error".to_string(), sandbox_result.error_message.clone().unwrap_or_default());
             Event {
                 id: Uuid::new_v4().to_string(),
                 event_type: "error".to_string(),
-                payload: std::collections::HashMap::from([
-                    ("error".to_string(), sandbox_result.error_message.clone().unwrap_or_default()),
-                ]),
+                payload,
                 timestamp: chrono::Utc::now(),
             }
         };
-        
+
         self.state_machine.trigger_event(&fsm_instance_id, result_event).await?;
 
         // Step 7: Record execution metrics
         let execution_success = sandbox_result.status == ExecutionStatus::Success;
         let tokens_used = self.calculate_actual_tokens(&sandbox_result);
-        let cost_usd = self.calculate_cost(tokens_used);
+        let cost_usd = self.calculate_cost(&resource_accounting);
 
         self.enforcement.record_execution_result(
             &enforcement_request,
             execution_success,
             sandbox_result.duration,
             tokens_used,
+            &resource_accounting,
         ).await;
 
         // Step 8: Complete FSM instance
@@ -303,10 +743,12 @@ impl ExecutionEngine {
         // Step 9: Final status update
         let final_status = if execution_success {
             ExecutionEngineStatus::Completed
+        } else if sandbox_result.status == ExecutionStatus::Cancelled {
+            ExecutionEngineStatus::Aborted
         } else {
             ExecutionEngineStatus::Failed
         };
-        self.update_execution_status(execution_id, final_status).await;
+        self.update_execution_status(execution_id, final_status, events).await;
 
         // Record final metrics
         self.metrics.record_agent_execution(
@@ -317,6 +759,22 @@ impl ExecutionEngine {
             execution_success,
         );
 
+        if let Some(key) = cache_key {
+            if execution_success {
+                self.cache_insert(
+                    key,
+                    CachedResult {
+                        status: sandbox_result.status,
+                        output: sandbox_result.output.clone(),
+                        error_message: sandbox_result.error_message.clone(),
+                        tokens_used,
+                        cost_usd,
+                    },
+                )
+                .await;
+            }
+        }
+
         Ok(AgentExecutionResult {
             execution_id: execution_id.to_string(),
             status: sandbox_result.status,
@@ -327,16 +785,31 @@ impl ExecutionEngine {
             cost_usd,
             security_violations: security_result.violations,
             fsm_result: Some(fsm_result),
+            cached: false,
+            resource_accounting: Some(resource_accounting),
         })
     }
 
-    /// Update execution status
-    async fn update_execution_status(&self, execution_id: &str, status: ExecutionEngineStatus) {
+    /// Update execution status, notifying `events` subscribers of the
+    /// transition when streaming is in use
+    async fn update_execution_status(
+        &self,
+        execution_id: &str,
+        status: ExecutionEngineStatus,
+        events: Option<&mpsc::Sender<ExecutionEvent>>,
+    ) {
+        let progress = status.progress();
+
         let mut executions = self.active_executions.lock().await;
         if let Some(execution) = executions.get_mut(execution_id) {
-            execution.status = status;
+            execution.status = status.clone();
             debug!("Execution {} status updated to {:?}", execution_id, execution.status);
         }
+        drop(executions);
+
+        if let Some(tx) = events {
+            let _ = tx.send(ExecutionEvent::StateChanged { status, progress }).await;
+        }
     }
 
     /// Estimate tokens for code execution
@@ -350,18 +823,94 @@ impl ExecutionEngine {
 
     /// Calculate actual tokens used based on execution result
     fn calculate_actual_tokens(&self, result: &ExecutionResult) -> u32 {
-        // Calculate based on execution metrics and output
         let base_tokens = 50;
+
+        // WASM executions carry real, instrumented execution-unit counts;
+        // use those instead of the output/CPU-time heuristic below, which
+        // only approximates cost for the Python/JS interpreter paths.
+        if result.metrics.gas_consumed > 0 {
+            return base_tokens + result.metrics.gas_consumed as u32;
+        }
+
         let output_tokens = result.output.len() as u32 / 4;
         let cpu_tokens = (result.metrics.cpu_time.as_millis() / 100) as u32;
-        
+
         base_tokens + output_tokens + cpu_tokens
     }
 
-    /// Calculate cost in USD
-    fn calculate_cost(&self, tokens_used: u32) -> f64 {
-        // Simple cost calculation - would be more sophisticated in production
-        tokens_used as f64 * 0.002 // $0.002 per token
+    /// Calculate cost in USD as a weighted combination of everything
+    /// `ResourceAccounting` tracks, rather than token count alone, so a
+    /// memory-heavy or network-heavy run is billed for what it actually
+    /// consumed instead of being priced the same as a CPU-only one of equal
+    /// output size.
+    ///
+    /// `syscall_cost`/`network_cost` are carried for when `sandbox.rs` wires
+    /// up real syscall/egress metering (both metrics are hard-coded `0` on
+    /// every path today, same as `ExecutionMetrics::network_requests`), so
+    /// they're inert for now rather than dead code. `BASE_EXECUTION_COST` is
+    /// a flat floor so a run that only exercises those not-yet-metered
+    /// dimensions — interpreted Python/JS, which also never sets
+    /// `execution_units` — still carries a non-negligible cost; without it,
+    /// `BatchPolicy::cost_ceiling_usd` would never trip against that class of
+    /// request.
+    fn calculate_cost(&self, accounting: &ResourceAccounting) -> f64 {
+        const BASE_EXECUTION_COST: f64 = 0.01; // $/execution flat overhead
+        const CPU_SECOND_COST: f64 = 0.05; // $/vCPU-second
+        const MEMORY_GB_SECOND_COST: f64 = 0.0000017; // $/GB-second of peak memory held for the run's wall time
+        const EXECUTION_UNIT_COST: f64 = 0.000001; // $/instrumented gas unit
+        const SYSCALL_COST: f64 = 0.0000005; // $/syscall
+        const NETWORK_MB_COST: f64 = 0.00002; // $/MB sent or received
+
+        let cpu_cost = accounting.cpu_time.as_secs_f64() * CPU_SECOND_COST;
+        let memory_gb = accounting.peak_memory_bytes as f64 / 1_073_741_824.0;
+        let memory_cost = memory_gb * accounting.wall_time.as_secs_f64() * MEMORY_GB_SECOND_COST;
+        let execution_unit_cost = accounting.execution_units as f64 * EXECUTION_UNIT_COST;
+        let syscall_cost = accounting.syscalls_count as f64 * SYSCALL_COST;
+        let network_mb = (accounting.network_bytes_sent + accounting.network_bytes_received) as f64 / 1_048_576.0;
+        let network_cost = network_mb * NETWORK_MB_COST;
+
+        BASE_EXECUTION_COST + cpu_cost + memory_cost + execution_unit_cost + syscall_cost + network_cost
+    }
+
+    /// Hex-encoded SHA-256 over the code, language, sorted environment
+    /// entries, and allowed hosts, so two requests that differ only in
+    /// field order still map to the same cache entry
+    fn cache_key(request: &AgentExecutionRequest) -> String {
+        let mut env_entries: Vec<(&str, &str)> = request
+            .environment
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        env_entries.sort_unstable();
+
+        let mut ctx = digest::Context::new(&digest::SHA256);
+        ctx.update(request.code.as_bytes());
+        ctx.update(format!("{:?}", request.language).as_bytes());
+        for (key, value) in env_entries {
+            ctx.update(key.as_bytes());
+            ctx.update(b"=");
+            ctx.update(value.as_bytes());
+            ctx.update(b"\0");
+        }
+        for host in &request.allowed_hosts {
+            ctx.update(host.as_bytes());
+            ctx.update(b"\0");
+        }
+
+        ctx.finish().as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Look up `key` in the result cache, if one is configured
+    async fn cache_get(&self, key: &str) -> Option<CachedResult> {
+        let cache = self.result_cache.as_ref()?;
+        cache.lock().await.get(key)
+    }
+
+    /// Insert a completed result into the result cache, if one is configured
+    async fn cache_insert(&self, key: String, result: CachedResult) {
+        if let Some(cache) = &self.result_cache {
+            cache.lock().await.insert(key, result);
+        }
     }
 
     /// Get active executions
@@ -370,6 +919,65 @@ impl ExecutionEngine {
         executions.values().cloned().collect()
     }
 
+    /// Signal an in-flight execution's sandbox to abort via its `cancel_tx`,
+    /// drive its FSM instance to the `abort` transition, and wait up to
+    /// `ABORT_GRACE_PERIOD` for `execute_with_monitoring` to notice and
+    /// remove its own `active_executions` record. If the sandbox hasn't
+    /// confirmed teardown by then, the record is force-removed here instead
+    /// so a stuck sandbox can't wedge the engine's bookkeeping (or a caller
+    /// polling `get_active_executions`) forever.
+    pub async fn cancel_execution(&self, execution_id: &str) -> CancelOutcome {
+        let fsm_instance_id = {
+            let executions = self.active_executions.lock().await;
+            match executions.get(execution_id) {
+                Some(execution) => {
+                    let _ = execution.cancel_tx.send(true);
+                    Some(execution.fsm_instance_id.clone())
+                }
+                None => None,
+            }
+        };
+
+        let Some(fsm_instance_id) = fsm_instance_id else {
+            return CancelOutcome::NotFound;
+        };
+
+        if !fsm_instance_id.is_empty() {
+            let abort_event = Event {
+                id: Uuid::new_v4().to_string(),
+                event_type: "abort".to_string(),
+                payload: std::collections::HashMap::new(),
+                timestamp: chrono::Utc::now(),
+            };
+            if let Err(e) = self.state_machine.trigger_event(&fsm_instance_id, abort_event).await {
+                warn!("Failed to trigger abort FSM event for execution {}: {}", execution_id, e);
+            }
+        }
+
+        let deadline = Instant::now() + ABORT_GRACE_PERIOD;
+        while Instant::now() < deadline {
+            {
+                let executions = self.active_executions.lock().await;
+                if !executions.contains_key(execution_id) {
+                    return CancelOutcome::Aborted;
+                }
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+
+        let mut executions = self.active_executions.lock().await;
+        if executions.remove(execution_id).is_some() {
+            warn!(
+                "Execution {} did not confirm teardown within the {:?} abort grace period; forcing removal",
+                execution_id, ABORT_GRACE_PERIOD
+            );
+            self.metrics.record_forced_abort();
+            CancelOutcome::ForcedRemoval
+        } else {
+            CancelOutcome::Aborted
+        }
+    }
+
     /// Get execution statistics
     pub async fn get_execution_stats(&self) -> ExecutionStats {
         let executions = self.active_executions.lock().await;
@@ -390,4 +998,349 @@ pub struct ExecutionStats {
     pub total_executions: u64,
     pub success_rate: f64,
     pub average_duration: Duration,
-}
\ No newline at end of file
+}
+
+/// The parts of an `AgentExecutionResult` that are reusable across requests
+/// with the same cache key
+#[derive(Debug, Clone)]
+struct CachedResult {
+    status: ExecutionStatus,
+    output: String,
+    error_message: Option<String>,
+    tokens_used: u32,
+    cost_usd: f64,
+}
+
+/// Fixed-capacity LRU cache of `CachedResult`s. A single `Mutex` guards both
+/// maps since hits are O(1) and cheap relative to the sandbox run they
+/// replace.
+struct ResultCache {
+    capacity: usize,
+    entries: std::collections::HashMap<String, CachedResult>,
+    /// Least-recently-used key at the front, most-recently-used at the back
+    order: VecDeque<String>,
+}
+
+impl ResultCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: std::collections::HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<CachedResult> {
+        let result = self.entries.get(key).cloned()?;
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key.to_string());
+        Some(result)
+    }
+
+    fn insert(&mut self, key: String, result: CachedResult) {
+        if self.entries.contains_key(&key) {
+            if let Some(pos) = self.order.iter().position(|k| k == &key) {
+                self.order.remove(pos);
+            }
+        } else if self.entries.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.push_back(key.clone());
+        self.entries.insert(key, result);
+    }
+}
+
+/// Recorded execution sequence an integration test can replay against a
+/// [`crate::mock_sandbox::MockSandbox`]-backed engine instead of hand-coding
+/// one `AgentExecutionRequest`/scripted-response pair per test: the request
+/// fields, the `ExecutionResult` the mock should hand back, and the final
+/// shape (`AgentExecutionResult::status` plus the FSM instance's terminal
+/// state and transition count) the replay is expected to produce.
+#[cfg(test)]
+#[derive(Debug, Clone, serde::Deserialize)]
+struct ExecutionFixture {
+    code: String,
+    /// One of `ExecutionStatus`'s variants in snake_case (`"success"`,
+    /// `"runtime_error"`, ...); see `execution_status_from_fixture`
+    scripted_status: String,
+    scripted_output: String,
+    #[serde(default)]
+    scripted_error: Option<String>,
+    #[serde(default)]
+    scripted_gas_consumed: u64,
+    expected_status: String,
+    expected_fsm_final_state: String,
+    expected_fsm_transition_count: u64,
+}
+
+#[cfg(test)]
+fn execution_status_from_fixture(raw: &str) -> ExecutionStatus {
+    match raw {
+        "success" => ExecutionStatus::Success,
+        "timeout" => ExecutionStatus::Timeout,
+        "memory_limit" => ExecutionStatus::MemoryLimit,
+        "cpu_limit" => ExecutionStatus::CpuLimit,
+        "security_violation" => ExecutionStatus::SecurityViolation,
+        "runtime_error" => ExecutionStatus::RuntimeError,
+        "compilation_error" => ExecutionStatus::CompilationError,
+        "cancelled" => ExecutionStatus::Cancelled,
+        other => panic!("unknown ExecutionStatus in fixture: {other}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        CircuitBreakerConfig, EnforcementConfig, FSMConfig, MetricsConfig, OtelExporterConfig, PriorityFactor,
+        PriorityFactors, PushGatewayConfig, RateLimitConfig, ResourceLimitsConfig, SecurityConfig, TimeoutConfig,
+        TokenBucketConfig, TokenValidatorConfig,
+    };
+    use crate::mock_sandbox::{code_hash, MockSandbox, ScriptedResponse};
+    use std::collections::HashMap as StdHashMap;
+
+    /// Every path below points somewhere that doesn't exist, so each
+    /// component falls back to its unconfigured-dev-environment default
+    /// (no OPA policies loaded → allow, no KEK/secret files → ephemeral,
+    /// audit logging disabled → no log directory created) instead of
+    /// touching the filesystem.
+    fn test_security_config() -> SecurityConfig {
+        SecurityConfig {
+            opa_policy_path: "/nonexistent/policies".into(),
+            opa_allow_query: "data.agent.allow".to_string(),
+            opa_violations_query: "data.agent.violations".to_string(),
+            encryption_key_path: "/nonexistent/keys/encryption.key".into(),
+            encryption_key_id: "test".to_string(),
+            auth_token_secret_path: "/nonexistent/keys/auth_token.secret".into(),
+            tls_cert_path: None,
+            tls_key_path: None,
+            enable_audit_log: false,
+            audit_log_path: "/nonexistent/agent-audit.log".into(),
+            audit_log_rotate_max_bytes: 104_857_600,
+            audit_log_rotate_max_age: Duration::from_secs(86_400),
+            allowed_egress_domains: vec![],
+            allowed_egress_cidrs: vec![],
+            denied_egress_cidrs: vec![],
+            allowed_egress_ports: vec!["80".to_string(), "443".to_string()],
+            response_content_security_policy: "default-src 'none'".to_string(),
+            response_x_frame_options: "DENY".to_string(),
+            response_x_content_type_options: "nosniff".to_string(),
+            response_referrer_policy: "no-referrer".to_string(),
+            response_permissions_policy: String::new(),
+        }
+    }
+
+    fn test_enforcement_config() -> EnforcementConfig {
+        let factor = PriorityFactor { usage_factor: 1.0, burst_multiplier: 1.0 };
+        EnforcementConfig {
+            timeout_config: TimeoutConfig {
+                max_duration: Duration::from_secs(300),
+                warning_threshold: Duration::from_secs(60),
+            },
+            rate_limit_config: RateLimitConfig {
+                ops: TokenBucketConfig {
+                    size: 1_000,
+                    complete_refill_time: Duration::from_secs(1),
+                    one_time_burst: None,
+                },
+                tokens: TokenBucketConfig {
+                    size: 1_000_000,
+                    complete_refill_time: Duration::from_secs(1),
+                    one_time_burst: None,
+                },
+                priority_factors: PriorityFactors { low: factor, normal: factor, high: factor, critical: factor },
+                max_blocking_wait: Duration::from_secs(1),
+            },
+            circuit_breaker_config: CircuitBreakerConfig {
+                failure_threshold: 100,
+                success_threshold: 1,
+                timeout: Duration::from_secs(60),
+            },
+            token_validator_config: TokenValidatorConfig { max_tokens: 1_000_000, cost_per_token: 0.002 },
+            resource_limits_config: ResourceLimitsConfig {
+                max_memory_mb: 4096,
+                max_cpu_cores: 8.0,
+                max_network_bandwidth_mbps: 1000,
+                max_storage_mb: 4096,
+            },
+            gc_sweep_interval: Duration::from_secs(3600),
+            ext_authz: crate::config::ExtAuthzConfig::default(),
+        }
+    }
+
+    fn test_fsm_config() -> FSMConfig {
+        FSMConfig {
+            max_states: 100,
+            max_transitions: 100,
+            state_timeout: Duration::from_secs(300),
+            persistence_enabled: false,
+            persistence_path: "/nonexistent/fsm".into(),
+            timeout_check_interval: Duration::from_secs(300),
+        }
+    }
+
+    fn test_metrics_config() -> MetricsConfig {
+        MetricsConfig {
+            enabled: false,
+            addr: "127.0.0.1:0".parse().unwrap(),
+            path: "/metrics".to_string(),
+            collection_interval: Duration::from_secs(3600),
+            otel: OtelExporterConfig {
+                enabled: false,
+                collector_endpoint: String::new(),
+                export_interval: Duration::from_secs(3600),
+                service_name: "agent-core-test".to_string(),
+            },
+            pushgateway: PushGatewayConfig {
+                enabled: false,
+                url: String::new(),
+                job: String::new(),
+                push_interval: Duration::from_secs(3600),
+                auth_header: None,
+            },
+            max_label_series: 200,
+        }
+    }
+
+    fn test_execution_config() -> ExecutionConfig {
+        ExecutionConfig { result_cache_enabled: false, result_cache_capacity: 0 }
+    }
+
+    fn test_request(code: &str) -> AgentExecutionRequest {
+        AgentExecutionRequest {
+            user_id: "fixture-user".to_string(),
+            tenant_id: "fixture-tenant".to_string(),
+            session_id: "fixture-session".to_string(),
+            code: code.to_string(),
+            language: CodeLanguage::Python,
+            timeout: Duration::from_secs(5),
+            memory_limit: 64 * 1024 * 1024,
+            cpu_limit: 1_000_000_000,
+            environment: StdHashMap::new(),
+            allowed_hosts: vec![],
+            cacheable: false,
+            priority: TaskPriority::Normal,
+        }
+    }
+
+    fn scripted_execution_result(fixture: &ExecutionFixture) -> ExecutionResult {
+        ExecutionResult {
+            execution_id: "mock".to_string(),
+            status: execution_status_from_fixture(&fixture.scripted_status),
+            output: fixture.scripted_output.clone(),
+            error_message: fixture.scripted_error.clone(),
+            metrics: ExecutionMetrics {
+                memory_used: 1024,
+                cpu_time: Duration::from_millis(10),
+                syscalls_count: 0,
+                file_operations: 0,
+                network_requests: 0,
+                network_bytes_sent: 0,
+                network_bytes_received: 0,
+                gas_consumed: fixture.scripted_gas_consumed,
+                trap_reason: None,
+            },
+            duration: Duration::from_millis(10),
+        }
+    }
+
+    /// Builds a real `ExecutionEngine` — genuine `SecurityManager`,
+    /// `EnforcementGateway`, `StateMachine`, `MetricsCollector` — wired to
+    /// `sandbox` instead of a `WASISandbox`, so the orchestration path under
+    /// test is exactly what production runs, just with the sandbox call
+    /// scripted.
+    async fn test_engine(sandbox: Arc<MockSandbox>) -> Arc<ExecutionEngine> {
+        let metrics = Arc::new(MetricsCollector::new(&test_metrics_config()).expect("metrics collector"));
+        let security =
+            Arc::new(SecurityManager::new(&test_security_config()).await.expect("security manager"));
+        let enforcement = Arc::new(
+            EnforcementGateway::new(&test_enforcement_config(), metrics.clone())
+                .await
+                .expect("enforcement gateway"),
+        );
+        let state_machine = Arc::new(StateMachine::new(&test_fsm_config()).expect("state machine"));
+
+        Arc::new(
+            ExecutionEngine::new(sandbox, enforcement, security, state_machine, metrics, &test_execution_config())
+                .expect("execution engine"),
+        )
+    }
+
+    /// Replay `fixture` against a fresh `MockSandbox`-backed engine and
+    /// assert both the result and the exact FSM trace it produced.
+    async fn replay(fixture: ExecutionFixture) {
+        let mock = Arc::new(MockSandbox::new());
+        let engine = test_engine(mock.clone()).await;
+
+        mock.script(fixture.code.as_bytes(), ScriptedResponse::success(scripted_execution_result(&fixture)));
+
+        let result = engine
+            .execute_agent_code(test_request(&fixture.code))
+            .await
+            .expect("execute_agent_code");
+
+        assert_eq!(result.status, execution_status_from_fixture(&fixture.expected_status));
+        let fsm_result = result.fsm_result.as_ref().expect("fsm_result present for a non-cached run");
+        assert_eq!(fsm_result.final_state, fixture.expected_fsm_final_state);
+        assert_eq!(fsm_result.transition_count, fixture.expected_fsm_transition_count);
+
+        let calls = mock.recorded_calls();
+        assert_eq!(calls.len(), 1, "exactly one sandbox call expected");
+        assert_eq!(calls[0].method, "execute_python");
+        assert_eq!(calls[0].code_hash, code_hash(fixture.code.as_bytes()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn replays_a_successful_execution_fixture() {
+        let fixture: ExecutionFixture = serde_json::from_str(
+            r#"{
+                "code": "print('hello from fixture')",
+                "scripted_status": "success",
+                "scripted_output": "hello from fixture",
+                "expected_status": "success",
+                "expected_fsm_final_state": "planning",
+                "expected_fsm_transition_count": 2
+            }"#,
+        )
+        .expect("valid fixture JSON");
+
+        replay(fixture).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn replays_a_failing_execution_fixture() {
+        let fixture: ExecutionFixture = serde_json::from_str(
+            r#"{
+                "code": "raise ValueError('boom')",
+                "scripted_status": "runtime_error",
+                "scripted_output": "",
+                "scripted_error": "ValueError: boom",
+                "expected_status": "runtime_error",
+                "expected_fsm_final_state": "failed",
+                "expected_fsm_transition_count": 2
+            }"#,
+        )
+        .expect("valid fixture JSON");
+
+        replay(fixture).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn rejects_unsafe_code_before_ever_reaching_the_sandbox() {
+        let mock = Arc::new(MockSandbox::new());
+        let engine = test_engine(mock.clone()).await;
+
+        let result = engine
+            .execute_agent_code(test_request("eval('1 + 1')"))
+            .await
+            .expect("execute_agent_code");
+
+        assert_eq!(result.status, ExecutionStatus::SecurityViolation);
+        assert!(result.fsm_result.is_none());
+        assert!(mock.recorded_calls().is_empty(), "unsafe code must never reach the sandbox");
+    }
+}
@@ -0,0 +1,190 @@
+//! Envoy `ext_authz` gRPC server (`envoy.service.auth.v3.Authorization`,
+//! scoped down in `proto/ext_authz.proto`). Runs each HTTP request Envoy
+//! forwards through the same [`EnforcementGateway`] pipeline as this
+//! crate's own gRPC API — rate limiting, circuit breaker, token validation
+//! — so the enforcement layer can be dropped in front of any Envoy-fronted
+//! service, not just this one.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tonic::{Request, Response, Status};
+use tracing::{debug, warn};
+
+use crate::config::ExtAuthzConfig;
+use crate::enforcement::{
+    EnforcementError, EnforcementGateway, ExecuteTaskRequest, ResourceRequirements, TaskPriority,
+};
+use crate::generated::ext_authz::authorization_server::Authorization;
+use crate::generated::ext_authz::{
+    check_response, CheckRequest, CheckResponse, DeniedHttpResponse, HeaderValueOption, HttpAttributes,
+    OkHttpResponse,
+};
+
+/// Nominal cost charged against the rate limiter/token validator for every
+/// request this filter admits to the pipeline — `HttpAttributes` carries no
+/// duration/token estimate of its own, unlike an `ExecuteCodeRequest`.
+const ESTIMATED_DURATION: Duration = Duration::from_secs(1);
+const ESTIMATED_TOKENS: u32 = 1;
+
+/// gRPC service wired to [`crate::generated::ext_authz::authorization_server::AuthorizationServer`].
+pub struct ExtAuthzService {
+    enforcement: Arc<EnforcementGateway>,
+    fail_open: bool,
+}
+
+impl ExtAuthzService {
+    pub fn new(enforcement: Arc<EnforcementGateway>, config: &ExtAuthzConfig) -> Self {
+        Self {
+            enforcement,
+            fail_open: config.fail_open,
+        }
+    }
+
+    /// Build the `ExecuteTaskRequest` charged against the enforcement
+    /// pipeline for this HTTP call, keying usage by `x-user-id`/`x-tenant-id`
+    /// headers (falling back to the connecting address/host) since Envoy's
+    /// `HttpAttributes` carries no notion of our own user/tenant identity.
+    /// Priority is similarly read from an `x-priority` header (one of
+    /// "low"/"high"/"critical", defaulting to `Normal`), so a caller fronted
+    /// by Envoy can get the same priority-scaled rate-limit treatment as a
+    /// direct gRPC caller.
+    fn task_request(attributes: &HttpAttributes) -> ExecuteTaskRequest {
+        let user_id = attributes
+            .headers
+            .get("x-user-id")
+            .cloned()
+            .unwrap_or_else(|| attributes.source_address.clone());
+        let tenant_id = attributes
+            .headers
+            .get("x-tenant-id")
+            .cloned()
+            .unwrap_or_else(|| attributes.host.clone());
+        let priority = match attributes.headers.get("x-priority").map(|p| p.to_lowercase()) {
+            Some(ref p) if p == "low" => TaskPriority::Low,
+            Some(ref p) if p == "high" => TaskPriority::High,
+            Some(ref p) if p == "critical" => TaskPriority::Critical,
+            _ => TaskPriority::Normal,
+        };
+
+        ExecuteTaskRequest {
+            user_id,
+            tenant_id,
+            session_id: String::new(),
+            task_id: format!("{} {}", attributes.method, attributes.path),
+            estimated_duration: ESTIMATED_DURATION,
+            estimated_tokens: ESTIMATED_TOKENS,
+            priority,
+            resource_requirements: ResourceRequirements {
+                memory_mb: 0,
+                cpu_cores: 0.0,
+                network_bandwidth_mbps: 0,
+                storage_mb: 0,
+            },
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Authorization for ExtAuthzService {
+    /// A policy denial (rate limit, circuit breaker, token/resource limits)
+    /// always produces a `DeniedHttpResponse`, regardless of `fail_open` —
+    /// that flag only governs what happens if the enforcement pipeline
+    /// itself panics, which `fail_open` lets operators treat as "let the
+    /// request through" rather than "deny everything" during an outage.
+    async fn check(&self, request: Request<CheckRequest>) -> Result<Response<CheckResponse>, Status> {
+        let attributes = request.into_inner().attributes.unwrap_or_default();
+        let task_request = Self::task_request(&attributes);
+
+        debug!("ext_authz check for {} {}", attributes.method, attributes.path);
+
+        let enforcement = self.enforcement.clone();
+        match tokio::spawn(async move { enforcement.enforce_request(&task_request).await }).await {
+            Ok(Ok(())) => Ok(Response::new(ok_response())),
+            Ok(Err(policy_err)) => Ok(Response::new(denied_response(&policy_err))),
+            Err(join_err) => {
+                warn!("ext_authz enforcement pipeline panicked: {}", join_err);
+                if self.fail_open {
+                    Ok(Response::new(ok_response()))
+                } else {
+                    Ok(Response::new(internal_error_response()))
+                }
+            }
+        }
+    }
+}
+
+fn ok_response() -> CheckResponse {
+    CheckResponse {
+        http_response: Some(check_response::HttpResponse::OkResponse(OkHttpResponse {
+            headers: Vec::new(),
+        })),
+    }
+}
+
+/// Map an [`EnforcementError`] to the HTTP status/headers Envoy should
+/// return to the downstream client: 429 with `Retry-After` for rate limits,
+/// 403 for everything else the pipeline actively denies.
+fn denied_response(err: &EnforcementError) -> CheckResponse {
+    let (status_code, headers) = match err {
+        EnforcementError::RateLimitExceeded { retry_after, .. } => (
+            429,
+            vec![HeaderValueOption {
+                key: "retry-after".to_string(),
+                value: retry_after.as_secs().max(1).to_string(),
+            }],
+        ),
+        _ => (403, Vec::new()),
+    };
+
+    CheckResponse {
+        http_response: Some(check_response::HttpResponse::DeniedResponse(DeniedHttpResponse {
+            status_code,
+            body: err.to_string(),
+            headers,
+        })),
+    }
+}
+
+/// Denial returned when the enforcement pipeline panicked and `fail_open`
+/// is false.
+fn internal_error_response() -> CheckResponse {
+    CheckResponse {
+        http_response: Some(check_response::HttpResponse::DeniedResponse(DeniedHttpResponse {
+            status_code: 503,
+            body: "enforcement pipeline error".to_string(),
+            headers: Vec::new(),
+        })),
+    }
+}
+
+/// Start the ext_authz gRPC server on `listener`, serving until the process
+/// shuts down. A no-op if `config.enabled` is false (the caller is expected
+/// to check that before binding/calling this at all).
+pub async fn start_ext_authz_server(
+    enforcement: Arc<EnforcementGateway>,
+    config: &ExtAuthzConfig,
+    listener: std::net::TcpListener,
+) -> Result<(), anyhow::Error> {
+    use crate::generated::ext_authz::authorization_server::AuthorizationServer;
+
+    if !config.enabled {
+        return Ok(());
+    }
+
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| anyhow::anyhow!("failed to set ext_authz listener non-blocking: {}", e))?;
+    let tcp_listener = tokio::net::TcpListener::from_std(listener)
+        .map_err(|e| anyhow::anyhow!("failed to adopt bound ext_authz listener: {}", e))?;
+    let incoming = tokio_stream::wrappers::TcpListenerStream::new(tcp_listener);
+
+    let service = AuthorizationServer::new(ExtAuthzService::new(enforcement, config));
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_incoming(incoming)
+        .await
+        .map_err(|e| anyhow::anyhow!("ext_authz server error: {}", e))?;
+
+    Ok(())
+}
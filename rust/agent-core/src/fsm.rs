@@ -1,20 +1,113 @@
 use anyhow::{Context, Result};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{watch, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::config::FSMConfig;
 
-/// Finite State Machine for agent execution control
+/// Finite State Machine for agent execution control. A thin handle around
+/// an [`FsmInner`] shared with the background timeout monitor and schedule
+/// dispatcher spawned in [`StateMachine::new`], so both drive transitions
+/// through the exact same code path a caller does.
 pub struct StateMachine {
+    inner: Arc<FsmInner>,
+    timeout_monitor: tokio::task::JoinHandle<()>,
+    schedule_dispatcher: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for StateMachine {
+    fn drop(&mut self) {
+        self.timeout_monitor.abort();
+        self.schedule_dispatcher.abort();
+    }
+}
+
+/// State shared between `StateMachine` and its background timeout monitor
+struct FsmInner {
     config: FSMConfig,
-    states: Arc<RwLock<HashMap<String, State>>>,
-    transitions: Arc<RwLock<HashMap<String, Vec<Transition>>>>,
-    active_instances: Arc<Mutex<HashMap<String, StateMachineInstance>>>,
+    states: RwLock<HashMap<String, State>>,
+    transitions: RwLock<HashMap<String, Vec<Transition>>>,
+    active_instances: Mutex<HashMap<String, StateMachineInstance>>,
+    action_handlers: RwLock<HashMap<String, Arc<dyn ActionHandler>>>,
+    condition_handlers: RwLock<HashMap<String, Arc<dyn ConditionHandler>>>,
+    /// One `watch` channel per live instance, so every concurrent
+    /// `await_completion` call for the same id shares a single notification
+    /// path instead of each polling `get_instance` in a loop. Holds `None`
+    /// until the instance reaches a terminal status.
+    completion_channels: DashMap<String, watch::Sender<Option<StateMachineResult>>>,
+    /// Pending recurring events, ordered by next fire time. Cancellation is
+    /// lazy: `cancel_scheduled` just drops the id from `active_schedules`,
+    /// and the dispatcher discards an entry it pops that's no longer there.
+    scheduled_events: Mutex<BinaryHeap<ScheduledEvent>>,
+    active_schedules: DashMap<String, ()>,
+    /// Marks an entry that `cancel_scheduled` tried to cancel while the
+    /// dispatcher had already popped it out of `active_schedules` for this
+    /// tick (i.e. the cancel's own `remove` was a no-op). The dispatcher
+    /// checks this right before re-queuing the entry, so a cancel racing
+    /// the dispatch loop still sticks instead of being silently dropped.
+    in_flight_cancellations: DashMap<String, ()>,
+}
+
+/// One entry in the `scheduled_events` heap: an event template to replay on
+/// `instance_id` every `every`, up to `remaining_repeats` more times (`None`
+/// means unbounded). Ordered by `next_fire` ascending so `BinaryHeap`, which
+/// is normally a max-heap, pops the soonest-due entry first.
+struct ScheduledEvent {
+    entry_id: String,
+    instance_id: String,
+    event_type: String,
+    payload: HashMap<String, String>,
+    every: Duration,
+    remaining_repeats: Option<u64>,
+    next_fire: Instant,
+}
+
+impl PartialEq for ScheduledEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.next_fire == other.next_fire
+    }
+}
+
+impl Eq for ScheduledEvent {}
+
+impl PartialOrd for ScheduledEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScheduledEvent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the soonest `next_fire` sorts as the greatest element,
+        // making this a min-heap by fire time
+        other.next_fire.cmp(&self.next_fire)
+    }
+}
+
+fn is_terminal_status(status: &InstanceStatus) -> bool {
+    matches!(
+        status,
+        InstanceStatus::Completed | InstanceStatus::Failed | InstanceStatus::Aborted | InstanceStatus::Timeout
+    )
+}
+
+/// Short label for an action, used in `FsmError::ActionFailed` so a failed
+/// `TransitionRecord` identifies which action in the list actually failed
+fn describe_action(action: &Action) -> String {
+    match action {
+        Action::Log(_) => "log".to_string(),
+        Action::SetVariable(key, _) => format!("set_variable:{key}"),
+        Action::CallFunction(name, _) => format!("call_function:{name}"),
+        Action::SendEvent(event_type, _) => format!("send_event:{event_type}"),
+        Action::UpdateMetrics(name, _) => format!("update_metrics:{name}"),
+        Action::Custom(name, _) => format!("custom:{name}"),
+    }
 }
 
 /// Represents a state in the FSM
@@ -38,6 +131,9 @@ pub enum StateType {
     Decision,
     Terminal,
     Error,
+    /// Terminal state reached via a caller-driven `abort` event rather than
+    /// the execution finishing (successfully or not) on its own
+    Aborted,
 }
 
 /// Represents a transition between states
@@ -59,6 +155,7 @@ pub enum TransitionCondition {
     OnTimeout,
     OnSuccess,
     OnError,
+    OnAbort,
     OnCondition(String), // Expression to evaluate
     Custom(String),      // Custom condition handler
 }
@@ -74,6 +171,31 @@ pub enum Action {
     Custom(String, HashMap<String, String>),
 }
 
+/// Arguments passed to a registered [`ActionHandler`]: a positional list for
+/// `Action::CallFunction`, or a named map for `Action::Custom`
+pub enum ActionArgs<'a> {
+    List(&'a [String]),
+    Map(&'a HashMap<String, String>),
+}
+
+/// Caller-registered handler for `Action::CallFunction`/`Action::Custom`,
+/// looked up by name in the registry populated via
+/// [`StateMachine::register_function`]. Takes the instance's context
+/// mutably so a handler can read and write `variables` the same way
+/// `Action::SetVariable` does.
+#[tonic::async_trait]
+pub trait ActionHandler: Send + Sync {
+    async fn call(&self, context: &mut StateMachineContext, args: ActionArgs<'_>) -> Result<()>;
+}
+
+/// Caller-registered handler for `TransitionCondition::Custom`, looked up by
+/// name in the registry populated via [`StateMachine::register_condition`].
+/// Read-only: a transition guard shouldn't have side effects.
+#[tonic::async_trait]
+pub trait ConditionHandler: Send + Sync {
+    async fn evaluate(&self, context: &StateMachineContext) -> Result<bool>;
+}
+
 /// Instance of a running state machine
 #[derive(Debug, Clone)]
 pub struct StateMachineInstance {
@@ -82,10 +204,81 @@ pub struct StateMachineInstance {
     pub context: StateMachineContext,
     pub created_at: Instant,
     pub last_transition: Instant,
+    /// Wall-clock mirror of `created_at`, carried alongside the `Instant`
+    /// purely so a snapshot can be persisted without an `Instant -> wall`
+    /// conversion (`Instant` has none)
+    created_at_wall: chrono::DateTime<chrono::Utc>,
+    /// Wall-clock mirror of `last_transition`, same reasoning
+    last_transition_wall: chrono::DateTime<chrono::Utc>,
     pub transition_count: u64,
     pub status: InstanceStatus,
 }
 
+/// On-disk snapshot of a [`StateMachineInstance`], written after every state
+/// change so an in-flight execution survives a process restart. `Instant`
+/// itself isn't serializable, and an absolute `Instant` couldn't be restored
+/// across a restart anyway, so the wall-clock timestamps are stored instead;
+/// [`StateMachine::recover_instances`] reconstructs `Instant`s with the same
+/// elapsed durations relative to the new process's clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedInstance {
+    id: String,
+    current_state: String,
+    context: StateMachineContext,
+    created_at: chrono::DateTime<chrono::Utc>,
+    last_transition: chrono::DateTime<chrono::Utc>,
+    transition_count: u64,
+    status: InstanceStatus,
+}
+
+impl From<&StateMachineInstance> for PersistedInstance {
+    fn from(instance: &StateMachineInstance) -> Self {
+        Self {
+            id: instance.id.clone(),
+            current_state: instance.current_state.clone(),
+            context: instance.context.clone(),
+            created_at: instance.created_at_wall,
+            last_transition: instance.last_transition_wall,
+            transition_count: instance.transition_count,
+            status: instance.status.clone(),
+        }
+    }
+}
+
+impl From<PersistedInstance> for StateMachineInstance {
+    fn from(snapshot: PersistedInstance) -> Self {
+        let now = Instant::now();
+        let wall_now = chrono::Utc::now();
+        Self {
+            id: snapshot.id,
+            current_state: snapshot.current_state,
+            context: snapshot.context,
+            created_at: instant_from_wall(now, wall_now, snapshot.created_at),
+            last_transition: instant_from_wall(now, wall_now, snapshot.last_transition),
+            created_at_wall: snapshot.created_at,
+            last_transition_wall: snapshot.last_transition,
+            transition_count: snapshot.transition_count,
+            status: snapshot.status,
+        }
+    }
+}
+
+/// Approximate the `Instant` a wall-clock timestamp from a previous process
+/// corresponds to "now" by applying the same elapsed duration to this
+/// process's `Instant::now()`. Falls back to `now` on clock skew that would
+/// otherwise underflow the subtraction.
+fn instant_from_wall(
+    now: Instant,
+    wall_now: chrono::DateTime<chrono::Utc>,
+    wall_then: chrono::DateTime<chrono::Utc>,
+) -> Instant {
+    (wall_now - wall_then)
+        .to_std()
+        .ok()
+        .and_then(|elapsed| now.checked_sub(elapsed))
+        .unwrap_or(now)
+}
+
 /// Context for state machine execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateMachineContext {
@@ -113,17 +306,64 @@ pub struct TransitionRecord {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub duration: Duration,
     pub success: bool,
+    /// [`FsmError::kind`] of the failure, so a caller (or an `OnCondition`
+    /// guard reading `context.execution_history`) can branch on failure
+    /// category without string-matching `error_message`
+    pub error_kind: Option<String>,
     pub error_message: Option<String>,
 }
 
+/// Typed failure taxonomy for FSM operations, returned by the public
+/// instance-lifecycle API instead of an `anyhow::anyhow!` string so callers
+/// can distinguish failure categories programmatically
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum FsmError {
+    #[error("instance not found: {0}")]
+    InstanceNotFound(String),
+
+    #[error("maximum number of states exceeded")]
+    MaxStatesExceeded,
+
+    #[error("maximum number of transitions exceeded")]
+    MaxTransitionsExceeded,
+
+    #[error("action '{action}' failed: {source}")]
+    ActionFailed { action: String, source: String },
+
+    #[error("condition evaluation failed: {0}")]
+    ConditionEvalFailed(String),
+
+    #[error("state timed out")]
+    Timeout,
+
+    #[error("persistence error: {0}")]
+    PersistenceError(String),
+}
+
+impl FsmError {
+    /// Stable discriminant name recorded in `TransitionRecord.error_kind`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FsmError::InstanceNotFound(_) => "instance_not_found",
+            FsmError::MaxStatesExceeded => "max_states_exceeded",
+            FsmError::MaxTransitionsExceeded => "max_transitions_exceeded",
+            FsmError::ActionFailed { .. } => "action_failed",
+            FsmError::ConditionEvalFailed(_) => "condition_eval_failed",
+            FsmError::Timeout => "timeout",
+            FsmError::PersistenceError(_) => "persistence_error",
+        }
+    }
+}
+
 /// Status of state machine instance
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum InstanceStatus {
     Running,
     Paused,
     Completed,
     Failed,
     Timeout,
+    Aborted,
 }
 
 /// Result of state machine operations
@@ -148,19 +388,207 @@ impl StateMachine {
                 .context("Failed to create FSM persistence directory")?;
         }
 
-        let fsm = Self {
+        let inner = Arc::new(FsmInner {
             config: config.clone(),
-            states: Arc::new(RwLock::new(HashMap::new())),
-            transitions: Arc::new(RwLock::new(HashMap::new())),
-            active_instances: Arc::new(Mutex::new(HashMap::new())),
-        };
+            states: RwLock::new(HashMap::new()),
+            transitions: RwLock::new(HashMap::new()),
+            active_instances: Mutex::new(HashMap::new()),
+            action_handlers: RwLock::new(HashMap::new()),
+            condition_handlers: RwLock::new(HashMap::new()),
+            completion_channels: DashMap::new(),
+            scheduled_events: Mutex::new(BinaryHeap::new()),
+            active_schedules: DashMap::new(),
+            in_flight_cancellations: DashMap::new(),
+        });
 
         // Load default states and transitions
-        fsm.initialize_default_fsm()?;
+        inner.initialize_default_fsm()?;
+
+        // Rehydrate any instances that were still running when the process
+        // last exited, so a restart doesn't silently drop in-flight work
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(inner.recover_instances())
+        })?;
+
+        let timeout_monitor = spawn_timeout_monitor(inner.clone(), config.timeout_check_interval);
+        let schedule_dispatcher = spawn_schedule_dispatcher(inner.clone());
 
-        Ok(fsm)
+        Ok(Self { inner, timeout_monitor, schedule_dispatcher })
     }
 
+    /// Stop the background timeout monitor and schedule dispatcher.
+    /// Idempotent, and also happens automatically when the `StateMachine`
+    /// is dropped.
+    pub fn shutdown(&self) {
+        self.timeout_monitor.abort();
+        self.schedule_dispatcher.abort();
+    }
+
+    /// Add states to the FSM
+    pub async fn add_states(&self, states: Vec<State>) -> Result<(), FsmError> {
+        self.inner.add_states(states).await
+    }
+
+    /// Add transitions to the FSM
+    pub async fn add_transitions(&self, transitions: Vec<Transition>) -> Result<(), FsmError> {
+        self.inner.add_transitions(transitions).await
+    }
+
+    /// Create a new state machine instance
+    pub async fn create_instance(&self, initial_context: StateMachineContext) -> Result<String> {
+        self.inner.create_instance(initial_context).await
+    }
+
+    /// Trigger an event on a state machine instance
+    pub async fn trigger_event(&self, instance_id: &str, event: Event) -> Result<(), FsmError> {
+        self.inner.trigger_event(instance_id, event).await
+    }
+
+    /// Get state machine instance
+    pub async fn get_instance(&self, instance_id: &str) -> Result<StateMachineInstance, FsmError> {
+        self.inner.get_instance(instance_id).await
+    }
+
+    /// Complete state machine instance
+    pub async fn complete_instance(&self, instance_id: &str) -> Result<StateMachineResult, FsmError> {
+        self.inner.complete_instance(instance_id).await
+    }
+
+    /// Get FSM statistics
+    pub async fn get_stats(&self) -> FSMStats {
+        self.inner.get_stats().await
+    }
+
+    /// Register a handler invoked whenever `Action::CallFunction` or
+    /// `Action::Custom` names `name`. Replaces any handler previously
+    /// registered under the same name.
+    pub async fn register_function(&self, name: impl Into<String>, handler: Arc<dyn ActionHandler>) {
+        self.inner.register_function(name, handler).await;
+    }
+
+    /// Register a handler invoked whenever `TransitionCondition::Custom`
+    /// names `name`. Replaces any handler previously registered under the
+    /// same name.
+    pub async fn register_condition(&self, name: impl Into<String>, handler: Arc<dyn ConditionHandler>) {
+        self.inner.register_condition(name, handler).await;
+    }
+
+    /// Resolve once `instance_id` reaches a terminal status, without
+    /// polling `get_instance`. Concurrent callers awaiting the same
+    /// instance share one underlying channel.
+    pub async fn await_completion(&self, instance_id: &str) -> Result<StateMachineResult, FsmError> {
+        self.inner.await_completion(instance_id).await
+    }
+
+    /// Replay `event` on `instance_id` every `every`, up to `max_repeats`
+    /// more times (`None` for unbounded), stopping early once the instance
+    /// reaches a terminal status. Returns an entry id that can be passed to
+    /// [`StateMachine::cancel_scheduled`].
+    pub async fn schedule_event(
+        &self,
+        instance_id: &str,
+        event: Event,
+        every: Duration,
+        max_repeats: Option<u64>,
+    ) -> Result<String, FsmError> {
+        self.inner.schedule_event(instance_id, event, every, max_repeats).await
+    }
+
+    /// Stop a recurring event previously set up with `schedule_event`. A
+    /// no-op if `entry_id` already fired its last repeat or was already
+    /// cancelled.
+    pub async fn cancel_scheduled(&self, entry_id: &str) {
+        self.inner.cancel_scheduled(entry_id).await;
+    }
+}
+
+/// Background task that periodically scans `active_instances` for a
+/// `Running` instance whose current state's timeout has elapsed (falling
+/// back to `config.state_timeout` for states that don't define their own),
+/// and synthesizes a `timeout` event to drive it through `check_transitions`
+/// exactly as `trigger_event` would. An instance with no applicable
+/// `OnTimeout` transition is parked in `InstanceStatus::Timeout` directly,
+/// since there's nowhere else for it to go and it shouldn't be re-checked
+/// every tick. Aborted by `StateMachine::shutdown` or on drop.
+fn spawn_timeout_monitor(inner: Arc<FsmInner>, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            let timed_out = {
+                let instances = inner.active_instances.lock().await;
+                let states = inner.states.read().await;
+                instances
+                    .values()
+                    .filter(|instance| instance.status == InstanceStatus::Running)
+                    .filter_map(|instance| {
+                        let timeout = states
+                            .get(&instance.current_state)
+                            .and_then(|state| state.timeout)
+                            .unwrap_or(inner.config.state_timeout);
+                        (instance.last_transition.elapsed() >= timeout).then(|| instance.id.clone())
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            for instance_id in timed_out {
+                let current_state = {
+                    let instances = inner.active_instances.lock().await;
+                    instances.get(&instance_id).map(|instance| instance.current_state.clone())
+                };
+                let Some(current_state) = current_state else { continue };
+
+                let event = Event {
+                    id: Uuid::new_v4().to_string(),
+                    event_type: "timeout".to_string(),
+                    payload: HashMap::new(),
+                    timestamp: chrono::Utc::now(),
+                };
+
+                if let Err(e) = inner.check_transitions(&instance_id, &current_state, Some(&event)).await {
+                    warn!("Timeout transition check failed for instance {}: {}", instance_id, e);
+                    continue;
+                }
+
+                let mut instances = inner.active_instances.lock().await;
+                let mut snapshot = None;
+                if let Some(instance) = instances.get_mut(&instance_id) {
+                    if instance.current_state == current_state && instance.status == InstanceStatus::Running {
+                        instance.status = InstanceStatus::Timeout;
+                        inner.publish_completion(instance);
+                        snapshot = Some(instance.clone());
+                    }
+                }
+                drop(instances);
+
+                if let Some(snapshot) = snapshot {
+                    if let Err(e) = inner.persist_instance(&snapshot).await {
+                        warn!("Failed to persist timed-out FSM instance {}: {}", instance_id, e);
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Background task that wakes on a short, fixed tick and dispatches every
+/// `scheduled_events` entry whose `next_fire` has passed, via the same
+/// `trigger_event` path an external caller would use. Aborted by
+/// `StateMachine::shutdown` or on drop.
+fn spawn_schedule_dispatcher(inner: Arc<FsmInner>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_millis(100));
+
+        loop {
+            ticker.tick().await;
+            inner.dispatch_due_schedules().await;
+        }
+    })
+}
+
+impl FsmInner {
     /// Initialize default FSM structure for agent execution
     fn initialize_default_fsm(&self) -> Result<()> {
         // Define default states for agent execution
@@ -228,6 +656,15 @@ impl StateMachine {
                 timeout: None,
                 metadata: HashMap::new(),
             },
+            State {
+                id: "aborted".to_string(),
+                name: "Aborted".to_string(),
+                state_type: StateType::Aborted,
+                entry_actions: vec![Action::Log("Task execution aborted".to_string())],
+                exit_actions: vec![],
+                timeout: None,
+                metadata: HashMap::new(),
+            },
         ];
 
         // Define default transitions
@@ -290,6 +727,15 @@ impl StateMachine {
                 actions: vec![Action::Log("State timeout occurred".to_string())],
                 priority: 9,
             },
+            // Abort transitions, driven by `ExecutionEngine::cancel_execution`
+            Transition {
+                id: "any_to_aborted".to_string(),
+                from_state: "*".to_string(),
+                to_state: "aborted".to_string(),
+                condition: TransitionCondition::OnAbort,
+                actions: vec![Action::Log("Transitioning to aborted state".to_string())],
+                priority: 10,
+            },
         ];
 
         // Add states and transitions
@@ -299,85 +745,117 @@ impl StateMachine {
                 self.add_transitions(default_transitions).await
             })
         })
+        .map_err(anyhow::Error::from)
     }
 
     /// Add states to the FSM
-    pub async fn add_states(&self, states: Vec<State>) -> Result<()> {
+    pub async fn add_states(&self, states: Vec<State>) -> Result<(), FsmError> {
         let mut state_map = self.states.write().await;
-        
+
         for state in states {
             if state_map.len() >= self.config.max_states {
-                return Err(anyhow::anyhow!("Maximum number of states exceeded"));
+                return Err(FsmError::MaxStatesExceeded);
             }
-            
+
             debug!("Adding state: {}", state.id);
             state_map.insert(state.id.clone(), state);
         }
-        
+
         Ok(())
     }
 
     /// Add transitions to the FSM
-    pub async fn add_transitions(&self, transitions: Vec<Transition>) -> Result<()> {
+    pub async fn add_transitions(&self, transitions: Vec<Transition>) -> Result<(), FsmError> {
         let mut transition_map = self.transitions.write().await;
-        
+
         for transition in transitions {
             let total_transitions: usize = transition_map.values().map(|v| v.len()).sum();
             if total_transitions >= self.config.max_transitions {
-                return Err(anyhow::anyhow!("Maximum number of transitions exceeded"));
+                return Err(FsmError::MaxTransitionsExceeded);
             }
-            
+
             debug!("Adding transition: {} -> {}", transition.from_state, transition.to_state);
-            
+
             transition_map
                 .entry(transition.from_state.clone())
                 .or_insert_with(Vec::new)
                 .push(transition);
         }
-        
+
         Ok(())
     }
 
     /// Create a new state machine instance
     pub async fn create_instance(&self, initial_context: StateMachineContext) -> Result<String> {
         let instance_id = Uuid::new_v4().to_string();
-        
+        let now = Instant::now();
+        let now_wall = chrono::Utc::now();
+
         let instance = StateMachineInstance {
             id: instance_id.clone(),
             current_state: "initial".to_string(),
             context: initial_context,
-            created_at: Instant::now(),
-            last_transition: Instant::now(),
+            created_at: now,
+            last_transition: now,
+            created_at_wall: now_wall,
+            last_transition_wall: now_wall,
             transition_count: 0,
             status: InstanceStatus::Running,
         };
 
-        let mut instances = self.active_instances.lock().await;
-        instances.insert(instance_id.clone(), instance);
-        
+        {
+            let mut instances = self.active_instances.lock().await;
+            instances.insert(instance_id.clone(), instance.clone());
+        }
+        self.completion_channels.insert(instance_id.clone(), watch::channel(None).0);
+
         info!("Created FSM instance: {}", instance_id);
-        
+
         // Execute entry actions for initial state
         self.execute_state_entry_actions(&instance_id, "initial").await?;
-        
+
+        // Persist the freshly created instance so it survives a crash before
+        // it ever transitions
+        self.persist_instance(&instance).await?;
+
         Ok(instance_id)
     }
 
+    /// Notify any `await_completion` subscribers once `instance` reaches a
+    /// terminal status. A no-op for a still-running instance, and a no-op if
+    /// `complete_instance` already removed the channel.
+    fn publish_completion(&self, instance: &StateMachineInstance) {
+        if !is_terminal_status(&instance.status) {
+            return;
+        }
+
+        if let Some(tx) = self.completion_channels.get(&instance.id) {
+            let _ = tx.send(Some(StateMachineResult {
+                instance_id: instance.id.clone(),
+                final_state: instance.current_state.clone(),
+                status: instance.status.clone(),
+                execution_time: instance.created_at.elapsed(),
+                transition_count: instance.transition_count,
+                context: instance.context.clone(),
+            }));
+        }
+    }
+
     /// Trigger an event on a state machine instance
-    pub async fn trigger_event(&self, instance_id: &str, event: Event) -> Result<()> {
+    pub async fn trigger_event(&self, instance_id: &str, event: Event) -> Result<(), FsmError> {
         debug!("Triggering event {} on instance {}", event.event_type, instance_id);
-        
+
         let mut instances = self.active_instances.lock().await;
         let instance = instances.get_mut(instance_id)
-            .ok_or_else(|| anyhow::anyhow!("Instance not found: {}", instance_id))?;
+            .ok_or_else(|| FsmError::InstanceNotFound(instance_id.to_string()))?;
 
         // Add event to context
         instance.context.events.push(event.clone());
-        
+
         // Check for applicable transitions
         let current_state = instance.current_state.clone();
         drop(instances); // Release lock before async operations
-        
+
         self.check_transitions(instance_id, &current_state, Some(&event)).await
     }
 
@@ -387,7 +865,7 @@ impl StateMachine {
         instance_id: &str,
         current_state: &str,
         event: Option<&Event>,
-    ) -> Result<()> {
+    ) -> Result<(), FsmError> {
         let transitions = self.transitions.read().await;
         
         // Get transitions from current state and wildcard transitions
@@ -404,22 +882,57 @@ impl StateMachine {
         // Sort by priority (higher priority first)
         applicable_transitions.sort_by(|a, b| b.priority.cmp(&a.priority));
         
-        // Find first applicable transition
+        // Find first applicable transition. A condition that fails to
+        // evaluate (e.g. a malformed `OnCondition` expression) is recorded as
+        // a failed transition attempt rather than aborting the whole check,
+        // so a single bad guard doesn't wedge the instance
         for transition in applicable_transitions {
-            if self.evaluate_transition_condition(&transition.condition, event).await? {
-                self.execute_transition(instance_id, transition).await?;
-                break;
+            match self.evaluate_transition_condition(&transition.condition, event, instance_id).await {
+                Ok(true) => {
+                    self.execute_transition(instance_id, transition).await?;
+                    break;
+                }
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!("Condition evaluation failed for transition {}: {}", transition.id, e);
+                    self.record_condition_failure(instance_id, transition, &e).await;
+                    continue;
+                }
             }
         }
-        
+
         Ok(())
     }
 
-    /// Evaluate transition condition
+    /// Record a transition attempt that never executed because its
+    /// condition failed to evaluate, so the failure is visible in
+    /// `context.execution_history` instead of being swallowed
+    async fn record_condition_failure(&self, instance_id: &str, transition: &Transition, error: &anyhow::Error) {
+        let mut instances = self.active_instances.lock().await;
+        if let Some(instance) = instances.get_mut(instance_id) {
+            instance.context.execution_history.push(TransitionRecord {
+                from_state: transition.from_state.clone(),
+                to_state: transition.to_state.clone(),
+                transition_id: transition.id.clone(),
+                timestamp: chrono::Utc::now(),
+                duration: Duration::ZERO,
+                success: false,
+                error_kind: Some(FsmError::ConditionEvalFailed(error.to_string()).kind().to_string()),
+                error_message: Some(error.to_string()),
+            });
+        }
+    }
+
+    /// Evaluate transition condition. `instance_id` is only consulted by
+    /// `OnCondition`, which resolves variable references against the
+    /// instance's `context.variables` overlaid with the triggering event's
+    /// `payload` (the event's values win on conflict, since they're the most
+    /// current input).
     async fn evaluate_transition_condition(
         &self,
         condition: &TransitionCondition,
         event: Option<&Event>,
+        instance_id: &str,
     ) -> Result<bool> {
         match condition {
             TransitionCondition::Always => Ok(true),
@@ -427,8 +940,10 @@ impl StateMachine {
                 Ok(event.map_or(false, |e| e.event_type == *event_type))
             }
             TransitionCondition::OnTimeout => {
-                // This would be handled by timeout monitoring
-                Ok(false)
+                // Matches the synthetic "timeout" event the background
+                // timeout monitor drives `check_transitions` with; see
+                // `spawn_timeout_monitor`
+                Ok(event.map_or(false, |e| e.event_type == "timeout"))
             }
             TransitionCondition::OnSuccess => {
                 Ok(event.map_or(false, |e| e.event_type == "success"))
@@ -436,36 +951,76 @@ impl StateMachine {
             TransitionCondition::OnError => {
                 Ok(event.map_or(false, |e| e.event_type == "error"))
             }
-            TransitionCondition::OnCondition(_expr) => {
-                // Would evaluate expression against context
-                Ok(false)
+            TransitionCondition::OnAbort => {
+                Ok(event.map_or(false, |e| e.event_type == "abort"))
+            }
+            TransitionCondition::OnCondition(expr) => {
+                let parsed = crate::fsm_expr::parse(expr)
+                    .with_context(|| format!("invalid OnCondition expression: {}", expr))?;
+
+                let mut variables = {
+                    let instances = self.active_instances.lock().await;
+                    instances
+                        .get(instance_id)
+                        .map(|instance| instance.context.variables.clone())
+                        .unwrap_or_default()
+                };
+                if let Some(event) = event {
+                    variables.extend(event.payload.clone());
+                }
+
+                Ok(parsed.eval(&variables))
             }
-            TransitionCondition::Custom(_handler) => {
-                // Would call custom condition handler
-                Ok(false)
+            TransitionCondition::Custom(handler_name) => {
+                let handler = {
+                    let handlers = self.condition_handlers.read().await;
+                    handlers.get(handler_name).cloned()
+                }
+                .ok_or_else(|| anyhow::anyhow!("Unknown condition handler: {}", handler_name))?;
+
+                let instances = self.active_instances.lock().await;
+                let instance = instances
+                    .get(instance_id)
+                    .ok_or_else(|| anyhow::anyhow!("Instance not found: {}", instance_id))?;
+
+                handler.evaluate(&instance.context).await
             }
         }
     }
 
-    /// Execute a state transition
-    async fn execute_transition(&self, instance_id: &str, transition: &Transition) -> Result<()> {
+    /// Execute a state transition. A failing exit/transition action is
+    /// caught rather than left to abort the caller: the attempt is recorded
+    /// as a failed `TransitionRecord` and the instance is driven through
+    /// whatever `OnError` transition applies, instead of getting stuck
+    /// mid-transition.
+    async fn execute_transition(&self, instance_id: &str, transition: &Transition) -> Result<(), FsmError> {
         let start_time = Instant::now();
-        
+
         debug!(
             "Executing transition {} -> {} for instance {}",
             transition.from_state, transition.to_state, instance_id
         );
 
         // Execute exit actions for current state
-        self.execute_state_exit_actions(instance_id, &transition.from_state).await?;
-        
+        if let Err(e) = self.execute_state_exit_actions(instance_id, &transition.from_state).await {
+            let error = FsmError::ActionFailed {
+                action: format!("exit:{}", transition.from_state),
+                source: e.to_string(),
+            };
+            return self.fail_transition(instance_id, transition, start_time, error).await;
+        }
+
         // Execute transition actions
         for action in &transition.actions {
-            self.execute_action(instance_id, action).await?;
+            if let Err(e) = self.execute_action(instance_id, action).await {
+                let error = FsmError::ActionFailed { action: describe_action(action), source: e.to_string() };
+                return self.fail_transition(instance_id, transition, start_time, error).await;
+            }
         }
-        
+
         // Update instance state
         let mut instances = self.active_instances.lock().await;
+        let mut snapshot = None;
         if let Some(instance) = instances.get_mut(instance_id) {
             let transition_record = TransitionRecord {
                 from_state: transition.from_state.clone(),
@@ -474,34 +1029,88 @@ impl StateMachine {
                 timestamp: chrono::Utc::now(),
                 duration: start_time.elapsed(),
                 success: true,
+                error_kind: None,
                 error_message: None,
             };
-            
+
             instance.context.execution_history.push(transition_record);
             instance.current_state = transition.to_state.clone();
             instance.last_transition = Instant::now();
+            instance.last_transition_wall = chrono::Utc::now();
             instance.transition_count += 1;
-            
+
             // Check if reached terminal state
             let states = self.states.read().await;
             if let Some(state) = states.get(&transition.to_state) {
-                if state.state_type == StateType::Terminal || state.state_type == StateType::Error {
-                    instance.status = if state.state_type == StateType::Terminal {
-                        InstanceStatus::Completed
-                    } else {
-                        InstanceStatus::Failed
-                    };
-                }
+                instance.status = match state.state_type {
+                    StateType::Terminal => InstanceStatus::Completed,
+                    StateType::Error => InstanceStatus::Failed,
+                    StateType::Aborted => InstanceStatus::Aborted,
+                    _ => instance.status.clone(),
+                };
             }
+
+            self.publish_completion(instance);
+            snapshot = Some(instance.clone());
         }
         drop(instances);
-        
+
+        if let Some(snapshot) = &snapshot {
+            self.persist_instance(snapshot).await.map_err(|e| FsmError::PersistenceError(e.to_string()))?;
+        }
+
         // Execute entry actions for new state
-        self.execute_state_entry_actions(instance_id, &transition.to_state).await?;
-        
+        self.execute_state_entry_actions(instance_id, &transition.to_state).await.map_err(|e| {
+            FsmError::ActionFailed { action: format!("entry:{}", transition.to_state), source: e.to_string() }
+        })?;
+
         Ok(())
     }
 
+    /// Record `transition` as a failed attempt and drive the instance
+    /// through whatever `OnError` transition applies, instead of leaving it
+    /// wedged mid-transition
+    async fn fail_transition(
+        &self,
+        instance_id: &str,
+        transition: &Transition,
+        start_time: Instant,
+        error: FsmError,
+    ) -> Result<(), FsmError> {
+        warn!("Transition {} failed for instance {}: {}", transition.id, instance_id, error);
+
+        let mut snapshot = None;
+        {
+            let mut instances = self.active_instances.lock().await;
+            if let Some(instance) = instances.get_mut(instance_id) {
+                instance.context.execution_history.push(TransitionRecord {
+                    from_state: transition.from_state.clone(),
+                    to_state: transition.to_state.clone(),
+                    transition_id: transition.id.clone(),
+                    timestamp: chrono::Utc::now(),
+                    duration: start_time.elapsed(),
+                    success: false,
+                    error_kind: Some(error.kind().to_string()),
+                    error_message: Some(error.to_string()),
+                });
+                snapshot = Some(instance.clone());
+            }
+        }
+
+        if let Some(snapshot) = &snapshot {
+            self.persist_instance(snapshot).await.map_err(|e| FsmError::PersistenceError(e.to_string()))?;
+        }
+
+        let error_event = Event {
+            id: Uuid::new_v4().to_string(),
+            event_type: "error".to_string(),
+            payload: HashMap::new(),
+            timestamp: chrono::Utc::now(),
+        };
+
+        self.check_transitions(instance_id, &transition.from_state, Some(&error_event)).await
+    }
+
     /// Execute state entry actions
     async fn execute_state_entry_actions(&self, instance_id: &str, state_id: &str) -> Result<()> {
         let states = self.states.read().await;
@@ -538,7 +1147,7 @@ impl StateMachine {
             }
             Action::CallFunction(func_name, args) => {
                 debug!("Calling function {} with args: {:?}", func_name, args);
-                // Would call registered function handlers
+                self.dispatch_action(instance_id, func_name, ActionArgs::List(args)).await?;
             }
             Action::SendEvent(event_type, payload) => {
                 let event = Event {
@@ -556,36 +1165,179 @@ impl StateMachine {
             }
             Action::Custom(handler_name, params) => {
                 debug!("Executing custom action {} with params: {:?}", handler_name, params);
-                // Would call custom action handlers
+                self.dispatch_action(instance_id, handler_name, ActionArgs::Map(params)).await?;
             }
         }
         Ok(())
     }
 
+    /// Look up `handler_name` in the action handler registry and invoke it
+    /// against the instance's context, erroring if no handler was ever
+    /// registered under that name
+    async fn dispatch_action(&self, instance_id: &str, handler_name: &str, args: ActionArgs<'_>) -> Result<()> {
+        let handler = {
+            let handlers = self.action_handlers.read().await;
+            handlers.get(handler_name).cloned()
+        }
+        .ok_or_else(|| anyhow::anyhow!("Unknown action handler: {}", handler_name))?;
+
+        let mut instances = self.active_instances.lock().await;
+        let instance = instances
+            .get_mut(instance_id)
+            .ok_or_else(|| anyhow::anyhow!("Instance not found: {}", instance_id))?;
+
+        handler.call(&mut instance.context, args).await
+    }
+
+    /// Register a handler for `Action::CallFunction`/`Action::Custom`
+    async fn register_function(&self, name: impl Into<String>, handler: Arc<dyn ActionHandler>) {
+        self.action_handlers.write().await.insert(name.into(), handler);
+    }
+
+    /// Register a handler for `TransitionCondition::Custom`
+    async fn register_condition(&self, name: impl Into<String>, handler: Arc<dyn ConditionHandler>) {
+        self.condition_handlers.write().await.insert(name.into(), handler);
+    }
+
+    /// Path the given instance's snapshot is written to, one file per
+    /// instance so a crash mid-write only corrupts that instance's recovery
+    fn persistence_file(&self, instance_id: &str) -> std::path::PathBuf {
+        self.config.persistence_path.join(format!("{}.json", instance_id))
+    }
+
+    /// Write `instance`'s current snapshot, overwriting any prior one for
+    /// the same id. A no-op when persistence is disabled.
+    async fn persist_instance(&self, instance: &StateMachineInstance) -> Result<()> {
+        if !self.config.persistence_enabled {
+            return Ok(());
+        }
+
+        let snapshot = PersistedInstance::from(instance);
+        let json = serde_json::to_vec_pretty(&snapshot)?;
+        tokio::fs::write(self.persistence_file(&instance.id), json)
+            .await
+            .context("Failed to persist FSM instance")?;
+
+        Ok(())
+    }
+
+    /// Remove a completed instance's snapshot. Best-effort: a stale snapshot
+    /// left behind by a failed removal is harmless clutter, not a reason to
+    /// fail an execution that has already finished.
+    async fn remove_persisted_instance(&self, instance_id: &str) {
+        if !self.config.persistence_enabled {
+            return;
+        }
+
+        if let Err(e) = tokio::fs::remove_file(self.persistence_file(instance_id)).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove persisted FSM instance {}: {}", instance_id, e);
+            }
+        }
+    }
+
+    /// Rehydrate `active_instances` from snapshots left behind by a prior
+    /// process, so an in-flight execution isn't silently dropped across a
+    /// restart. A snapshot that fails to read or parse is logged and skipped
+    /// rather than treated as fatal, since the rest of the directory may
+    /// still be recoverable.
+    async fn recover_instances(&self) -> Result<()> {
+        if !self.config.persistence_enabled {
+            return Ok(());
+        }
+
+        let mut entries = match tokio::fs::read_dir(&self.config.persistence_path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).context("Failed to read FSM persistence directory"),
+        };
+
+        let mut instances = self.active_instances.lock().await;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+
+            let raw = match tokio::fs::read_to_string(&path).await {
+                Ok(raw) => raw,
+                Err(e) => {
+                    warn!("Failed to read persisted FSM instance {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let snapshot: PersistedInstance = match serde_json::from_str(&raw) {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    warn!("Failed to parse persisted FSM instance {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            info!("Recovered FSM instance {} in state {}", snapshot.id, snapshot.current_state);
+            instances.insert(snapshot.id.clone(), snapshot.into());
+        }
+
+        Ok(())
+    }
+
     /// Get state machine instance
-    pub async fn get_instance(&self, instance_id: &str) -> Result<StateMachineInstance> {
+    pub async fn get_instance(&self, instance_id: &str) -> Result<StateMachineInstance, FsmError> {
         let instances = self.active_instances.lock().await;
         instances.get(instance_id)
             .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Instance not found: {}", instance_id))
+            .ok_or_else(|| FsmError::InstanceNotFound(instance_id.to_string()))
     }
 
     /// Complete state machine instance
-    pub async fn complete_instance(&self, instance_id: &str) -> Result<StateMachineResult> {
+    pub async fn complete_instance(&self, instance_id: &str) -> Result<StateMachineResult, FsmError> {
         let mut instances = self.active_instances.lock().await;
         let instance = instances.remove(instance_id)
-            .ok_or_else(|| anyhow::anyhow!("Instance not found: {}", instance_id))?;
+            .ok_or_else(|| FsmError::InstanceNotFound(instance_id.to_string()))?;
+        drop(instances);
+
+        self.remove_persisted_instance(instance_id).await;
 
         let execution_time = instance.created_at.elapsed();
-        
-        Ok(StateMachineResult {
+
+        let result = StateMachineResult {
             instance_id: instance.id,
             final_state: instance.current_state,
             status: instance.status,
             execution_time,
             transition_count: instance.transition_count,
             context: instance.context,
-        })
+        };
+
+        if let Some((_, tx)) = self.completion_channels.remove(instance_id) {
+            let _ = tx.send(Some(result.clone()));
+        }
+
+        Ok(result)
+    }
+
+    /// Resolve once `instance_id` reaches a terminal status, without
+    /// polling `get_instance`. Concurrent callers subscribe to the same
+    /// `watch` channel, so a result delivered before or after subscription
+    /// is observed either way.
+    async fn await_completion(&self, instance_id: &str) -> Result<StateMachineResult, FsmError> {
+        let mut rx = self
+            .completion_channels
+            .get(instance_id)
+            .map(|entry| entry.subscribe())
+            .ok_or_else(|| FsmError::InstanceNotFound(instance_id.to_string()))?;
+
+        loop {
+            if let Some(result) = rx.borrow_and_update().clone() {
+                return Ok(result);
+            }
+            rx.changed().await.map_err(|_| {
+                FsmError::InstanceNotFound(format!(
+                    "{instance_id} (completion channel closed before instance finished)"
+                ))
+            })?;
+        }
     }
 
     /// Get FSM statistics
@@ -593,13 +1345,113 @@ impl StateMachine {
         let instances = self.active_instances.lock().await;
         let states = self.states.read().await;
         let transitions = self.transitions.read().await;
-        
+
         FSMStats {
             active_instances: instances.len(),
             total_states: states.len(),
             total_transitions: transitions.values().map(|v| v.len()).sum(),
             max_states: self.config.max_states,
             max_transitions: self.config.max_transitions,
+            active_schedules: self.active_schedules.len(),
+        }
+    }
+
+    /// Schedule `event` to replay on `instance_id` every `every`
+    pub async fn schedule_event(
+        &self,
+        instance_id: &str,
+        event: Event,
+        every: Duration,
+        max_repeats: Option<u64>,
+    ) -> Result<String, FsmError> {
+        // Fail fast on an unknown instance rather than silently scheduling
+        // an event that can never fire
+        self.get_instance(instance_id).await?;
+
+        let entry_id = Uuid::new_v4().to_string();
+        let entry = ScheduledEvent {
+            entry_id: entry_id.clone(),
+            instance_id: instance_id.to_string(),
+            event_type: event.event_type,
+            payload: event.payload,
+            every,
+            remaining_repeats: max_repeats,
+            next_fire: Instant::now() + every,
+        };
+
+        self.active_schedules.insert(entry_id.clone(), ());
+        self.scheduled_events.lock().await.push(entry);
+
+        Ok(entry_id)
+    }
+
+    /// Stop a recurring event scheduled with `schedule_event`
+    pub async fn cancel_scheduled(&self, entry_id: &str) {
+        if self.active_schedules.remove(entry_id).is_none() {
+            // Already gone from `active_schedules` -- either cancelled
+            // before, or (more likely) `dispatch_due_schedules` has this
+            // entry_id popped out right now and will otherwise re-insert
+            // it once it's done replaying the event. Mark the cancel so
+            // the dispatcher can still honor it when it gets there.
+            self.in_flight_cancellations.insert(entry_id.to_string(), ());
+        }
+    }
+
+    /// Pop and replay every `scheduled_events` entry whose `next_fire` has
+    /// passed, re-inserting it with its next deadline unless it was
+    /// cancelled, exhausted its repeats, or its instance has completed
+    async fn dispatch_due_schedules(&self) {
+        let now = Instant::now();
+        let due = {
+            let mut heap = self.scheduled_events.lock().await;
+            let mut due = Vec::new();
+            while matches!(heap.peek(), Some(entry) if entry.next_fire <= now) {
+                due.push(heap.pop().expect("peek just confirmed an entry exists"));
+            }
+            due
+        };
+
+        for mut entry in due {
+            if self.active_schedules.remove(&entry.entry_id).is_none() {
+                continue; // cancelled since it was scheduled
+            }
+
+            let event = Event {
+                id: Uuid::new_v4().to_string(),
+                event_type: entry.event_type.clone(),
+                payload: entry.payload.clone(),
+                timestamp: chrono::Utc::now(),
+            };
+
+            if let Err(e) = self.trigger_event(&entry.instance_id, event).await {
+                warn!("Scheduled event dispatch failed for instance {}: {}", entry.instance_id, e);
+            }
+
+            let instance_done = match self.get_instance(&entry.instance_id).await {
+                Ok(instance) => is_terminal_status(&instance.status),
+                Err(_) => true, // instance is gone; nothing left to drive
+            };
+
+            // A `cancel_scheduled` call that raced the `remove` above left
+            // its mark here instead of actually removing anything; consume
+            // it now so that race doesn't cause this schedule to be
+            // silently re-queued below as if it were never cancelled.
+            let cancelled_in_flight = self.in_flight_cancellations.remove(&entry.entry_id).is_some();
+
+            if instance_done || cancelled_in_flight {
+                continue;
+            }
+
+            if let Some(remaining) = entry.remaining_repeats {
+                if remaining <= 1 {
+                    continue; // that was the last allowed repeat
+                }
+                entry.remaining_repeats = Some(remaining - 1);
+            }
+
+            entry.next_fire = Instant::now() + entry.every;
+            self.active_schedules.insert(entry.entry_id.clone(), ());
+            self.scheduled_events.lock().await.push(entry);
         }
     }
 }
@@ -612,4 +1464,5 @@ pub struct FSMStats {
     pub total_transitions: usize,
     pub max_states: usize,
     pub max_transitions: usize,
+    pub active_schedules: usize,
 }
\ No newline at end of file
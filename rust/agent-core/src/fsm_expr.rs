@@ -0,0 +1,331 @@
+//! Boolean expression language for FSM `TransitionCondition::OnCondition`
+//! guards. Grammar: variable references (`foo`), string/numeric literals,
+//! comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`), logical `&&`/`||`/`!`, and
+//! parenthesization. A tokenizer feeds a recursive-descent parser producing
+//! an [`Expr`] AST, which [`Expr::eval`] then evaluates against a variable
+//! map pulled from the instance's `context.variables` and the triggering
+//! event's `payload`.
+
+use std::collections::HashMap;
+
+/// A parsed `OnCondition` guard expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Var(String),
+    Lit(String),
+    Compare { op: CompareOp, lhs: Box<Expr>, rhs: Box<Expr> },
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Errors produced while tokenizing, parsing, or evaluating an `OnCondition`
+/// expression
+#[derive(Debug, thiserror::Error)]
+pub enum ExprError {
+    #[error("unexpected character '{0}' at position {1}")]
+    UnexpectedChar(char, usize),
+
+    #[error("unterminated string literal starting at position {0}")]
+    UnterminatedString(usize),
+
+    #[error("unexpected end of expression, expected {0}")]
+    UnexpectedEnd(&'static str),
+
+    #[error("expected {expected}, found '{found}'")]
+    Expected { expected: &'static str, found: String },
+
+    #[error("trailing input after expression: '{0}'")]
+    TrailingInput(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Number(String),
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ExprError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '!' => {
+                if chars.get(i + 1) == Some(&'=') {
+                    tokens.push(Token::Ne);
+                    i += 2;
+                } else {
+                    tokens.push(Token::Not);
+                    i += 1;
+                }
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Eq);
+                i += 2;
+            }
+            '<' => {
+                if chars.get(i + 1) == Some(&'=') {
+                    tokens.push(Token::Le);
+                    i += 2;
+                } else {
+                    tokens.push(Token::Lt);
+                    i += 1;
+                }
+            }
+            '>' => {
+                if chars.get(i + 1) == Some(&'=') {
+                    tokens.push(Token::Ge);
+                    i += 2;
+                } else {
+                    tokens.push(Token::Gt);
+                    i += 1;
+                }
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '\'' | '"' => {
+                let quote = c;
+                let start = i;
+                i += 1;
+                let mut value = String::new();
+                loop {
+                    match chars.get(i) {
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            value.push(ch);
+                            i += 1;
+                        }
+                        None => return Err(ExprError::UnterminatedString(start)),
+                    }
+                }
+                tokens.push(Token::String(value));
+            }
+            _ if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while chars.get(i).is_some_and(|ch| ch.is_ascii_digit() || *ch == '.') {
+                    i += 1;
+                }
+                tokens.push(Token::Number(chars[start..i].iter().collect()));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                i += 1;
+                while chars.get(i).is_some_and(|ch| ch.is_alphanumeric() || *ch == '_' || *ch == '.') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => return Err(ExprError::UnexpectedChar(c, i)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the token stream, one method per precedence
+/// level: `or` -> `and` -> `unary` -> `comparison` -> `primary`
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.advance();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.parse_unary()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.advance();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ExprError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.advance();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ExprError> {
+        let lhs = self.parse_primary()?;
+
+        let op = match self.peek() {
+            Some(Token::Eq) => CompareOp::Eq,
+            Some(Token::Ne) => CompareOp::Ne,
+            Some(Token::Lt) => CompareOp::Lt,
+            Some(Token::Le) => CompareOp::Le,
+            Some(Token::Gt) => CompareOp::Gt,
+            Some(Token::Ge) => CompareOp::Ge,
+            _ => return Ok(lhs),
+        };
+        self.advance();
+
+        let rhs = self.parse_primary()?;
+        Ok(Expr::Compare { op, lhs: Box::new(lhs), rhs: Box::new(rhs) })
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ExprError> {
+        match self.advance() {
+            Some(Token::Ident(name)) => Ok(Expr::Var(name)),
+            Some(Token::String(value)) => Ok(Expr::Lit(value)),
+            Some(Token::Number(value)) => Ok(Expr::Lit(value)),
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    Some(other) => Err(ExprError::Expected { expected: "')'", found: format!("{:?}", other) }),
+                    None => Err(ExprError::UnexpectedEnd("')'")),
+                }
+            }
+            Some(other) => Err(ExprError::Expected { expected: "a value", found: format!("{:?}", other) }),
+            None => Err(ExprError::UnexpectedEnd("a value")),
+        }
+    }
+}
+
+/// Parse `source` into an [`Expr`] AST
+pub fn parse(source: &str) -> Result<Expr, ExprError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+
+    if parser.pos != parser.tokens.len() {
+        let remaining = parser.tokens[parser.pos..].iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>().join(" ");
+        return Err(ExprError::TrailingInput(remaining));
+    }
+
+    Ok(expr)
+}
+
+impl Expr {
+    /// Evaluate this expression against `variables`, treating a missing
+    /// variable as an empty string (and therefore `false` when read as a
+    /// bool). Compares numerically when both sides parse as `f64`, and
+    /// lexicographically otherwise.
+    pub fn eval(&self, variables: &HashMap<String, String>) -> bool {
+        match self {
+            Expr::Var(name) => truthy(&resolve(name, variables)),
+            Expr::Lit(value) => truthy(value),
+            Expr::Not(inner) => !inner.eval(variables),
+            Expr::And(lhs, rhs) => lhs.eval(variables) && rhs.eval(variables),
+            Expr::Or(lhs, rhs) => lhs.eval(variables) || rhs.eval(variables),
+            Expr::Compare { op, lhs, rhs } => {
+                let lhs = self.resolve_operand(lhs, variables);
+                let rhs = self.resolve_operand(rhs, variables);
+                compare(op, &lhs, &rhs)
+            }
+        }
+    }
+
+    fn resolve_operand(&self, operand: &Expr, variables: &HashMap<String, String>) -> String {
+        match operand {
+            Expr::Var(name) => resolve(name, variables),
+            Expr::Lit(value) => value.clone(),
+            // A comparison operand is always a `Var` or `Lit` from the
+            // grammar; anything else can't appear here, so fall back to its
+            // own truthiness string rather than panicking on a malformed AST
+            other => if other.eval(variables) { "true".to_string() } else { "false".to_string() },
+        }
+    }
+}
+
+fn resolve(name: &str, variables: &HashMap<String, String>) -> String {
+    variables.get(name).cloned().unwrap_or_default()
+}
+
+fn truthy(value: &str) -> bool {
+    !value.is_empty() && value != "0" && value != "false"
+}
+
+fn compare(op: &CompareOp, lhs: &str, rhs: &str) -> bool {
+    if let (Ok(lhs), Ok(rhs)) = (lhs.parse::<f64>(), rhs.parse::<f64>()) {
+        return match op {
+            CompareOp::Eq => lhs == rhs,
+            CompareOp::Ne => lhs != rhs,
+            CompareOp::Lt => lhs < rhs,
+            CompareOp::Le => lhs <= rhs,
+            CompareOp::Gt => lhs > rhs,
+            CompareOp::Ge => lhs >= rhs,
+        };
+    }
+
+    match op {
+        CompareOp::Eq => lhs == rhs,
+        CompareOp::Ne => lhs != rhs,
+        CompareOp::Lt => lhs < rhs,
+        CompareOp::Le => lhs <= rhs,
+        CompareOp::Gt => lhs > rhs,
+        CompareOp::Ge => lhs >= rhs,
+    }
+}
@@ -0,0 +1,566 @@
+// This file is @generated by prost-build and tonic-build from `proto/agent_core.proto`.
+// Do not edit by hand — regenerate with `cargo build --features gen-proto`
+// and commit the resulting diff.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecuteCodeRequest {
+    #[prost(string, tag = "1")]
+    pub user_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub tenant_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub session_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub code: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub language: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "6")]
+    pub timeout_seconds: u32,
+    #[prost(uint32, tag = "7")]
+    pub memory_limit_mb: u32,
+    #[prost(uint32, tag = "8")]
+    pub cpu_limit_seconds: u32,
+    #[prost(map = "string, string", tag = "9")]
+    pub environment:
+        ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "10")]
+    pub allowed_hosts: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "11")]
+    pub cacheable: bool,
+    #[prost(string, tag = "12")]
+    pub priority: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecuteCodeResponse {
+    #[prost(string, tag = "1")]
+    pub execution_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub output: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub error_message: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "5")]
+    pub execution_time_ms: u64,
+    #[prost(uint32, tag = "6")]
+    pub tokens_used: u32,
+    #[prost(double, tag = "7")]
+    pub cost_usd: f64,
+    #[prost(string, repeated, tag = "8")]
+    pub security_violations: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "9")]
+    pub cached: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamExecuteCodeResponse {
+    #[prost(oneof = "stream_execute_code_response::Event", tags = "1, 2, 3")]
+    pub event: ::core::option::Option<stream_execute_code_response::Event>,
+}
+/// Nested message and enum types in `StreamExecuteCodeResponse`.
+pub mod stream_execute_code_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OutputChunk {
+        #[prost(string, tag = "1")]
+        pub stream: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub chunk: ::prost::alloc::string::String,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StateChange {
+        #[prost(string, tag = "1")]
+        pub state: ::prost::alloc::string::String,
+        #[prost(float, tag = "2")]
+        pub progress: f32,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ExecutionSummary {
+        #[prost(string, tag = "1")]
+        pub execution_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub status: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "3")]
+        pub tokens_used: u32,
+        #[prost(double, tag = "4")]
+        pub cost_usd: f64,
+        #[prost(string, repeated, tag = "5")]
+        pub security_violations: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(bool, tag = "6")]
+        pub cached: bool,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "1")]
+        Output(OutputChunk),
+        #[prost(message, tag = "2")]
+        StateChanged(StateChange),
+        #[prost(message, tag = "3")]
+        Summary(ExecutionSummary),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetStatusRequest {
+    #[prost(string, tag = "1")]
+    pub execution_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetStatusResponse {
+    #[prost(string, tag = "1")]
+    pub execution_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(float, tag = "3")]
+    pub progress: f32,
+    #[prost(string, tag = "4")]
+    pub current_state: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub started_at: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub estimated_completion: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelExecutionRequest {
+    #[prost(string, tag = "1")]
+    pub execution_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelExecutionResponse {
+    #[prost(bool, tag = "1")]
+    pub cancelled: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMetricsRequest {
+    #[prost(bool, tag = "1")]
+    pub include_detailed: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMetricsResponse {
+    #[prost(uint64, tag = "1")]
+    pub total_executions: u64,
+    #[prost(double, tag = "2")]
+    pub success_rate: f64,
+    #[prost(uint64, tag = "3")]
+    pub average_duration_ms: u64,
+    #[prost(uint32, tag = "4")]
+    pub active_executions: u32,
+    #[prost(string, tag = "5")]
+    pub system_health: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod agent_core_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+
+    #[derive(Debug, Clone)]
+    pub struct AgentCoreClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl AgentCoreClient<tonic::transport::Channel> {
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> AgentCoreClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+
+        pub async fn execute_code(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExecuteCodeRequest>,
+        ) -> Result<tonic::Response<super::ExecuteCodeResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(tonic::Code::Unknown, format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/agent_core.AgentCore/ExecuteCode");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("agent_core.AgentCore", "ExecuteCode"));
+            self.inner.unary(req, path, codec).await
+        }
+
+        pub async fn stream_execute_code(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExecuteCodeRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::StreamExecuteCodeResponse>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(tonic::Code::Unknown, format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/agent_core.AgentCore/StreamExecuteCode");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("agent_core.AgentCore", "StreamExecuteCode"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+
+        pub async fn get_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetStatusRequest>,
+        ) -> Result<tonic::Response<super::GetStatusResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(tonic::Code::Unknown, format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/agent_core.AgentCore/GetStatus");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("agent_core.AgentCore", "GetStatus"));
+            self.inner.unary(req, path, codec).await
+        }
+
+        pub async fn cancel_execution(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CancelExecutionRequest>,
+        ) -> Result<tonic::Response<super::CancelExecutionResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(tonic::Code::Unknown, format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/agent_core.AgentCore/CancelExecution");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("agent_core.AgentCore", "CancelExecution"));
+            self.inner.unary(req, path, codec).await
+        }
+
+        pub async fn get_metrics(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetMetricsRequest>,
+        ) -> Result<tonic::Response<super::GetMetricsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(tonic::Code::Unknown, format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/agent_core.AgentCore/GetMetrics");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("agent_core.AgentCore", "GetMetrics"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod agent_core_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+
+    /// Generated trait containing gRPC methods that should be implemented for use with AgentCoreServer.
+    #[tonic::async_trait]
+    pub trait AgentCore: Send + Sync + 'static {
+        async fn execute_code(
+            &self,
+            request: tonic::Request<super::ExecuteCodeRequest>,
+        ) -> std::result::Result<tonic::Response<super::ExecuteCodeResponse>, tonic::Status>;
+
+        /// Server streaming response type for the StreamExecuteCode method.
+        type StreamExecuteCodeStream: futures_core::Stream<
+                Item = std::result::Result<super::StreamExecuteCodeResponse, tonic::Status>,
+            > + Send
+            + 'static;
+        async fn stream_execute_code(
+            &self,
+            request: tonic::Request<super::ExecuteCodeRequest>,
+        ) -> std::result::Result<tonic::Response<Self::StreamExecuteCodeStream>, tonic::Status>;
+
+        async fn get_status(
+            &self,
+            request: tonic::Request<super::GetStatusRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetStatusResponse>, tonic::Status>;
+
+        async fn cancel_execution(
+            &self,
+            request: tonic::Request<super::CancelExecutionRequest>,
+        ) -> std::result::Result<tonic::Response<super::CancelExecutionResponse>, tonic::Status>;
+
+        async fn get_metrics(
+            &self,
+            request: tonic::Request<super::GetMetricsRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetMetricsResponse>, tonic::Status>;
+    }
+
+    /// The `agent_core.AgentCore` server, generated from the `AgentCore` service definition.
+    #[derive(Debug)]
+    pub struct AgentCoreServer<T: AgentCore> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: AgentCore> AgentCoreServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AgentCoreServer<T>
+    where
+        T: AgentCore,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/agent_core.AgentCore/ExecuteCode" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExecuteCodeSvc<T: AgentCore>(pub Arc<T>);
+                    impl<T: AgentCore> tonic::server::UnaryService<super::ExecuteCodeRequest> for ExecuteCodeSvc<T> {
+                        type Response = super::ExecuteCodeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExecuteCodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            Box::pin(async move { (*inner).execute_code(request).await })
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    Box::pin(async move {
+                        let inner = inner.0;
+                        let method = ExecuteCodeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    })
+                }
+                "/agent_core.AgentCore/StreamExecuteCode" => {
+                    #[allow(non_camel_case_types)]
+                    struct StreamExecuteCodeSvc<T: AgentCore>(pub Arc<T>);
+                    impl<T: AgentCore> tonic::server::ServerStreamingService<super::ExecuteCodeRequest>
+                        for StreamExecuteCodeSvc<T>
+                    {
+                        type Response = super::StreamExecuteCodeResponse;
+                        type ResponseStream = T::StreamExecuteCodeStream;
+                        type Future = BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExecuteCodeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            Box::pin(async move { (*inner).stream_execute_code(request).await })
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    Box::pin(async move {
+                        let inner = inner.0;
+                        let method = StreamExecuteCodeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    })
+                }
+                "/agent_core.AgentCore/GetStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetStatusSvc<T: AgentCore>(pub Arc<T>);
+                    impl<T: AgentCore> tonic::server::UnaryService<super::GetStatusRequest> for GetStatusSvc<T> {
+                        type Response = super::GetStatusResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            Box::pin(async move { (*inner).get_status(request).await })
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    Box::pin(async move {
+                        let inner = inner.0;
+                        let method = GetStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    })
+                }
+                "/agent_core.AgentCore/CancelExecution" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelExecutionSvc<T: AgentCore>(pub Arc<T>);
+                    impl<T: AgentCore> tonic::server::UnaryService<super::CancelExecutionRequest>
+                        for CancelExecutionSvc<T>
+                    {
+                        type Response = super::CancelExecutionResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CancelExecutionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            Box::pin(async move { (*inner).cancel_execution(request).await })
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    Box::pin(async move {
+                        let inner = inner.0;
+                        let method = CancelExecutionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    })
+                }
+                "/agent_core.AgentCore/GetMetrics" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMetricsSvc<T: AgentCore>(pub Arc<T>);
+                    impl<T: AgentCore> tonic::server::UnaryService<super::GetMetricsRequest> for GetMetricsSvc<T> {
+                        type Response = super::GetMetricsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMetricsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            Box::pin(async move { (*inner).get_metrics(request).await })
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    Box::pin(async move {
+                        let inner = inner.0;
+                        let method = GetMetricsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    })
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: AgentCore> Clone for AgentCoreServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    impl<T: AgentCore> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(Arc::clone(&self.0))
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: AgentCore> tonic::server::NamedService for AgentCoreServer<T> {
+        const NAME: &'static str = "agent_core.AgentCore";
+    }
+}
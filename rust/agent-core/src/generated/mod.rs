@@ -0,0 +1,15 @@
+//! Checked-in output of `tonic-build`/`prost-build` over `proto/*.proto`.
+//! Regenerate with `cargo build --features gen-proto` and commit the diff;
+//! a plain build just compiles these files as-is, no `protoc` required.
+
+pub mod agent_core {
+    include!("agent_core.rs");
+}
+
+pub mod health {
+    include!("grpc.health.v1.rs");
+}
+
+pub mod ext_authz {
+    include!("ext_authz.rs");
+}
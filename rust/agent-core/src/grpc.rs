@@ -1,79 +1,113 @@
 use anyhow::Result;
+use futures_core::Stream;
+use std::collections::HashMap;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::{mpsc, watch, Mutex};
+use tokio_stream::wrappers::{ReceiverStream, WatchStream};
+use tokio_stream::StreamExt;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, warn};
 
-use crate::execution::{ExecutionEngine, AgentExecutionRequest, CodeLanguage};
+use crate::enforcement::TaskPriority;
+use crate::execution::{
+    ExecutionEngine, ExecutionEvent, AgentExecutionRequest, CodeLanguage, OutputStream,
+};
+use crate::generated::agent_core::{
+    self as proto, agent_core_server, CancelExecutionRequest, CancelExecutionResponse,
+    ExecuteCodeRequest, ExecuteCodeResponse, GetMetricsRequest, GetMetricsResponse,
+    GetStatusRequest, GetStatusResponse, StreamExecuteCodeResponse,
+};
+use crate::generated::health::{
+    health_check_response::ServingStatus, health_server, HealthCheckRequest, HealthCheckResponse,
+};
 use crate::metrics::MetricsCollector;
-
-// Generated protobuf code would go here
-// For now, we'll define the service manually
-
-/// gRPC service for agent core
-pub struct AgentCoreService {
-    execution_engine: Arc<ExecutionEngine>,
-    metrics: Arc<MetricsCollector>,
+use crate::security::{AuthenticatedIdentity, SecurityManager};
+
+/// Tonic interceptor that authenticates every request via an `authorization:
+/// Bearer <token>` metadata entry, verifies it through `SecurityManager`, and
+/// injects the resulting `AuthenticatedIdentity` into the request's
+/// extensions so handlers no longer have to trust `user_id`/`tenant_id`
+/// fields supplied in the request body. A no-op when `require_auth` is
+/// false, so deployments can opt into enforced multi-tenant isolation.
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    security: Arc<SecurityManager>,
+    require_auth: bool,
 }
 
-/// Execute code request
-#[derive(Debug, Clone)]
-pub struct ExecuteCodeRequest {
-    pub user_id: String,
-    pub tenant_id: String,
-    pub session_id: String,
-    pub code: String,
-    pub language: String,
-    pub timeout_seconds: u32,
-    pub memory_limit_mb: u32,
-    pub cpu_limit_seconds: u32,
-    pub environment: std::collections::HashMap<String, String>,
-    pub allowed_hosts: Vec<String>,
+impl AuthInterceptor {
+    pub fn new(security: Arc<SecurityManager>, require_auth: bool) -> Self {
+        Self {
+            security,
+            require_auth,
+        }
+    }
 }
 
-/// Execute code response
-#[derive(Debug, Clone)]
-pub struct ExecuteCodeResponse {
-    pub execution_id: String,
-    pub status: String,
-    pub output: String,
-    pub error_message: String,
-    pub execution_time_ms: u64,
-    pub tokens_used: u32,
-    pub cost_usd: f64,
-    pub security_violations: Vec<String>,
-}
+impl tonic::service::Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if !self.require_auth {
+            return Ok(request);
+        }
 
-/// Get status request
-#[derive(Debug, Clone)]
-pub struct GetStatusRequest {
-    pub execution_id: String,
-}
+        let header = request
+            .metadata()
+            .get("authorization")
+            .ok_or_else(|| Status::unauthenticated("missing authorization metadata"))?;
 
-/// Get status response
-#[derive(Debug, Clone)]
-pub struct GetStatusResponse {
-    pub execution_id: String,
-    pub status: String,
-    pub progress: f32,
-    pub current_state: String,
-    pub started_at: String,
-    pub estimated_completion: String,
+        let header = header
+            .to_str()
+            .map_err(|_| Status::unauthenticated("authorization metadata is not valid ASCII"))?;
+
+        let token = header
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| Status::unauthenticated("authorization metadata must use the Bearer scheme"))?;
+
+        let identity = self
+            .security
+            .authenticate_bearer_token(token)
+            .map_err(|e| Status::unauthenticated(format!("invalid bearer token: {e}")))?;
+
+        request.extensions_mut().insert(identity);
+        Ok(request)
+    }
 }
 
-/// Get metrics request
-#[derive(Debug, Clone)]
-pub struct GetMetricsRequest {
-    pub include_detailed: bool,
+/// Response stream type for the `stream_execute_code` server-streaming RPC
+pub type StreamExecuteCodeStream =
+    Pin<Box<dyn Stream<Item = Result<StreamExecuteCodeResponse, Status>> + Send + 'static>>;
+
+impl From<ExecutionEvent> for StreamExecuteCodeResponse {
+    fn from(event: ExecutionEvent) -> Self {
+        use proto::stream_execute_code_response::Event;
+
+        let event = match event {
+            ExecutionEvent::StateChanged { status, progress } => {
+                Event::StateChanged(proto::stream_execute_code_response::StateChange {
+                    state: format!("{:?}", status),
+                    progress,
+                })
+            }
+            ExecutionEvent::Output { stream, chunk } => {
+                Event::Output(proto::stream_execute_code_response::OutputChunk {
+                    stream: match stream {
+                        OutputStream::Stdout => "stdout".to_string(),
+                        OutputStream::Stderr => "stderr".to_string(),
+                    },
+                    chunk,
+                })
+            }
+        };
+
+        StreamExecuteCodeResponse { event: Some(event) }
+    }
 }
 
-/// Get metrics response
-#[derive(Debug, Clone)]
-pub struct GetMetricsResponse {
-    pub total_executions: u64,
-    pub success_rate: f64,
-    pub average_duration_ms: u64,
-    pub active_executions: u32,
-    pub system_health: String,
+/// gRPC service for agent core
+pub struct AgentCoreService {
+    execution_engine: Arc<ExecutionEngine>,
+    metrics: Arc<MetricsCollector>,
 }
 
 impl AgentCoreService {
@@ -83,7 +117,7 @@ impl AgentCoreService {
         metrics: Arc<MetricsCollector>,
     ) -> Self {
         info!("Initializing Agent Core gRPC service");
-        
+
         Self {
             execution_engine,
             metrics,
@@ -95,11 +129,108 @@ impl AgentCoreService {
         &self,
         request: Request<ExecuteCodeRequest>,
     ) -> Result<Response<ExecuteCodeResponse>, Status> {
-        let req = request.into_inner();
-        
+        let identity = request.extensions().get::<AuthenticatedIdentity>().cloned();
+        let mut req = request.into_inner();
+        if let Some(identity) = identity {
+            req.user_id = identity.user_id;
+            req.tenant_id = identity.tenant_id;
+        }
+
         debug!("Received execute_code request for user: {}", req.user_id);
 
-        // Validate request
+        let execution_request = Self::build_execution_request(req)?;
+
+        // Execute code
+        match self.execution_engine.execute_agent_code(execution_request).await {
+            Ok(result) => {
+                let response = ExecuteCodeResponse {
+                    execution_id: result.execution_id,
+                    status: format!("{:?}", result.status),
+                    output: result.output,
+                    error_message: result.error_message.unwrap_or_default(),
+                    execution_time_ms: result.execution_time.as_millis() as u64,
+                    tokens_used: result.tokens_used,
+                    cost_usd: result.cost_usd,
+                    security_violations: result.security_violations,
+                    cached: result.cached,
+                };
+
+                Ok(Response::new(response))
+            }
+            Err(e) => {
+                error!("Execution failed: {}", e);
+                Err(Status::internal(format!("Execution failed: {}", e)))
+            }
+        }
+    }
+
+    /// Execute agent code, streaming state transitions, output chunks, and a
+    /// final summary as they become available instead of blocking until the
+    /// run completes
+    pub async fn stream_execute_code(
+        &self,
+        request: Request<ExecuteCodeRequest>,
+    ) -> Result<Response<StreamExecuteCodeStream>, Status> {
+        let identity = request.extensions().get::<AuthenticatedIdentity>().cloned();
+        let mut req = request.into_inner();
+        if let Some(identity) = identity {
+            req.user_id = identity.user_id;
+            req.tenant_id = identity.tenant_id;
+        }
+
+        debug!("Received stream_execute_code request for user: {}", req.user_id);
+
+        let execution_request = Self::build_execution_request(req)?;
+
+        let (events_tx, mut events_rx) = mpsc::channel(32);
+        let (response_tx, response_rx) = mpsc::channel(32);
+        let execution_engine = self.execution_engine.clone();
+
+        tokio::spawn(async move {
+            let events_forwarder = {
+                let response_tx = response_tx.clone();
+                async move {
+                    while let Some(event) = events_rx.recv().await {
+                        if response_tx.send(Ok(event.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            };
+
+            let execution = execution_engine.execute_agent_code_streaming(execution_request, events_tx);
+
+            let (_, result) = tokio::join!(events_forwarder, execution);
+
+            let summary = match result {
+                Ok(result) => Ok(StreamExecuteCodeResponse {
+                    event: Some(proto::stream_execute_code_response::Event::Summary(
+                        proto::stream_execute_code_response::ExecutionSummary {
+                            execution_id: result.execution_id,
+                            status: format!("{:?}", result.status),
+                            tokens_used: result.tokens_used,
+                            cost_usd: result.cost_usd,
+                            security_violations: result.security_violations,
+                            cached: result.cached,
+                        },
+                    )),
+                }),
+                Err(e) => {
+                    error!("Streamed execution failed: {}", e);
+                    Err(Status::internal(format!("Execution failed: {}", e)))
+                }
+            };
+
+            let _ = response_tx.send(summary).await;
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(response_rx))))
+    }
+
+    /// Validate an `ExecuteCodeRequest` and translate it into the execution
+    /// engine's internal request type. Shared by `execute_code` and
+    /// `stream_execute_code` so both RPCs apply the same validation.
+    fn build_execution_request(req: ExecuteCodeRequest) -> Result<AgentExecutionRequest, Status> {
         if req.code.is_empty() {
             return Err(Status::invalid_argument("Code cannot be empty"));
         }
@@ -108,7 +239,6 @@ impl AgentCoreService {
             return Err(Status::invalid_argument("User ID and Tenant ID are required"));
         }
 
-        // Parse language
         let language = match req.language.to_lowercase().as_str() {
             "python" => CodeLanguage::Python,
             "javascript" | "js" => CodeLanguage::JavaScript,
@@ -120,8 +250,19 @@ impl AgentCoreService {
             }
         };
 
-        // Create execution request
-        let execution_request = AgentExecutionRequest {
+        let priority = match req.priority.to_lowercase().as_str() {
+            "" | "normal" => TaskPriority::Normal,
+            "low" => TaskPriority::Low,
+            "high" => TaskPriority::High,
+            "critical" => TaskPriority::Critical,
+            _ => {
+                return Err(Status::invalid_argument(
+                    format!("Unsupported priority: {}", req.priority)
+                ));
+            }
+        };
+
+        Ok(AgentExecutionRequest {
             user_id: req.user_id,
             tenant_id: req.tenant_id,
             session_id: req.session_id,
@@ -132,29 +273,9 @@ impl AgentCoreService {
             cpu_limit: (req.cpu_limit_seconds as u64) * 1_000_000_000, // Convert seconds to nanoseconds
             environment: req.environment,
             allowed_hosts: req.allowed_hosts,
-        };
-
-        // Execute code
-        match self.execution_engine.execute_agent_code(execution_request).await {
-            Ok(result) => {
-                let response = ExecuteCodeResponse {
-                    execution_id: result.execution_id,
-                    status: format!("{:?}", result.status),
-                    output: result.output,
-                    error_message: result.error_message.unwrap_or_default(),
-                    execution_time_ms: result.execution_time.as_millis() as u64,
-                    tokens_used: result.tokens_used,
-                    cost_usd: result.cost_usd,
-                    security_violations: result.security_violations,
-                };
-
-                Ok(Response::new(response))
-            }
-            Err(e) => {
-                error!("Execution failed: {}", e);
-                Err(Status::internal(format!("Execution failed: {}", e)))
-            }
-        }
+            cacheable: req.cacheable,
+            priority,
+        })
     }
 
     /// Get execution status
@@ -163,12 +284,12 @@ impl AgentCoreService {
         request: Request<GetStatusRequest>,
     ) -> Result<Response<GetStatusResponse>, Status> {
         let req = request.into_inner();
-        
+
         debug!("Received get_status request for execution: {}", req.execution_id);
 
         // Get active executions
         let active_executions = self.execution_engine.get_active_executions().await;
-        
+
         // Find the requested execution
         if let Some(execution) = active_executions.iter().find(|e| e.execution_id == req.execution_id) {
             let response = GetStatusResponse {
@@ -186,13 +307,35 @@ impl AgentCoreService {
         }
     }
 
+    /// Cancel an in-flight execution, signalling its sandbox to abort
+    /// instead of waiting for `timeout`. Unlike `get_status`, an execution
+    /// that is not found is not an error: one that has already finished (or
+    /// never existed) just gets `cancelled: false` back.
+    pub async fn cancel_execution(
+        &self,
+        request: Request<CancelExecutionRequest>,
+    ) -> Result<Response<CancelExecutionResponse>, Status> {
+        let req = request.into_inner();
+
+        debug!("Received cancel_execution request for execution: {}", req.execution_id);
+
+        if req.execution_id.is_empty() {
+            return Err(Status::invalid_argument("Execution ID is required"));
+        }
+
+        let outcome = self.execution_engine.cancel_execution(&req.execution_id).await;
+        let cancelled = outcome != crate::execution::CancelOutcome::NotFound;
+
+        Ok(Response::new(CancelExecutionResponse { cancelled }))
+    }
+
     /// Get system metrics
     pub async fn get_metrics(
         &self,
         request: Request<GetMetricsRequest>,
     ) -> Result<Response<GetMetricsResponse>, Status> {
         let _req = request.into_inner();
-        
+
         debug!("Received get_metrics request");
 
         // Get execution statistics
@@ -212,14 +355,7 @@ impl AgentCoreService {
 
     /// Calculate execution progress based on status
     fn calculate_progress(&self, status: &crate::execution::ExecutionEngineStatus) -> f32 {
-        match status {
-            crate::execution::ExecutionEngineStatus::Initializing => 0.1,
-            crate::execution::ExecutionEngineStatus::PolicyCheck => 0.2,
-            crate::execution::ExecutionEngineStatus::Executing => 0.6,
-            crate::execution::ExecutionEngineStatus::Validating => 0.9,
-            crate::execution::ExecutionEngineStatus::Completed => 1.0,
-            crate::execution::ExecutionEngineStatus::Failed => 1.0,
-        }
+        status.progress()
     }
 
     /// Determine system health based on metrics
@@ -234,90 +370,123 @@ impl AgentCoreService {
         }
     }
 
-    /// Convert to tonic service (this would be generated by tonic-build in real implementation)
-    pub fn into_service(self) -> AgentCoreServiceImpl {
-        AgentCoreServiceImpl { inner: Arc::new(self) }
-    }
-}
-
-/// Implementation wrapper for tonic service
-pub struct AgentCoreServiceImpl {
-    inner: Arc<AgentCoreService>,
-}
-
-// In a real implementation, this would be generated by tonic-build
-// For now, we'll provide a mock implementation
-impl AgentCoreServiceImpl {
-    pub async fn execute_code_mock(
+    /// Poll execution metrics on an interval and reflect them into the
+    /// `grpc.health.v1.Health` status map, so a degrading `success_rate`
+    /// flips the reported status to `NOT_SERVING` and wakes any `Watch`
+    /// streams rather than requiring a client to poll `Check`
+    pub async fn run_health_updater(
         &self,
-        request: tonic::Request<()>,
-    ) -> Result<tonic::Response<()>, tonic::Status> {
-        // Mock implementation - would be replaced by generated code
-        info!("Mock gRPC execute_code called");
-        Ok(tonic::Response::new(()))
+        health: Arc<HealthService>,
+        interval: std::time::Duration,
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let metrics_summary = self.metrics.get_metrics_summary().await;
+            let status = if metrics_summary.success_rate > 0.8 {
+                ServingStatus::Serving
+            } else {
+                ServingStatus::NotServing
+            };
+            health.set_status("agent-core", status).await;
+            health.set_status("", status).await;
+        }
     }
 }
 
-// Mock trait for the generated service
-// In real implementation, this would be generated by protobuf
-pub trait AgentCore {
+#[tonic::async_trait]
+impl agent_core_server::AgentCore for AgentCoreService {
     async fn execute_code(
         &self,
         request: tonic::Request<ExecuteCodeRequest>,
-    ) -> Result<tonic::Response<ExecuteCodeResponse>, tonic::Status>;
-
-    async fn get_status(
-        &self,
-        request: tonic::Request<GetStatusRequest>,
-    ) -> Result<tonic::Response<GetStatusResponse>, tonic::Status>;
-
-    async fn get_metrics(
-        &self,
-        request: tonic::Request<GetMetricsRequest>,
-    ) -> Result<tonic::Response<GetMetricsResponse>, tonic::Status>;
-}
+    ) -> Result<tonic::Response<ExecuteCodeResponse>, tonic::Status> {
+        AgentCoreService::execute_code(self, request).await
+    }
 
-#[tonic::async_trait]
-impl AgentCore for AgentCoreServiceImpl {
-    async fn execute_code(
+    type StreamExecuteCodeStream = StreamExecuteCodeStream;
+    async fn stream_execute_code(
         &self,
         request: tonic::Request<ExecuteCodeRequest>,
-    ) -> Result<tonic::Response<ExecuteCodeResponse>, tonic::Status> {
-        self.inner.execute_code(request).await
+    ) -> Result<tonic::Response<Self::StreamExecuteCodeStream>, tonic::Status> {
+        AgentCoreService::stream_execute_code(self, request).await
     }
 
     async fn get_status(
         &self,
         request: tonic::Request<GetStatusRequest>,
     ) -> Result<tonic::Response<GetStatusResponse>, tonic::Status> {
-        self.inner.get_status(request).await
+        AgentCoreService::get_status(self, request).await
+    }
+
+    async fn cancel_execution(
+        &self,
+        request: tonic::Request<CancelExecutionRequest>,
+    ) -> Result<tonic::Response<CancelExecutionResponse>, tonic::Status> {
+        AgentCoreService::cancel_execution(self, request).await
     }
 
     async fn get_metrics(
         &self,
         request: tonic::Request<GetMetricsRequest>,
     ) -> Result<tonic::Response<GetMetricsResponse>, tonic::Status> {
-        self.inner.get_metrics(request).await
+        AgentCoreService::get_metrics(self, request).await
     }
 }
 
-/// Health check service
-pub struct HealthService;
-
-#[derive(Debug, Clone)]
-pub struct HealthCheckRequest {
-    pub service: String,
-}
-
-#[derive(Debug, Clone)]
-pub struct HealthCheckResponse {
-    pub status: String,
-    pub message: String,
+/// Response stream type for the `Watch` server-streaming RPC
+pub type WatchHealthStream =
+    Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send + 'static>>;
+
+/// Implementation of the standard `grpc.health.v1.Health` service: `Check`
+/// (unary) and `Watch` (server-streaming), so load balancers, Kubernetes
+/// probes, and `grpc_health_probe` can talk to this server instead of a
+/// bespoke health RPC. Each service name is backed by a `watch::Sender` so
+/// `Watch` can push every subsequent transition to long-lived subscribers.
+pub struct HealthService {
+    statuses: Mutex<HashMap<String, watch::Sender<ServingStatus>>>,
 }
 
 impl HealthService {
+    /// Create a new registry seeded with the overall ("") and "agent-core"
+    /// services, both initially `SERVING`
     pub fn new() -> Self {
-        Self
+        let mut statuses = HashMap::new();
+        statuses.insert(String::new(), watch::channel(ServingStatus::Serving).0);
+        statuses.insert("agent-core".to_string(), watch::channel(ServingStatus::Serving).0);
+
+        Self {
+            statuses: Mutex::new(statuses),
+        }
+    }
+
+    /// Set the status for `service`, registering it if unseen, and notify
+    /// any active `Watch` subscribers of the transition
+    pub async fn set_status(&self, service: &str, status: ServingStatus) {
+        let mut statuses = self.statuses.lock().await;
+        match statuses.get(service) {
+            Some(tx) => {
+                let _ = tx.send(status);
+            }
+            None => {
+                statuses.insert(service.to_string(), watch::channel(status).0);
+            }
+        }
+    }
+
+    async fn current_status(&self, service: &str) -> ServingStatus {
+        let statuses = self.statuses.lock().await;
+        statuses
+            .get(service)
+            .map(|tx| *tx.borrow())
+            .unwrap_or(ServingStatus::ServiceUnknown)
+    }
+
+    async fn subscribe(&self, service: &str) -> watch::Receiver<ServingStatus> {
+        let mut statuses = self.statuses.lock().await;
+        statuses
+            .entry(service.to_string())
+            .or_insert_with(|| watch::channel(ServingStatus::ServiceUnknown).0)
+            .subscribe()
     }
 
     pub async fn check(
@@ -325,36 +494,76 @@ impl HealthService {
         request: Request<HealthCheckRequest>,
     ) -> Result<Response<HealthCheckResponse>, Status> {
         let req = request.into_inner();
-        
+
         debug!("Health check requested for service: {}", req.service);
 
-        // Perform health checks
-        let (status, message) = match req.service.as_str() {
-            "agent-core" => {
-                // Check if core services are running
-                ("SERVING".to_string(), "Agent core is healthy".to_string())
-            }
-            "" => {
-                // Overall health check
-                ("SERVING".to_string(), "All services are healthy".to_string())
-            }
-            _ => {
-                ("NOT_FOUND".to_string(), format!("Unknown service: {}", req.service))
-            }
-        };
+        let status = self.current_status(&req.service).await;
+        Ok(Response::new(HealthCheckResponse { status: status as i32 }))
+    }
 
-        let response = HealthCheckResponse { status, message };
-        Ok(Response::new(response))
+    /// Stream the current status immediately, then every subsequent
+    /// transition, until the client disconnects. Matches `grpc.health.v1`
+    /// semantics: an unknown service streams `SERVICE_UNKNOWN` rather than
+    /// returning an error, since the service may register after the watch
+    /// is established.
+    pub async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<WatchHealthStream>, Status> {
+        let req = request.into_inner();
+
+        debug!("Health watch requested for service: {}", req.service);
+
+        let rx = self.subscribe(&req.service).await;
+        let stream = WatchStream::new(rx).map(|status| Ok(HealthCheckResponse { status: status as i32 }));
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[tonic::async_trait]
+impl health_server::Health for HealthService {
+    async fn check(
+        &self,
+        request: tonic::Request<HealthCheckRequest>,
+    ) -> Result<tonic::Response<HealthCheckResponse>, tonic::Status> {
+        HealthService::check(self, request).await
+    }
+
+    type WatchStream = WatchHealthStream;
+    async fn watch(
+        &self,
+        request: tonic::Request<HealthCheckRequest>,
+    ) -> Result<tonic::Response<Self::WatchStream>, tonic::Status> {
+        HealthService::watch(self, request).await
     }
 }
 
+/// Encoded `FileDescriptorSet` covering `agent_core.proto`,
+/// `health.proto` and `ext_authz.proto`, committed alongside the rest of `src/generated` and
+/// regenerated together with it via `cargo build --features gen-proto`.
+/// Backs the `grpc.reflection.v1.ServerReflection` service so tools like
+/// `grpcurl` can introspect the API without a local copy of the `.proto`
+/// files.
+const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("generated/agent_core_descriptor.bin");
+
 /// gRPC server configuration
+#[derive(Clone)]
 pub struct GrpcServerConfig {
     pub addr: std::net::SocketAddr,
     pub max_connections: usize,
     pub request_timeout: std::time::Duration,
     pub enable_reflection: bool,
     pub enable_health_check: bool,
+    /// Reject every call that doesn't carry a valid `authorization: Bearer
+    /// <token>` header via `AuthInterceptor`. The token's signing secret is
+    /// configured separately, through `SecurityConfig::auth_token_secret_path`.
+    pub require_auth: bool,
+    /// When set, also serve the same services over a Unix domain socket at
+    /// this filesystem path, for co-located sidecars that want to talk to
+    /// the execution engine without going through TCP. Served alongside
+    /// `addr`, not instead of it.
+    pub uds_path: Option<std::path::PathBuf>,
 }
 
 impl Default for GrpcServerConfig {
@@ -365,32 +574,107 @@ impl Default for GrpcServerConfig {
             request_timeout: std::time::Duration::from_secs(30),
             enable_reflection: true,
             enable_health_check: true,
+            require_auth: false,
+            uds_path: None,
         }
     }
 }
 
-/// Start gRPC server
+/// Start gRPC server, serving on a socket that was already bound (and,
+/// typically, bound before `crate::privilege::drop_privileges` ran) rather
+/// than binding `config.addr` itself. This lets the supervisor claim a
+/// privileged port as root and still hand the accept loop off to an
+/// unprivileged process.
 pub async fn start_grpc_server(
     config: GrpcServerConfig,
     agent_service: AgentCoreService,
+    security: Arc<SecurityManager>,
+    listener: std::net::TcpListener,
 ) -> Result<()> {
-    info!("Starting gRPC server on {}", config.addr);
-
-    let agent_service = agent_service.into_service();
-    let health_service = HealthService::new();
-
-    // Build server
-    let mut server_builder = tonic::transport::Server::builder()
-        .timeout(config.request_timeout)
-        .concurrency_limit_per_connection(256);
-
-    // Add services
-    let server = server_builder
-        .add_service(tonic::transport::server::Routes::new()) // Mock - would add real services
-        .serve(config.addr);
+    info!("Starting gRPC server on {} (require_auth={})", config.addr, config.require_auth);
+
+    let agent_service = Arc::new(agent_service);
+    let health_service = Arc::new(HealthService::new());
+
+    if config.enable_health_check {
+        let agent_service = agent_service.clone();
+        let health_service = health_service.clone();
+        tokio::spawn(async move {
+            agent_service
+                .run_health_updater(health_service, std::time::Duration::from_secs(10))
+                .await;
+        });
+    }
 
-    info!("gRPC server started successfully on {}", config.addr);
+    let reflection_service = if config.enable_reflection {
+        Some(
+            tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+                .build_v1()?,
+        )
+    } else {
+        None
+    };
+
+    // Builds a fresh router over the same underlying services each time
+    // it's called, so the TCP and (optional) UDS listeners can each own
+    // their own `Router` instead of fighting over one.
+    let build_router = {
+        let agent_service = agent_service.clone();
+        let health_service = health_service.clone();
+        let security = security.clone();
+        let reflection_service = reflection_service.clone();
+        let require_auth = config.require_auth;
+        let request_timeout = config.request_timeout;
+        move || {
+            let interceptor = AuthInterceptor::new(security.clone(), require_auth);
+            let agent_server = tonic::service::interceptor::InterceptedService::new(
+                agent_core_server::AgentCoreServer::from_arc(agent_service.clone()),
+                interceptor,
+            );
+            let health_server = health_server::HealthServer::from_arc(health_service.clone());
+
+            let router = tonic::transport::Server::builder()
+                .timeout(request_timeout)
+                .concurrency_limit_per_connection(256)
+                .add_service(agent_server)
+                .add_service(health_server);
+
+            match reflection_service.clone() {
+                Some(reflection_service) => router.add_service(reflection_service),
+                None => router,
+            }
+        }
+    };
+
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| anyhow::anyhow!("failed to set gRPC listener non-blocking: {}", e))?;
+    let tcp_listener = tokio::net::TcpListener::from_std(listener)
+        .map_err(|e| anyhow::anyhow!("failed to adopt bound gRPC listener: {}", e))?;
+    let tcp_stream = tokio_stream::wrappers::TcpListenerStream::new(tcp_listener);
+    let tcp_server = build_router().serve_with_incoming(tcp_stream);
+    info!("gRPC server listening on {}", config.addr);
+
+    if let Some(uds_path) = config.uds_path.clone() {
+        // Binding fails if a stale socket file from a previous run is
+        // still there; starting fresh each time is the expected behavior
+        // for a Unix domain socket.
+        if uds_path.exists() {
+            std::fs::remove_file(&uds_path)?;
+        }
+        let uds_listener = tokio::net::UnixListener::bind(&uds_path).map_err(|e| {
+            anyhow::anyhow!("failed to bind gRPC unix socket at {}: {}", uds_path.display(), e)
+        })?;
+        let uds_stream = tokio_stream::wrappers::UnixListenerStream::new(uds_listener);
+        let uds_server = build_router().serve_with_incoming(uds_stream);
+        info!("gRPC server also listening on unix socket {}", uds_path.display());
+
+        let (tcp_result, uds_result) = tokio::join!(tcp_server, uds_server);
+        tcp_result.map_err(|e| anyhow::anyhow!("gRPC TCP server error: {}", e))?;
+        uds_result.map_err(|e| anyhow::anyhow!("gRPC UDS server error: {}", e))?;
+        return Ok(());
+    }
 
-    // Start server
-    server.await.map_err(|e| anyhow::anyhow!("gRPC server error: {}", e))
-}
\ No newline at end of file
+    tcp_server.await.map_err(|e| anyhow::anyhow!("gRPC server error: {}", e))
+}
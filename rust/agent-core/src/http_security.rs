@@ -0,0 +1,81 @@
+//! Response-header hardening for whatever HTTP/WS surface the runtime
+//! exposes (currently the metrics/health endpoints in `metrics.rs`, applied
+//! directly via `warp::reply::with::headers` -- there's no `tower` stack
+//! anywhere in this crate to hang a `Layer` off of, so the logic here is
+//! plain functions rather than a middleware type, kept in their own module
+//! so a future axum/warp/tonic surface can reuse them without duplication).
+
+use http::{HeaderMap, HeaderValue};
+
+use crate::config::SecurityConfig;
+
+/// The header values injected onto outgoing responses, sourced from
+/// `SecurityConfig` so operators can tune them without a rebuild
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersConfig {
+    pub content_security_policy: String,
+    pub x_frame_options: String,
+    pub x_content_type_options: String,
+    pub referrer_policy: String,
+    pub permissions_policy: String,
+}
+
+impl From<&SecurityConfig> for SecurityHeadersConfig {
+    fn from(config: &SecurityConfig) -> Self {
+        Self {
+            content_security_policy: config.response_content_security_policy.clone(),
+            x_frame_options: config.response_x_frame_options.clone(),
+            x_content_type_options: config.response_x_content_type_options.clone(),
+            referrer_policy: config.response_referrer_policy.clone(),
+            permissions_policy: config.response_permissions_policy.clone(),
+        }
+    }
+}
+
+/// Returns true if the request headers describe a WebSocket upgrade
+/// handshake, in which case the CSP/framing/permissions headers
+/// are skipped below so proxied agent streaming connections don't break
+pub fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    let has_connection_upgrade = headers
+        .get(http::header::CONNECTION)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_ascii_lowercase().contains("upgrade"))
+        .unwrap_or(false);
+
+    let has_upgrade_websocket = headers
+        .get(http::header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false);
+
+    has_connection_upgrade && has_upgrade_websocket
+}
+
+/// Inject the configured hardening headers onto `headers`. When
+/// `is_websocket` is set, only `X-Content-Type-Options`-agnostic headers
+/// that have no bearing on a raw byte stream are skipped: CSP, framing and
+/// permissions policy assume a rendered document and have no meaning (and
+/// can confuse some proxies) on a 101 Switching Protocols response.
+pub fn apply_security_headers(headers: &mut HeaderMap, config: &SecurityHeadersConfig, is_websocket: bool) {
+    if !is_websocket {
+        if let Ok(value) = HeaderValue::from_str(&config.content_security_policy) {
+            headers.insert(http::header::CONTENT_SECURITY_POLICY, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&config.x_frame_options) {
+            headers.insert(http::header::X_FRAME_OPTIONS, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&config.permissions_policy) {
+            headers.insert(
+                http::HeaderName::from_static("permissions-policy"),
+                value,
+            );
+        }
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&config.x_content_type_options) {
+        headers.insert(http::header::X_CONTENT_TYPE_OPTIONS, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&config.referrer_policy) {
+        headers.insert(http::header::REFERRER_POLICY, value);
+    }
+}
@@ -4,49 +4,119 @@ use tokio::signal;
 use tracing::{info, warn};
 
 mod config;
+mod config_manager;
 mod enforcement;
 mod execution;
+mod ext_authz;
 mod fsm;
+mod fsm_expr;
+mod generated;
 mod grpc;
+mod http_security;
+mod merkle_audit;
 mod metrics;
+#[cfg(test)]
+mod mock_sandbox;
+mod privilege;
 mod sandbox;
 mod security;
 
 use crate::config::Config;
+use crate::config_manager::ConfigManager;
 use crate::enforcement::EnforcementGateway;
 use crate::execution::ExecutionEngine;
 use crate::fsm::StateMachine;
-use crate::grpc::AgentCoreService;
+use crate::grpc::{AgentCoreService, GrpcServerConfig};
 use crate::metrics::MetricsCollector;
 use crate::sandbox::WASISandbox;
 use crate::security::SecurityManager;
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
+/// Set up the global tracing subscriber, optionally wiring in a
+/// `console-subscriber` layer so stuck/leaked async tasks can be inspected
+/// live with `tokio-console`. The layer only compiles in when built with
+/// `RUSTFLAGS="--cfg tokio_unstable"` and the `tokio-console` feature, since
+/// both the instrumentation points and the wire protocol are unstable.
+fn init_tracing(config: &Config) {
+    if config.server.enable_tokio_console {
+        #[cfg(all(tokio_unstable, feature = "tokio-console"))]
+        {
+            use tracing_subscriber::prelude::*;
+
+            let console_layer = console_subscriber::ConsoleLayer::builder()
+                .server_addr(config.server.console_bind_addr)
+                .spawn();
+
+            tracing_subscriber::registry()
+                .with(console_layer)
+                .with(tracing_subscriber::fmt::layer().json())
+                .with(tracing_subscriber::EnvFilter::from_default_env())
+                .init();
+            return;
+        }
+
+        #[cfg(not(all(tokio_unstable, feature = "tokio-console")))]
+        {
+            eprintln!(
+                "ENABLE_TOKIO_CONSOLE is set but this binary was not built with \
+                 --cfg tokio_unstable and the `tokio-console` feature; falling back to standard logging"
+            );
+        }
+    }
+
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .json()
         .init();
+}
 
-    info!("Starting Multi-Agent Core Execution Engine");
-
-    // Load configuration
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Load configuration first so the tracing setup can see whether
+    // tokio-console was requested
     let config = Config::from_env()?;
+
+    init_tracing(&config);
+
+    info!("Starting Multi-Agent Core Execution Engine");
     info!("Configuration loaded successfully");
 
     // Initialize metrics collector
-    let metrics = Arc::new(MetricsCollector::new()?);
+    let metrics = Arc::new(MetricsCollector::new(&config.metrics)?);
+    metrics.start_sampler(config.metrics.collection_interval);
     
     // Initialize security manager
     let security_manager = Arc::new(SecurityManager::new(&config.security).await?);
     
     // Initialize WASI sandbox
-    let sandbox = Arc::new(WASISandbox::new(&config.sandbox).await?);
+    let sandbox = Arc::new(WASISandbox::new(&config.sandbox, security_manager.clone()).await?);
     
     // Initialize enforcement gateway
     let enforcement = Arc::new(EnforcementGateway::new(&config.enforcement, metrics.clone()).await?);
-    
+
+    // Optionally watch a config file for live reloads of the fields that
+    // support it (currently enforcement's timeouts/rate limits/circuit
+    // breaker/token limits). Sandbox, metrics and server settings are only
+    // read at startup today, so changes to those sections are logged but
+    // otherwise take effect on the next restart.
+    let _config_manager = match std::env::var("CONFIG_FILE") {
+        Ok(path) => {
+            let reload_enforcement = enforcement.clone();
+            match ConfigManager::new(path, move |_old, new| {
+                reload_enforcement.reload_config(&new.enforcement);
+            }) {
+                Ok(manager) => {
+                    info!("Watching {:?} for configuration reloads", manager.path());
+                    Some(manager)
+                }
+                Err(e) => {
+                    warn!("Configuration hot-reload disabled: {}", e);
+                    None
+                }
+            }
+        }
+        Err(_) => None,
+    };
+
     // Initialize state machine
     let state_machine = Arc::new(StateMachine::new(&config.fsm)?);
     
@@ -57,6 +127,7 @@ async fn main() -> Result<()> {
         security_manager.clone(),
         state_machine.clone(),
         metrics.clone(),
+        &config.execution,
     )?);
 
     // Initialize gRPC service
@@ -65,29 +136,74 @@ async fn main() -> Result<()> {
         metrics.clone(),
     );
 
+    // Start gRPC server, registering the real tonic-build-generated
+    // `AgentCoreServer`/`HealthServer` services behind the auth interceptor
+    let grpc_config = GrpcServerConfig {
+        addr: config.server.grpc_addr.parse()?,
+        max_connections: config.server.max_connections,
+        request_timeout: config.server.request_timeout,
+        enable_reflection: false,
+        enable_health_check: true,
+        require_auth: false,
+        uds_path: None,
+    };
+
+    // Bind all listen sockets while still running as whatever user started
+    // the process (root, for a low port), then drop privileges before a
+    // single byte of untrusted traffic is accepted.
+    let grpc_listener = std::net::TcpListener::bind(grpc_config.addr)
+        .map_err(|e| anyhow::anyhow!("Failed to bind gRPC address {}: {}", grpc_config.addr, e))?;
+    let metrics_listener = std::net::TcpListener::bind(config.metrics.addr)
+        .map_err(|e| anyhow::anyhow!("Failed to bind metrics address {}: {}", config.metrics.addr, e))?;
+    let ext_authz_listener = if config.enforcement.ext_authz.enabled {
+        let addr: std::net::SocketAddr = config.enforcement.ext_authz.listen_addr.parse()?;
+        Some(
+            std::net::TcpListener::bind(addr)
+                .map_err(|e| anyhow::anyhow!("Failed to bind ext_authz address {}: {}", addr, e))?,
+        )
+    } else {
+        None
+    };
+
+    crate::privilege::drop_privileges(&config.privilege, &config.sandbox.temp_dir)?;
+
+    // Log the endpoints peers/registries should actually be told to dial,
+    // which may differ from the bind addresses above behind NAT or a
+    // container port-map.
+    info!(
+        grpc = %config.server.advertised_grpc_addr()?,
+        metrics = %config.server.advertised_metrics_addr()?,
+        "Resolved advertised endpoints"
+    );
+
     // Start metrics server
+    let metrics_security_headers = crate::http_security::SecurityHeadersConfig::from(&config.security);
     let metrics_handle = tokio::spawn({
         let metrics = metrics.clone();
         async move {
-            if let Err(e) = metrics.start_server().await {
+            if let Err(e) = metrics.start_server(metrics_listener, &metrics_security_headers).await {
                 warn!("Metrics server error: {}", e);
             }
         }
     });
 
-    // Start gRPC server
     let grpc_handle = tokio::spawn({
-        let service = grpc_service;
-        let addr = config.server.grpc_addr.parse()?;
+        let security_manager = security_manager.clone();
         async move {
-            info!("Starting gRPC server on {}", addr);
-            tonic::transport::Server::builder()
-                .add_service(service.into_service())
-                .serve(addr)
-                .await
+            crate::grpc::start_grpc_server(grpc_config, grpc_service, security_manager, grpc_listener).await
         }
     });
 
+    // Optionally expose the enforcement pipeline as an Envoy ext_authz
+    // filter, so it can be dropped in front of any Envoy-fronted service.
+    let ext_authz_handle = ext_authz_listener.map(|listener| {
+        let enforcement = enforcement.clone();
+        let ext_authz_config = config.enforcement.ext_authz.clone();
+        tokio::spawn(async move {
+            crate::ext_authz::start_ext_authz_server(enforcement, &ext_authz_config, listener).await
+        })
+    });
+
     info!("Multi-Agent Core started successfully");
 
     // Wait for shutdown signal
@@ -105,6 +221,25 @@ async fn main() -> Result<()> {
                 warn!("Metrics server join error: {}", e);
             }
         }
+        result = async {
+            match ext_authz_handle {
+                Some(handle) => handle.await,
+                None => std::future::pending().await,
+            }
+        } => {
+            if let Err(e) = result? {
+                warn!("ext_authz server error: {}", e);
+            }
+        }
+    }
+
+    // Make sure a short-lived process still contributes its terminal-state
+    // counters to the Pushgateway before exiting
+    if let Err(e) = metrics.flush().await {
+        warn!("Failed to flush final metrics: {}", e);
+    }
+    if let Err(e) = security_manager.flush_audit_merkle_root() {
+        warn!("Failed to flush final audit Merkle root: {}", e);
     }
 
     info!("Shutting down Multi-Agent Core");
@@ -1,49 +1,123 @@
-use anyhow::Result;
-use prometheus::{Counter, Histogram, Gauge, Registry, Encoder, TextEncoder};
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
+use anyhow::{Context, Result};
+use opentelemetry::trace::{SpanBuilder, TraceContextExt, Tracer};
+use opentelemetry::{global, Context as OtelContext, KeyValue};
+use prometheus::{CounterVec, Histogram, HistogramVec, Gauge, GaugeVec, HistogramOpts, Opts, Registry, Encoder, TextEncoder};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock as StdRwLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
 use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn, Instrument};
 use warp::{Filter, Reply};
 
-use crate::execution::CodeLanguage;
+use crate::config::{OtelExporterConfig, PushGatewayConfig};
+use crate::execution::{CodeLanguage, ResourceAccounting};
+use crate::http_security::{apply_security_headers, SecurityHeadersConfig};
 
 /// Metrics collector for the agent core system
 pub struct MetricsCollector {
     registry: Registry,
     
     // Execution metrics
-    executions_total: Counter,
-    execution_duration: Histogram,
-    execution_tokens: Histogram,
+    executions_total: CounterVec,
+    executions_total_guard: CardinalityGuard,
+    execution_duration: HistogramVec,
+    execution_duration_guard: CardinalityGuard,
+    execution_tokens: HistogramVec,
+    execution_tokens_guard: CardinalityGuard,
     execution_success_rate: Gauge,
-    
+
     // Sandbox metrics
     sandbox_instances_active: Gauge,
     sandbox_memory_usage: Histogram,
     sandbox_cpu_usage: Histogram,
-    
+    sandbox_memory_by_id: GaugeVec,
+
+    // PIDs registered for per-process resource sampling, keyed by sandbox id
+    sandbox_pids: Arc<RwLock<HashMap<String, u32>>>,
+
     // Security metrics
-    security_violations_total: Counter,
-    policy_evaluations_total: Counter,
-    
+    security_violations_total: CounterVec,
+    security_violations_total_guard: CardinalityGuard,
+    policy_evaluations_total: CounterVec,
+    policy_evaluations_total_guard: CardinalityGuard,
+
     // FSM metrics
     fsm_instances_active: Gauge,
-    fsm_transitions_total: Counter,
+    fsm_transitions_total: CounterVec,
+    fsm_transitions_total_guard: CardinalityGuard,
     fsm_state_duration: Histogram,
-    
+
     // Enforcement metrics
-    enforcement_checks_total: Counter,
-    rate_limit_violations: Counter,
-    circuit_breaker_trips: Counter,
-    
+    enforcement_checks_total: CounterVec,
+    enforcement_checks_total_guard: CardinalityGuard,
+    rate_limit_violations: prometheus::Counter,
+    circuit_breaker_trips: prometheus::Counter,
+    gc_evictions_total: CounterVec,
+    gc_evictions_total_guard: CardinalityGuard,
+    rate_limit_wait: Histogram,
+    forced_aborts_total: prometheus::Counter,
+
+    // Execution engine result-cache metrics
+    result_cache_lookups_total: CounterVec,
+
     // System metrics
     system_memory_usage: Gauge,
     system_cpu_usage: Gauge,
     
     // Runtime statistics
     stats: Arc<RwLock<RuntimeStats>>,
+
+    // Optional push-based OTLP exporter running alongside the /metrics scrape endpoint
+    otel: Option<Arc<OtelExporter>>,
+
+    // Optional Prometheus Pushgateway reporter for short-lived/batch executions
+    pushgateway: Option<Arc<PushGatewayReporter>>,
+}
+
+/// Bounds the number of distinct label-value tuples a labeled metric tracks.
+/// Once `max_series` tuples have been seen, further distinct combinations are
+/// collapsed into a shared `"other"` series instead of creating unbounded
+/// cardinality (e.g. from high-cardinality fields like `execution_id`).
+struct CardinalityGuard {
+    seen: StdRwLock<HashSet<Vec<String>>>,
+    max_series: usize,
+}
+
+impl CardinalityGuard {
+    fn new(max_series: usize) -> Self {
+        Self { seen: StdRwLock::new(HashSet::new()), max_series }
+    }
+
+    /// Returns the label values to actually record: the input values if this
+    /// tuple is already tracked or there's still room, otherwise `"other"`
+    /// repeated for every label position.
+    fn admit<'a>(&self, labels: &'a [&'a str]) -> Vec<String> {
+        let owned: Vec<String> = labels.iter().map(|s| s.to_string()).collect();
+
+        {
+            let seen = self.seen.read().unwrap();
+            if seen.contains(&owned) {
+                return owned;
+            }
+            if seen.len() >= self.max_series {
+                return labels.iter().map(|_| "other".to_string()).collect();
+            }
+        }
+
+        let mut seen = self.seen.write().unwrap();
+        if seen.len() >= self.max_series {
+            return labels.iter().map(|_| "other".to_string()).collect();
+        }
+        seen.insert(owned.clone());
+        owned
+    }
+}
+
+/// Borrow a guard's owned label values back out as `&str`s for
+/// `with_label_values`, which takes slices rather than owned strings
+fn as_str_slice(values: &[String]) -> Vec<&str> {
+    values.iter().map(|s| s.as_str()).collect()
 }
 
 /// Runtime statistics
@@ -54,36 +128,74 @@ struct RuntimeStats {
     failed_executions: u64,
     total_duration: Duration,
     total_tokens: u64,
+
+    // Set once any `ingest_external_report` call has merged in out-of-process
+    // numbers, so consumers of `MetricsSummary` know the totals aren't purely
+    // from executions this process observed directly
+    has_external_data: bool,
+
+    // Most recently ingested external quantiles, if the reporting harness
+    // tracked its own histogram instead of relying on ours
+    external_quantiles: Option<ExternalQuantiles>,
+}
+
+/// A benchmark or load-test report produced outside this process (e.g. a
+/// standalone harness driving the gRPC API) and merged into the running
+/// totals via [`MetricsCollector::ingest_external_report`]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ExternalReport {
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub op_count: u64,
+    pub success_count: u64,
+    pub failure_count: u64,
+    pub total_duration: Duration,
+    pub total_tokens: u64,
+    /// Pre-computed latency quantiles from the external harness, if it
+    /// tracked its own histogram rather than relying on ours
+    pub quantiles: Option<ExternalQuantiles>,
+}
+
+/// Pre-computed p50/p95/p99 latencies from an external report
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ExternalQuantiles {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
 }
 
 impl MetricsCollector {
-    /// Create a new metrics collector
-    pub fn new() -> Result<Self> {
+    /// Create a new metrics collector, optionally starting a push-based OTLP
+    /// exporter and/or Pushgateway reporter alongside the pull-based
+    /// Prometheus endpoint
+    pub fn new(config: &crate::config::MetricsConfig) -> Result<Self> {
+        let otel_config = &config.otel;
         let registry = Registry::new();
         
         // Initialize execution metrics
-        let executions_total = Counter::new(
-            "agent_executions_total",
-            "Total number of agent code executions"
+        let executions_total = CounterVec::new(
+            Opts::new("agent_executions_total", "Total number of agent code executions"),
+            &["language", "result"]
         )?;
         registry.register(Box::new(executions_total.clone()))?;
-        
-        let execution_duration = Histogram::with_opts(
-            prometheus::HistogramOpts::new(
+
+        let execution_duration = HistogramVec::new(
+            HistogramOpts::new(
                 "agent_execution_duration_seconds",
                 "Duration of agent code executions in seconds"
-            ).buckets(vec![0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0])
+            ).buckets(vec![0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0]),
+            &["language"]
         )?;
         registry.register(Box::new(execution_duration.clone()))?;
-        
-        let execution_tokens = Histogram::with_opts(
-            prometheus::HistogramOpts::new(
+
+        let execution_tokens = HistogramVec::new(
+            HistogramOpts::new(
                 "agent_execution_tokens_total",
                 "Number of tokens used in agent executions"
-            ).buckets(vec![10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0, 10000.0])
+            ).buckets(vec![10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0, 10000.0]),
+            &["language"]
         )?;
         registry.register(Box::new(execution_tokens.clone()))?;
-        
+
         let execution_success_rate = Gauge::new(
             "agent_execution_success_rate",
             "Success rate of agent executions (0-1)"
@@ -118,33 +230,42 @@ impl MetricsCollector {
             ).buckets(vec![0.01, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0])
         )?;
         registry.register(Box::new(sandbox_cpu_usage.clone()))?;
-        
+
+        let sandbox_memory_by_id = GaugeVec::new(
+            Opts::new(
+                "sandbox_memory_usage_by_id_bytes",
+                "Resident memory usage per sandbox instance, sampled from /proc"
+            ),
+            &["sandbox_id"]
+        )?;
+        registry.register(Box::new(sandbox_memory_by_id.clone()))?;
+
         // Initialize security metrics
-        let security_violations_total = Counter::new(
-            "security_violations_total",
-            "Total number of security violations detected"
+        let security_violations_total = CounterVec::new(
+            Opts::new("security_violations_total", "Total number of security violations detected"),
+            &["violation_type"]
         )?;
         registry.register(Box::new(security_violations_total.clone()))?;
-        
-        let policy_evaluations_total = Counter::new(
-            "policy_evaluations_total",
-            "Total number of policy evaluations performed"
+
+        let policy_evaluations_total = CounterVec::new(
+            Opts::new("policy_evaluations_total", "Total number of policy evaluations performed"),
+            &["policy_name", "result"]
         )?;
         registry.register(Box::new(policy_evaluations_total.clone()))?;
-        
+
         // Initialize FSM metrics
         let fsm_instances_active = Gauge::new(
             "fsm_instances_active",
             "Number of active FSM instances"
         )?;
         registry.register(Box::new(fsm_instances_active.clone()))?;
-        
-        let fsm_transitions_total = Counter::new(
-            "fsm_transitions_total",
-            "Total number of FSM state transitions"
+
+        let fsm_transitions_total = CounterVec::new(
+            Opts::new("fsm_transitions_total", "Total number of FSM state transitions"),
+            &["from_state", "to_state"]
         )?;
         registry.register(Box::new(fsm_transitions_total.clone()))?;
-        
+
         let fsm_state_duration = Histogram::with_opts(
             prometheus::HistogramOpts::new(
                 "fsm_state_duration_seconds",
@@ -152,26 +273,52 @@ impl MetricsCollector {
             ).buckets(vec![0.1, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0])
         )?;
         registry.register(Box::new(fsm_state_duration.clone()))?;
-        
+
         // Initialize enforcement metrics
-        let enforcement_checks_total = Counter::new(
-            "enforcement_checks_total",
-            "Total number of enforcement checks performed"
+        let enforcement_checks_total = CounterVec::new(
+            Opts::new("enforcement_checks_total", "Total number of enforcement checks performed"),
+            &["result"]
         )?;
         registry.register(Box::new(enforcement_checks_total.clone()))?;
-        
-        let rate_limit_violations = Counter::new(
+
+        let rate_limit_violations = prometheus::Counter::new(
             "rate_limit_violations_total",
             "Total number of rate limit violations"
         )?;
         registry.register(Box::new(rate_limit_violations.clone()))?;
         
-        let circuit_breaker_trips = Counter::new(
+        let circuit_breaker_trips = prometheus::Counter::new(
             "circuit_breaker_trips_total",
             "Total number of circuit breaker trips"
         )?;
         registry.register(Box::new(circuit_breaker_trips.clone()))?;
-        
+
+        let gc_evictions_total = CounterVec::new(
+            Opts::new("enforcement_gc_evictions_total", "Total number of idle entries evicted by the enforcement GC sweep"),
+            &["component"]
+        )?;
+        registry.register(Box::new(gc_evictions_total.clone()))?;
+
+        let rate_limit_wait = Histogram::with_opts(
+            HistogramOpts::new(
+                "rate_limit_wait_seconds",
+                "Time enforce_request_blocking slept before retrying a rate-limited request"
+            ).buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 2.0, 5.0, 10.0])
+        )?;
+        registry.register(Box::new(rate_limit_wait.clone()))?;
+
+        let forced_aborts_total = prometheus::Counter::new(
+            "execution_forced_aborts_total",
+            "Total number of cancelled executions whose sandbox didn't confirm teardown within the abort grace period"
+        )?;
+        registry.register(Box::new(forced_aborts_total.clone()))?;
+
+        let result_cache_lookups_total = CounterVec::new(
+            Opts::new("execution_result_cache_lookups_total", "Total number of ExecutionEngine result-cache lookups"),
+            &["outcome"]
+        )?;
+        registry.register(Box::new(result_cache_lookups_total.clone()))?;
+
         // Initialize system metrics
         let system_memory_usage = Gauge::new(
             "system_memory_usage_bytes",
@@ -184,30 +331,82 @@ impl MetricsCollector {
             "System CPU usage percentage"
         )?;
         registry.register(Box::new(system_cpu_usage.clone()))?;
-        
+
+        let otel = if otel_config.enabled {
+            Some(Arc::new(OtelExporter::new(registry.clone(), otel_config.clone())?))
+        } else {
+            None
+        };
+
+        let pushgateway = if config.pushgateway.enabled {
+            Some(Arc::new(PushGatewayReporter::new(registry.clone(), config.pushgateway.clone())))
+        } else {
+            None
+        };
+
         Ok(Self {
             registry,
             executions_total,
+            executions_total_guard: CardinalityGuard::new(config.max_label_series),
             execution_duration,
+            execution_duration_guard: CardinalityGuard::new(config.max_label_series),
             execution_tokens,
+            execution_tokens_guard: CardinalityGuard::new(config.max_label_series),
             execution_success_rate,
             sandbox_instances_active,
             sandbox_memory_usage,
             sandbox_cpu_usage,
+            sandbox_memory_by_id,
+            sandbox_pids: Arc::new(RwLock::new(HashMap::new())),
             security_violations_total,
+            security_violations_total_guard: CardinalityGuard::new(config.max_label_series),
             policy_evaluations_total,
+            policy_evaluations_total_guard: CardinalityGuard::new(config.max_label_series),
             fsm_instances_active,
             fsm_transitions_total,
+            fsm_transitions_total_guard: CardinalityGuard::new(config.max_label_series),
             fsm_state_duration,
             enforcement_checks_total,
+            enforcement_checks_total_guard: CardinalityGuard::new(config.max_label_series),
             rate_limit_violations,
             circuit_breaker_trips,
+            gc_evictions_total,
+            gc_evictions_total_guard: CardinalityGuard::new(config.max_label_series),
+            rate_limit_wait,
+            forced_aborts_total,
+            result_cache_lookups_total,
             system_memory_usage,
             system_cpu_usage,
             stats: Arc::new(RwLock::new(RuntimeStats::default())),
+            otel,
+            pushgateway,
         })
     }
 
+    /// Start the background OTLP export loop (no-op if OTLP export is disabled)
+    pub fn start_otel_exporter(&self) {
+        if let Some(otel) = &self.otel {
+            otel.clone().start();
+        }
+    }
+
+    /// Start the background Pushgateway reporter loop (no-op if disabled)
+    pub fn start_pushgateway_reporter(&self) {
+        if let Some(pushgateway) = &self.pushgateway {
+            pushgateway.clone().start();
+        }
+    }
+
+    /// Synchronously push the current registry state one final time, e.g. from
+    /// a shutdown hook, so terminal-state counters for a short-lived run are
+    /// never dropped just because no scrape happened before the process exited
+    pub async fn flush(&self) -> Result<()> {
+        if let Some(pushgateway) = &self.pushgateway {
+            pushgateway.flush().await?;
+        }
+        Ok(())
+    }
+
     /// Record agent execution metrics
     pub fn record_agent_execution(
         &self,
@@ -220,23 +419,43 @@ impl MetricsCollector {
         debug!("Recording execution metrics for {}: success={}, duration={:?}, tokens={}", 
                execution_id, success, duration, tokens_used);
 
-        // Update counters and histograms
-        self.executions_total.with_label_values(&[
-            &format!("{:?}", language).to_lowercase(),
+        // Update counters and histograms, routing label values through the
+        // cardinality guards so an unbounded field can never explode series count
+        let language_label = format!("{:?}", language).to_lowercase();
+
+        let execution_labels = self.executions_total_guard.admit(&[
+            &language_label,
             if success { "success" } else { "failure" }
-        ]).inc();
-        
-        self.execution_duration.with_label_values(&[
-            &format!("{:?}", language).to_lowercase()
-        ]).observe(duration.as_secs_f64());
-        
-        self.execution_tokens.with_label_values(&[
-            &format!("{:?}", language).to_lowercase()
-        ]).observe(tokens_used as f64);
+        ]);
+        self.executions_total
+            .with_label_values(&as_str_slice(&execution_labels))
+            .inc();
+
+        let duration_labels = self.execution_duration_guard.admit(&[&language_label]);
+        self.execution_duration
+            .with_label_values(&as_str_slice(&duration_labels))
+            .observe(duration.as_secs_f64());
+
+        let tokens_labels = self.execution_tokens_guard.admit(&[&language_label]);
+        self.execution_tokens
+            .with_label_values(&as_str_slice(&tokens_labels))
+            .observe(tokens_used as f64);
 
-        // Update runtime statistics
-        tokio::spawn({
-            let stats = self.stats.clone();
+        // Emit a span for this execution so it can be correlated across the
+        // sandbox, security, and FSM subsystems in the OTLP collector
+        if let Some(otel) = &self.otel {
+            otel.record_execution_span(execution_id, &format!("{:?}", language).to_lowercase(), tokens_used, success, duration);
+        }
+
+        // Update runtime statistics and the success-rate gauge under a single
+        // lock acquisition. Splitting this into two detached spawns let a
+        // second execution's write race the first's gauge update, so the
+        // gauge could reflect either ordering depending on scheduling; a
+        // single named task closes that window.
+        let stats = self.stats.clone();
+        let success_rate_gauge = self.execution_success_rate.clone();
+        let execution_id_owned = execution_id.to_string();
+        tokio::spawn(
             async move {
                 let mut stats = stats.write().await;
                 stats.total_executions += 1;
@@ -247,25 +466,13 @@ impl MetricsCollector {
                 }
                 stats.total_duration += duration;
                 stats.total_tokens += tokens_used as u64;
-                
-                // Update success rate gauge
+
                 let success_rate = stats.successful_executions as f64 / stats.total_executions as f64;
-                drop(stats); // Release lock before calling gauge
+                drop(stats);
+                success_rate_gauge.set(success_rate);
             }
-        });
-        
-        // Update success rate (this is approximate due to async nature)
-        tokio::spawn({
-            let success_rate_gauge = self.execution_success_rate.clone();
-            let stats = self.stats.clone();
-            async move {
-                let stats = stats.read().await;
-                if stats.total_executions > 0 {
-                    let rate = stats.successful_executions as f64 / stats.total_executions as f64;
-                    success_rate_gauge.set(rate);
-                }
-            }
-        });
+            .instrument(tracing::info_span!("record_agent_execution_stats", execution_id = %execution_id_owned)),
+        );
     }
 
     /// Record sandbox metrics
@@ -275,17 +482,44 @@ impl MetricsCollector {
         self.sandbox_cpu_usage.observe(cpu_usage.as_secs_f64());
     }
 
+    /// Attach a sandbox instance's OS PID so the background `ResourceSampler`
+    /// picks up its real memory/CPU usage on the next sampling tick
+    pub async fn register_sandbox_pid(&self, sandbox_id: &str, pid: u32) {
+        self.sandbox_pids.write().await.insert(sandbox_id.to_string(), pid);
+    }
+
+    /// Detach a sandbox instance's PID, e.g. once its process has exited
+    pub async fn unregister_sandbox_pid(&self, sandbox_id: &str) {
+        self.sandbox_pids.write().await.remove(sandbox_id);
+        if let Ok(gauge) = self.sandbox_memory_by_id.get_metric_with_label_values(&[sandbox_id]) {
+            gauge.set(0.0);
+        }
+    }
+
+    /// Spawn the background `ResourceSampler` task that refreshes per-PID
+    /// memory/CPU usage for every registered sandbox on a fixed interval
+    pub fn start_sampler(self: &Arc<Self>, interval: Duration) {
+        let sampler = ResourceSampler::new(self.clone());
+        sampler.start(interval);
+    }
+
     /// Record security violation
     pub fn record_security_violation(&self, violation_type: &str) {
-        self.security_violations_total.with_label_values(&[violation_type]).inc();
+        let labels = self.security_violations_total_guard.admit(&[violation_type]);
+        self.security_violations_total
+            .with_label_values(&as_str_slice(&labels))
+            .inc();
     }
 
     /// Record policy evaluation
     pub fn record_policy_evaluation(&self, policy_name: &str, result: bool) {
-        self.policy_evaluations_total.with_label_values(&[
+        let labels = self.policy_evaluations_total_guard.admit(&[
             policy_name,
             if result { "allowed" } else { "denied" }
-        ]).inc();
+        ]);
+        self.policy_evaluations_total
+            .with_label_values(&as_str_slice(&labels))
+            .inc();
     }
 
     /// Record FSM metrics
@@ -295,19 +529,32 @@ impl MetricsCollector {
 
     /// Record FSM transition
     pub fn record_fsm_transition(&self, from_state: &str, to_state: &str, duration: Duration) {
-        self.fsm_transitions_total.with_label_values(&[from_state, to_state]).inc();
+        let labels = self.fsm_transitions_total_guard.admit(&[from_state, to_state]);
+        self.fsm_transitions_total
+            .with_label_values(&as_str_slice(&labels))
+            .inc();
         self.fsm_state_duration.with_label_values(&[from_state]).observe(duration.as_secs_f64());
+
+        if let Some(otel) = &self.otel {
+            otel.record_transition_event(from_state, to_state, duration);
+        }
     }
 
     /// Record enforcement success
     pub fn record_enforcement_success(&self, task_id: &str) {
-        self.enforcement_checks_total.with_label_values(&["success"]).inc();
+        let labels = self.enforcement_checks_total_guard.admit(&["success"]);
+        self.enforcement_checks_total
+            .with_label_values(&as_str_slice(&labels))
+            .inc();
     }
 
     /// Record enforcement failure
     pub fn record_enforcement_failure(&self, reason: &str) {
-        self.enforcement_checks_total.with_label_values(&["failure"]).inc();
-        
+        let labels = self.enforcement_checks_total_guard.admit(&["failure"]);
+        self.enforcement_checks_total
+            .with_label_values(&as_str_slice(&labels))
+            .inc();
+
         match reason {
             "rate_limit" => self.rate_limit_violations.inc(),
             "circuit_breaker" => self.circuit_breaker_trips.inc(),
@@ -315,14 +562,75 @@ impl MetricsCollector {
         }
     }
 
+    /// Record entries evicted from a given component's idle-state map
+    /// (`"rate_limiter"` or `"circuit_breaker"`) during a GC sweep
+    pub fn record_gc_eviction(&self, component: &str, count: u64) {
+        let labels = self.gc_evictions_total_guard.admit(&[component]);
+        self.gc_evictions_total
+            .with_label_values(&as_str_slice(&labels))
+            .inc_by(count as f64);
+    }
+
+    /// Record how long `enforce_request_blocking` slept before retrying a
+    /// rate-limited request
+    pub fn record_rate_limit_wait(&self, wait: Duration) {
+        self.rate_limit_wait.observe(wait.as_secs_f64());
+    }
+
+    /// Record that `cancel_execution` gave up waiting for the sandbox to
+    /// confirm teardown and force-removed the active-execution record
+    pub fn record_forced_abort(&self) {
+        self.forced_aborts_total.inc();
+    }
+
+    /// Record the outcome of an `ExecutionEngine` result-cache lookup
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let outcome = if hit { "hit" } else { "miss" };
+        self.result_cache_lookups_total
+            .with_label_values(&[outcome])
+            .inc();
+    }
+
+    /// Merge an out-of-process benchmark or load-test report into the
+    /// running totals, so results gathered by a standalone harness still
+    /// show up in `get_metrics_summary`/`MetricsSummary` alongside executions
+    /// this process observed directly
+    pub async fn ingest_external_report(&self, report: ExternalReport) {
+        info!(
+            "Ingesting external report from {}: {} ops ({} success, {} failure)",
+            report.started_at, report.op_count, report.success_count, report.failure_count
+        );
+
+        let mut stats = self.stats.write().await;
+        stats.total_executions += report.op_count;
+        stats.successful_executions += report.success_count;
+        stats.failed_executions += report.failure_count;
+        stats.total_duration += report.total_duration;
+        stats.total_tokens += report.total_tokens;
+        stats.has_external_data = true;
+        if report.quantiles.is_some() {
+            stats.external_quantiles = report.quantiles;
+        }
+    }
+
     /// Record task success
-    pub fn record_task_success(&self, task_id: &str, duration: Duration, tokens_used: u32) {
-        debug!("Task {} completed successfully in {:?} using {} tokens", task_id, duration, tokens_used);
+    pub fn record_task_success(&self, task_id: &str, duration: Duration, tokens_used: u32, accounting: &ResourceAccounting) {
+        debug!(
+            "Task {} completed successfully in {:?} using {} tokens (peak_memory={}B cpu_time={:?} execution_units={} syscalls={} network_bytes={}/{})",
+            task_id, duration, tokens_used, accounting.peak_memory_bytes, accounting.cpu_time,
+            accounting.execution_units, accounting.syscalls_count,
+            accounting.network_bytes_sent, accounting.network_bytes_received
+        );
     }
 
     /// Record task failure
-    pub fn record_task_failure(&self, task_id: &str, duration: Duration) {
-        debug!("Task {} failed after {:?}", task_id, duration);
+    pub fn record_task_failure(&self, task_id: &str, duration: Duration, accounting: &ResourceAccounting) {
+        debug!(
+            "Task {} failed after {:?} (peak_memory={}B cpu_time={:?} execution_units={} syscalls={} network_bytes={}/{})",
+            task_id, duration, accounting.peak_memory_bytes, accounting.cpu_time,
+            accounting.execution_units, accounting.syscalls_count,
+            accounting.network_bytes_sent, accounting.network_bytes_received
+        );
     }
 
     /// Update system metrics
@@ -366,9 +674,20 @@ impl MetricsCollector {
     }
 
     /// Start metrics server
-    pub async fn start_server(&self) -> Result<()> {
+    /// Serve the metrics/health endpoints on a socket that was already
+    /// bound (and, typically, bound before `crate::privilege::drop_privileges`
+    /// ran), rather than binding a fresh one here. `security_headers`
+    /// hardens every response with the same headers any other HTTP surface
+    /// in the process would apply -- there's no `tower` stack here to hang
+    /// a `Layer` off of, so it's applied directly via
+    /// `warp::reply::with::headers`.
+    pub async fn start_server(&self, listener: std::net::TcpListener, security_headers: &SecurityHeadersConfig) -> Result<()> {
+        // Ship metrics/spans to an OTLP collector alongside the scrape endpoint
+        self.start_otel_exporter();
+        self.start_pushgateway_reporter();
+
         let registry = self.registry.clone();
-        
+
         // Create metrics endpoint
         let metrics_route = warp::path("metrics")
             .and(warp::get())
@@ -402,12 +721,26 @@ impl MetricsCollector {
         // Combine routes
         let routes = metrics_route.or(health_route);
 
-        info!("Starting metrics server on 0.0.0.0:2113");
-        
+        // Neither endpoint ever upgrades to a WebSocket, so the hardening
+        // headers apply unconditionally
+        let mut header_map = warp::http::HeaderMap::new();
+        apply_security_headers(&mut header_map, security_headers, false);
+        let routes = routes.with(warp::reply::with::headers(header_map));
+
+        let addr = listener
+            .local_addr()
+            .map_err(|e| anyhow::anyhow!("failed to read bound metrics listener address: {}", e))?;
+        info!("Starting metrics server on {}", addr);
+
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| anyhow::anyhow!("failed to set metrics listener non-blocking: {}", e))?;
+        let tokio_listener = tokio::net::TcpListener::from_std(listener)
+            .map_err(|e| anyhow::anyhow!("failed to adopt bound metrics listener: {}", e))?;
+        let incoming = tokio_stream::wrappers::TcpListenerStream::new(tokio_listener);
+
         // Start server
-        warp::serve(routes)
-            .run(([0, 0, 0, 0], 2113))
-            .await;
+        warp::serve(routes).run_incoming(incoming).await;
 
         Ok(())
     }
@@ -415,7 +748,7 @@ impl MetricsCollector {
     /// Get metrics summary
     pub async fn get_metrics_summary(&self) -> MetricsSummary {
         let stats = self.stats.read().await;
-        
+
         MetricsSummary {
             total_executions: stats.total_executions,
             successful_executions: stats.successful_executions,
@@ -436,7 +769,349 @@ impl MetricsCollector {
             } else {
                 0
             },
+            includes_external_data: stats.has_external_data,
+            external_quantiles: stats.external_quantiles.clone(),
+            p50_duration: self.get_quantile(0.5),
+            p95_duration: self.get_quantile(0.95),
+            p99_duration: self.get_quantile(0.99),
+            p50_tokens: self.get_token_quantile(0.5),
+            p95_tokens: self.get_token_quantile(0.95),
+            p99_tokens: self.get_token_quantile(0.99),
+        }
+    }
+
+    /// Latency quantile (e.g. `q = 0.95` for p95) computed by linear
+    /// interpolation within the `agent_execution_duration_seconds` buckets,
+    /// aggregated across every `language` series. Returns zero when no
+    /// samples have been observed yet rather than propagating a NaN.
+    pub fn get_quantile(&self, q: f64) -> Duration {
+        Duration::from_secs_f64(self.quantile_from_family("agent_execution_duration_seconds", q))
+    }
+
+    /// Token-count quantile computed the same way, from
+    /// `agent_execution_tokens_total` buckets
+    pub fn get_token_quantile(&self, q: f64) -> f64 {
+        self.quantile_from_family("agent_execution_tokens_total", q)
+    }
+
+    /// Sum the named histogram's buckets across every label series in the
+    /// registry and interpolate the requested quantile within them
+    fn quantile_from_family(&self, metric_name: &str, q: f64) -> f64 {
+        let families = self.registry.gather();
+        let Some(family) = families.iter().find(|f| f.get_name() == metric_name) else {
+            return 0.0;
+        };
+
+        let mut upper_bounds: Vec<f64> = Vec::new();
+        let mut cumulative: Vec<u64> = Vec::new();
+        let mut total_count: u64 = 0;
+
+        for metric in family.get_metric() {
+            if !metric.has_histogram() {
+                continue;
+            }
+            let histogram = metric.get_histogram();
+            let buckets = histogram.get_bucket();
+
+            if upper_bounds.is_empty() {
+                upper_bounds = buckets.iter().map(|b| b.get_upper_bound()).collect();
+                cumulative = vec![0; buckets.len()];
+            }
+            for (i, bucket) in buckets.iter().enumerate() {
+                if i < cumulative.len() {
+                    cumulative[i] += bucket.get_cumulative_count();
+                }
+            }
+            total_count += histogram.get_sample_count();
+        }
+
+        if total_count == 0 {
+            return 0.0;
         }
+
+        upper_bounds.push(f64::INFINITY);
+        cumulative.push(total_count);
+
+        let target = q * total_count as f64;
+        let mut prev_bound = 0.0;
+        let mut prev_count = 0.0;
+        for (upper, count) in upper_bounds.into_iter().zip(cumulative) {
+            let count = count as f64;
+            if count >= target {
+                if upper.is_infinite() {
+                    return prev_bound;
+                }
+                let bucket_count = count - prev_count;
+                if bucket_count <= 0.0 {
+                    return upper;
+                }
+                let fraction = (target - prev_count) / bucket_count;
+                return prev_bound + (upper - prev_bound) * fraction;
+            }
+            prev_bound = upper;
+            prev_count = count;
+        }
+        prev_bound
+    }
+}
+
+/// Background task that samples true per-process resource usage for every
+/// registered sandbox PID, replacing the machine-wide load-average heuristic
+/// with real per-sandbox memory/CPU numbers
+struct ResourceSampler {
+    metrics: Arc<MetricsCollector>,
+}
+
+/// Previous sample for a PID, used to compute CPU-percent as
+/// delta-cpu-time / delta-wall-time between refreshes rather than reporting
+/// a cumulative counter
+struct PidSample {
+    cpu_seconds: f64,
+    sampled_at: Instant,
+}
+
+impl ResourceSampler {
+    fn new(metrics: Arc<MetricsCollector>) -> Self {
+        Self { metrics }
+    }
+
+    fn start(self, interval: Duration) {
+        tokio::spawn(async move {
+            let mut system = System::new();
+            let mut previous: HashMap<String, PidSample> = HashMap::new();
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+
+                let registered: HashMap<String, u32> = self.metrics.sandbox_pids.read().await.clone();
+
+                for (sandbox_id, raw_pid) in &registered {
+                    let pid = Pid::from_u32(*raw_pid);
+
+                    // refresh_process returns false once the PID has exited; drop its
+                    // stale sample instead of panicking on a vanished process
+                    if !system.refresh_process(pid) {
+                        previous.remove(sandbox_id);
+                        continue;
+                    }
+
+                    let Some(process) = system.process(pid) else {
+                        previous.remove(sandbox_id);
+                        continue;
+                    };
+
+                    let memory_bytes = process.memory() * 1024; // sysinfo reports KiB
+                    self.metrics.sandbox_memory_usage.observe(memory_bytes as f64);
+                    self.metrics
+                        .sandbox_memory_by_id
+                        .with_label_values(&[sandbox_id.as_str()])
+                        .set(memory_bytes as f64);
+
+                    // accumulated_process_time is wall-clock seconds the process has
+                    // consumed CPU for; sysinfo exposes it as run_time() scaled by
+                    // cpu_usage(), so derive a monotonically increasing counter here
+                    let cpu_seconds = process.run_time() as f64 * (process.cpu_usage() as f64 / 100.0);
+                    let now = Instant::now();
+
+                    if let Some(prev) = previous.get(sandbox_id) {
+                        let elapsed_wall = now.duration_since(prev.sampled_at).as_secs_f64();
+                        if elapsed_wall > 0.0 {
+                            let delta_cpu = (cpu_seconds - prev.cpu_seconds).max(0.0);
+                            self.metrics.sandbox_cpu_usage.observe(delta_cpu / elapsed_wall);
+                        }
+                    }
+
+                    previous.insert(sandbox_id.clone(), PidSample { cpu_seconds, sampled_at: now });
+                }
+
+                // Drop samples for sandboxes that were unregistered since the last tick
+                previous.retain(|id, _| registered.contains_key(id));
+            }
+        });
+    }
+}
+
+/// Periodically encodes the Prometheus registry and POSTs it to a
+/// Pushgateway so executions that exit before a scrape (batch jobs,
+/// one-shot sandboxed runs) still contribute their counters/histograms
+struct PushGatewayReporter {
+    registry: Registry,
+    config: PushGatewayConfig,
+    client: reqwest::Client,
+}
+
+impl PushGatewayReporter {
+    fn new(registry: Registry, config: PushGatewayConfig) -> Self {
+        Self { registry, config, client: reqwest::Client::new() }
+    }
+
+    fn start(self: Arc<Self>) {
+        info!(
+            "Starting Pushgateway reporter, pushing every {:?} to {}",
+            self.config.push_interval, self.config.url
+        );
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.config.push_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.push().await {
+                    error!("Pushgateway push failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Build the grouping-key URL following the Pushgateway `job`/`instance` convention
+    fn push_url(&self) -> String {
+        let hostname = sys_info::hostname().unwrap_or_else(|_| "unknown".to_string());
+        format!(
+            "{}/metrics/job/{}/instance/{}",
+            self.config.url.trim_end_matches('/'),
+            self.config.job,
+            hostname
+        )
+    }
+
+    async fn push(&self) -> Result<()> {
+        let encoder = TextEncoder::new();
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        encoder.encode(&metric_families, &mut buffer)?;
+
+        let mut request = self.client.post(self.push_url()).body(buffer);
+        if let Some(auth_header) = &self.config.auth_header {
+            request = request.header("Authorization", auth_header);
+        }
+
+        let response = request.send().await.context("Pushgateway request failed")?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Pushgateway returned status {}",
+                response.status()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Synchronous final push guaranteeing no terminal-state counters are
+    /// dropped when the process tears down
+    async fn flush(&self) -> Result<()> {
+        debug!("Flushing final metrics to Pushgateway at {}", self.config.url);
+        self.push().await
+    }
+}
+
+/// Push-based OpenTelemetry OTLP exporter that mirrors the Prometheus
+/// registry and emits per-execution spans so a single agent run can be
+/// correlated across the sandbox, security, and FSM subsystems in a
+/// distributed tracing backend (Jaeger/Tempo/Grafana Agent)
+pub struct OtelExporter {
+    registry: Registry,
+    config: OtelExporterConfig,
+    tracer: global::BoxedTracer,
+}
+
+impl OtelExporter {
+    fn new(registry: Registry, config: OtelExporterConfig) -> Result<Self> {
+        let exporter = opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(&config.collector_endpoint);
+
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(
+                opentelemetry_sdk::trace::config().with_resource(opentelemetry_sdk::Resource::new(vec![
+                    KeyValue::new("service.name", config.service_name.clone()),
+                ])),
+            )
+            .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+        let tracer = global::tracer(config.service_name.clone());
+
+        Ok(Self { registry, config, tracer })
+    }
+
+    /// Spawn the periodic registry -> OTLP metrics bridge
+    fn start(self: Arc<Self>) {
+        info!(
+            "Starting OTLP exporter, shipping metrics every {:?} to {}",
+            self.config.export_interval, self.config.collector_endpoint
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.export_interval);
+            loop {
+                interval.tick().await;
+                self.export_registry_snapshot();
+            }
+        });
+    }
+
+    /// Mirror the Prometheus registry through to the OTLP metrics pipeline.
+    /// Each gathered metric family becomes a short-lived span carrying the
+    /// sample as an attribute, since the bridge runs on the same cadence as
+    /// a Prometheus reader rather than maintaining its own aggregation state.
+    fn export_registry_snapshot(&self) {
+        let families = self.registry.gather();
+        let mut span = self.tracer.start("metrics_snapshot");
+        span.set_attribute(KeyValue::new("metric_families", families.len() as i64));
+
+        for family in &families {
+            for metric in family.get_metric() {
+                let value = if metric.has_counter() {
+                    metric.get_counter().get_value()
+                } else if metric.has_gauge() {
+                    metric.get_gauge().get_value()
+                } else {
+                    continue;
+                };
+                span.add_event(
+                    family.get_name().to_string(),
+                    vec![KeyValue::new("value", value)],
+                );
+            }
+        }
+
+        span.end();
+    }
+
+    /// Emit a span for a single `record_agent_execution` call carrying the
+    /// execution's identifying attributes
+    fn record_execution_span(&self, execution_id: &str, language: &str, tokens_used: u32, success: bool, duration: Duration) {
+        let mut span = self
+            .tracer
+            .span_builder("agent_execution")
+            .with_attributes(vec![
+                KeyValue::new("execution_id", execution_id.to_string()),
+                KeyValue::new("language", language.to_string()),
+                KeyValue::new("tokens_used", tokens_used as i64),
+                KeyValue::new("success", success),
+            ])
+            .start(&self.tracer);
+
+        span.add_event(
+            "execution_completed",
+            vec![KeyValue::new("duration_ms", duration.as_millis() as i64)],
+        );
+        span.end();
+    }
+
+    /// Emit an FSM transition as a child span event keyed by from/to state
+    fn record_transition_event(&self, from_state: &str, to_state: &str, duration: Duration) {
+        let cx = OtelContext::current();
+        let span = cx.span();
+        span.add_event(
+            "fsm_transition",
+            vec![
+                KeyValue::new("from_state", from_state.to_string()),
+                KeyValue::new("to_state", to_state.to_string()),
+                KeyValue::new("duration_ms", duration.as_millis() as i64),
+            ],
+        );
     }
 }
 
@@ -450,4 +1125,15 @@ pub struct MetricsSummary {
     pub average_duration: Duration,
     pub total_tokens: u64,
     pub average_tokens: u64,
+    /// True if at least one `ingest_external_report` call has contributed to
+    /// these totals, i.e. they aren't purely from executions observed directly
+    pub includes_external_data: bool,
+    /// Latency quantiles from the most recently ingested external report, if any
+    pub external_quantiles: Option<ExternalQuantiles>,
+    pub p50_duration: Duration,
+    pub p95_duration: Duration,
+    pub p99_duration: Duration,
+    pub p50_tokens: f64,
+    pub p95_tokens: f64,
+    pub p99_tokens: f64,
 }
\ No newline at end of file
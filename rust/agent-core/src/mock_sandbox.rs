@@ -0,0 +1,149 @@
+//! Scripted [`SandboxBackend`] used to exercise `ExecutionEngine`'s
+//! orchestration logic (security → enforcement → FSM transitions → metrics)
+//! against deterministic, programmable sandbox responses instead of a real
+//! Wasmtime engine. Test-only: see `execution::tests` for the fixture-replay
+//! tests built on top of this.
+#![cfg(test)]
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use ring::digest;
+
+use crate::sandbox::{ExecutionContext, ExecutionResult, SandboxBackend};
+
+/// Hex-encoded SHA-256 of `code`, used to key the scripted-response registry
+/// so a fixture can reference a response by hash without restating the full
+/// source alongside it.
+pub fn code_hash(code: &[u8]) -> String {
+    digest::digest(&digest::SHA256, code)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Terminal outcome of a single scripted [`MockSandbox`] call
+pub enum ScriptedOutcome {
+    /// Hand back a fully-formed `ExecutionResult`, success or otherwise
+    Result(ExecutionResult),
+    /// Fail the call itself, as `?` would inside a real sandbox method
+    /// (e.g. a Wasmtime instantiation error), as opposed to an
+    /// `ExecutionResult` whose `status` reports the failure
+    Error(String),
+}
+
+/// One queued reply to a `SandboxBackend` call
+pub struct ScriptedResponse {
+    outcome: ScriptedOutcome,
+    /// Simulated wall-clock cost paid before `outcome` is returned, so a
+    /// fixture can script a slow execution without an actual sleep in the
+    /// sandbox under test
+    delay: Duration,
+}
+
+impl ScriptedResponse {
+    pub fn success(result: ExecutionResult) -> Self {
+        Self { outcome: ScriptedOutcome::Result(result), delay: Duration::ZERO }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { outcome: ScriptedOutcome::Error(message.into()), delay: Duration::ZERO }
+    }
+
+    /// Have this response pay `delay` before resolving
+    pub fn after(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A single observed `execute_*` call, recorded so a test can assert the
+/// exact sequence and arguments the engine drove the sandbox with
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    pub method: &'static str,
+    pub code_hash: String,
+    pub execution_id: String,
+}
+
+/// `SandboxBackend` driven entirely by a scripted registry instead of a real
+/// Wasmtime engine, so `ExecutionEngine`'s coordination path can be unit
+/// tested in CI with no external runtime. Each code hash maps to a FIFO
+/// queue of responses, one popped per matching call, so a fixture can script
+/// a sequence (e.g. timeout then success) for repeated runs of the same
+/// code.
+#[derive(Default)]
+pub struct MockSandbox {
+    registry: Mutex<HashMap<String, VecDeque<ScriptedResponse>>>,
+    calls: Mutex<Vec<RecordedCall>>,
+}
+
+impl MockSandbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `response` to be returned the next time any `execute_*` method
+    /// is called with code hashing to `code_hash(code)`
+    pub fn script(&self, code: &[u8], response: ScriptedResponse) {
+        self.registry.lock().unwrap().entry(code_hash(code)).or_default().push_back(response);
+    }
+
+    /// The call log in invocation order, for asserting the engine drove the
+    /// sandbox with the expected code and call count
+    pub fn recorded_calls(&self) -> Vec<RecordedCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    async fn respond(
+        &self,
+        method: &'static str,
+        code: &[u8],
+        context: &ExecutionContext,
+    ) -> Result<ExecutionResult> {
+        let hash = code_hash(code);
+
+        self.calls.lock().unwrap().push(RecordedCall {
+            method,
+            code_hash: hash.clone(),
+            execution_id: context.execution_id.clone(),
+        });
+
+        let scripted = {
+            let mut registry = self.registry.lock().unwrap();
+            let queue = registry
+                .get_mut(&hash)
+                .ok_or_else(|| anyhow!("MockSandbox: no scripted response for code hash {hash}"))?;
+            queue
+                .pop_front()
+                .ok_or_else(|| anyhow!("MockSandbox: scripted response queue exhausted for code hash {hash}"))?
+        };
+
+        if !scripted.delay.is_zero() {
+            tokio::time::sleep(scripted.delay).await;
+        }
+
+        match scripted.outcome {
+            ScriptedOutcome::Result(result) => Ok(result),
+            ScriptedOutcome::Error(message) => Err(anyhow!(message)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl SandboxBackend for MockSandbox {
+    async fn execute_python(&self, code: &str, context: ExecutionContext) -> Result<ExecutionResult> {
+        self.respond("execute_python", code.as_bytes(), &context).await
+    }
+
+    async fn execute_javascript(&self, code: &str, context: ExecutionContext) -> Result<ExecutionResult> {
+        self.respond("execute_javascript", code.as_bytes(), &context).await
+    }
+
+    async fn execute_wasm(&self, code: &[u8], context: ExecutionContext) -> Result<ExecutionResult> {
+        self.respond("execute_wasm", code, &context).await
+    }
+}
@@ -0,0 +1,234 @@
+//! Post-bind privilege drop. [`drop_privileges`] runs once, after the
+//! gRPC/metrics listen sockets are already bound, so a supervisor that
+//! started as root to claim a privileged port can still hand the rest of
+//! the process off to an unprivileged user: resolve the configured
+//! `run_as_user`/`run_as_group`, `chroot` into `chroot_dir` (the sandbox's
+//! temp dir when that is unset), `setgid`/`setuid`, and verify the drop
+//! actually stuck.
+
+use tracing::info;
+
+use crate::config::PrivilegeConfig;
+
+/// Errors raised while resolving or applying a privilege drop
+#[derive(Debug, thiserror::Error)]
+pub enum PrivilegeError {
+    #[error("privilege drop was requested but this platform is not Unix")]
+    UnsupportedPlatform,
+
+    #[error("unknown user: {0}")]
+    UnknownUser(String),
+
+    #[error("unknown group: {0}")]
+    UnknownGroup(String),
+
+    #[error("chroot to {0:?} failed: {1}")]
+    ChrootFailed(std::path::PathBuf, String),
+
+    #[error("setgroups failed: {0}")]
+    SetGroupsFailed(String),
+
+    #[error("setgid failed: {0}")]
+    SetGidFailed(String),
+
+    #[error("setuid failed: {0}")]
+    SetUidFailed(String),
+
+    #[error("privilege drop did not take effect: re-acquiring root via setuid(0) unexpectedly succeeded")]
+    DropDidNotTakeEffect,
+
+    #[error("privilege drop did not take effect: process still belongs to {0} supplementary group(s)")]
+    GroupsNotCleared(usize),
+}
+
+/// Drop root privileges after the listen sockets are already bound. A no-op
+/// when `config.run_as_user` is unset. `sandbox_temp_dir` is used as the
+/// chroot target when `config.chroot_dir` is unset, since that's the only
+/// directory a sandboxed agent execution needs access to after the drop.
+#[cfg(unix)]
+pub fn drop_privileges(
+    config: &PrivilegeConfig,
+    sandbox_temp_dir: &std::path::Path,
+) -> Result<(), PrivilegeError> {
+    let Some(user) = &config.run_as_user else {
+        return Ok(());
+    };
+
+    let uid = unix::resolve_uid(user)?;
+    let gid = match &config.run_as_group {
+        Some(group) => unix::resolve_gid(group)?,
+        None => unix::primary_gid(user)?,
+    };
+
+    let chroot_dir = config.chroot_dir.as_deref().unwrap_or(sandbox_temp_dir);
+    unix::chroot(chroot_dir)?;
+
+    // Clear supplementary groups inherited from the parent (root) process
+    // before anything else: setgid/setuid only change the real/effective
+    // ids, not the group list, so without this the dropped-to user would
+    // keep membership in whatever privileged groups (possibly including
+    // gid 0) the parent process happened to carry.
+    unix::clear_supplementary_groups()?;
+
+    // setgid before setuid: once we're no longer root, we can't change our
+    // group anymore.
+    unix::setgid(gid)?;
+    unix::setuid(uid)?;
+    unix::verify_drop()?;
+
+    info!("Dropped privileges to uid={} gid={} (chroot={:?})", uid, gid, chroot_dir);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn drop_privileges(
+    config: &PrivilegeConfig,
+    _sandbox_temp_dir: &std::path::Path,
+) -> Result<(), PrivilegeError> {
+    if config.run_as_user.is_some() {
+        return Err(PrivilegeError::UnsupportedPlatform);
+    }
+    Ok(())
+}
+
+/// Whether `name` resolves to a real user on this platform. Used by
+/// `Config::validate` to reject `privilege.run_as_user` typos at startup
+/// rather than at drop time.
+#[cfg(unix)]
+pub fn user_exists(name: &str) -> bool {
+    unix::resolve_uid(name).is_ok()
+}
+
+#[cfg(not(unix))]
+pub fn user_exists(_name: &str) -> bool {
+    false
+}
+
+/// Whether `name` resolves to a real group on this platform, mirroring
+/// [`user_exists`].
+#[cfg(unix)]
+pub fn group_exists(name: &str) -> bool {
+    unix::resolve_gid(name).is_ok()
+}
+
+#[cfg(not(unix))]
+pub fn group_exists(_name: &str) -> bool {
+    false
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::PrivilegeError;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    pub fn resolve_uid(user: &str) -> Result<libc::uid_t, PrivilegeError> {
+        let passwd = lookup_passwd(user)?;
+        Ok(passwd.pw_uid)
+    }
+
+    pub fn primary_gid(user: &str) -> Result<libc::gid_t, PrivilegeError> {
+        let passwd = lookup_passwd(user)?;
+        Ok(passwd.pw_gid)
+    }
+
+    pub fn resolve_gid(group: &str) -> Result<libc::gid_t, PrivilegeError> {
+        let cname =
+            CString::new(group).map_err(|_| PrivilegeError::UnknownGroup(group.to_string()))?;
+        // SAFETY: `cname` is a valid, NUL-terminated C string for the
+        // duration of this call; `getgrnam` returns a pointer into
+        // thread-local/static storage that we only read before returning.
+        let entry = unsafe { libc::getgrnam(cname.as_ptr()) };
+        if entry.is_null() {
+            return Err(PrivilegeError::UnknownGroup(group.to_string()));
+        }
+        // SAFETY: `entry` was just checked non-null and was populated by `getgrnam`.
+        Ok(unsafe { (*entry).gr_gid })
+    }
+
+    fn lookup_passwd(user: &str) -> Result<libc::passwd, PrivilegeError> {
+        let cname =
+            CString::new(user).map_err(|_| PrivilegeError::UnknownUser(user.to_string()))?;
+        // SAFETY: `cname` is a valid, NUL-terminated C string for the
+        // duration of this call; `getpwnam` returns a pointer into
+        // thread-local/static storage that we only read before returning.
+        let entry = unsafe { libc::getpwnam(cname.as_ptr()) };
+        if entry.is_null() {
+            return Err(PrivilegeError::UnknownUser(user.to_string()));
+        }
+        // SAFETY: `entry` was just checked non-null and was populated by `getpwnam`.
+        Ok(unsafe { *entry })
+    }
+
+    pub fn chroot(dir: &std::path::Path) -> Result<(), PrivilegeError> {
+        let cpath = CString::new(dir.as_os_str().as_bytes())
+            .map_err(|e| PrivilegeError::ChrootFailed(dir.to_path_buf(), e.to_string()))?;
+        // SAFETY: `cpath` is a valid, NUL-terminated C string for the
+        // duration of this call.
+        let rc = unsafe { libc::chroot(cpath.as_ptr()) };
+        if rc != 0 {
+            return Err(PrivilegeError::ChrootFailed(
+                dir.to_path_buf(),
+                std::io::Error::last_os_error().to_string(),
+            ));
+        }
+        std::env::set_current_dir("/")
+            .map_err(|e| PrivilegeError::ChrootFailed(dir.to_path_buf(), e.to_string()))
+    }
+
+    /// Drop every supplementary group the current (root) process carries.
+    /// Must run before `setgid`/`setuid`: `setgroups` requires `CAP_SETGID`,
+    /// which is only still held while the process is root.
+    pub fn clear_supplementary_groups() -> Result<(), PrivilegeError> {
+        // SAFETY: passing a zero count with a null list is the documented
+        // way to clear the supplementary group list; no buffer is read.
+        let rc = unsafe { libc::setgroups(0, std::ptr::null()) };
+        if rc != 0 {
+            return Err(PrivilegeError::SetGroupsFailed(std::io::Error::last_os_error().to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn setgid(gid: libc::gid_t) -> Result<(), PrivilegeError> {
+        // SAFETY: `setgid` has no preconditions beyond a valid gid.
+        let rc = unsafe { libc::setgid(gid) };
+        if rc != 0 {
+            return Err(PrivilegeError::SetGidFailed(std::io::Error::last_os_error().to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn setuid(uid: libc::uid_t) -> Result<(), PrivilegeError> {
+        // SAFETY: `setuid` has no preconditions beyond a valid uid.
+        let rc = unsafe { libc::setuid(uid) };
+        if rc != 0 {
+            return Err(PrivilegeError::SetUidFailed(std::io::Error::last_os_error().to_string()));
+        }
+        Ok(())
+    }
+
+    /// Confirm the drop can't be undone: once we're no longer root,
+    /// `setuid(0)` must fail with `EPERM` rather than silently re-granting
+    /// it, and the supplementary group list cleared by
+    /// `clear_supplementary_groups` must still be empty -- re-acquiring
+    /// either would let the process reach back into root-owned resources.
+    pub fn verify_drop() -> Result<(), PrivilegeError> {
+        // SAFETY: `setuid` has no preconditions beyond a valid uid; this call
+        // is expected to fail, which is exactly what we're checking for.
+        let rc = unsafe { libc::setuid(0) };
+        if rc == 0 {
+            return Err(PrivilegeError::DropDidNotTakeEffect);
+        }
+
+        // SAFETY: a size of 0 with a null buffer is the documented way to
+        // query the supplementary group count without reading into memory;
+        // `getgroups` returns that count (or -1 on error, which is treated
+        // the same as "not provably empty" since EPERM/EINVAL can't happen
+        // here and anything else is unexpected enough to fail closed).
+        let count = unsafe { libc::getgroups(0, std::ptr::null_mut()) };
+        if count != 0 {
+            return Err(PrivilegeError::GroupsNotCleared(count.max(0) as usize));
+        }
+        Ok(())
+    }
+}
@@ -1,33 +1,428 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, Semaphore};
+use tokio::sync::{watch, Mutex, Semaphore};
 use tracing::{debug, error, info, warn};
-use uuid::Uuid;
 
 use wasmtime::*;
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder};
 
-use crate::config::SandboxConfig;
+use wasm_instrument::gas_metering;
+use wasm_instrument::parity_wasm::elements::{deserialize_buffer, serialize, Module as PwasmModule};
+
+use crate::config::{Backend, SandboxConfig};
+use crate::security::SecurityManager;
+
+/// Export name of the mutable gas-accounting global injected by
+/// `instrument_wasm` into every user-supplied module before it is compiled.
+const GAS_GLOBAL_EXPORT: &str = "gas_left";
+
+/// Stack-height budget (in abstract stack-slot units counted by the injected
+/// limiter, not native stack bytes) enforced on every instrumented module.
+const STACK_HEIGHT_LIMIT: u32 = 65536;
+
+/// Code-execution surface `ExecutionEngine` drives, abstracted away from
+/// `WASISandbox` so the orchestration logic above it (security →
+/// enforcement → FSM transitions → metrics) can be exercised against a
+/// scripted `MockSandbox` instead of a real Wasmtime engine. Mirrors
+/// `WASISandbox::execute_python`/`execute_javascript`/`execute_wasm` exactly;
+/// `WASISandbox` itself implements this trait with no behavior change.
+#[tonic::async_trait]
+pub trait SandboxBackend: Send + Sync {
+    async fn execute_python(&self, code: &str, context: ExecutionContext) -> Result<ExecutionResult>;
+    async fn execute_javascript(&self, code: &str, context: ExecutionContext) -> Result<ExecutionResult>;
+    async fn execute_wasm(&self, code: &[u8], context: ExecutionContext) -> Result<ExecutionResult>;
+}
+
+/// Instrumented-module execution abstracted away from the specific engine,
+/// so [`WASISandbox::execute_wasm`] can run a guest module on either
+/// Wasmtime's JIT or wasmi's interpreter depending on `SandboxConfig::backend`
+/// without the caller branching on it. `WASISandbox` itself plays the role
+/// of the Wasmtime implementation (it already owns the `Engine`/pool/WASI
+/// plumbing that path needs); [`WasmiExecutionBackend`] is the interpreter
+/// fallback, held as a plain field and selected with a `match` rather than a
+/// `Box<dyn WasmExecutionBackend>`, since this trait's async methods hit the
+/// same dyn-compatibility wrinkle as [`SandboxBackend`] above.
+#[tonic::async_trait]
+trait WasmExecutionBackend: Send + Sync {
+    /// Run an already gas/stack-instrumented module's `_start` entry point
+    /// to completion (or to a trap/fuel exhaustion) and build the resulting
+    /// `ExecutionResult`.
+    async fn execute(
+        &self,
+        instrumented: &[u8],
+        context: ExecutionContext,
+        start_time: Instant,
+    ) -> Result<ExecutionResult>;
+}
 
 /// WASI Sandbox provides secure execution environment for agent code
 pub struct WASISandbox {
     config: SandboxConfig,
     engine: Engine,
-    instances: Arc<Mutex<HashMap<String, SandboxInstance>>>,
+    /// Precompiled once in `new()` so `execute_python`/`execute_javascript`
+    /// only ever pay instantiation cost, never recompilation.
+    python_module: Module,
+    javascript_module: Module,
+    /// Total completed Python/JS executions, for `get_stats`. There used to
+    /// be a pool of checked-out-and-returned `SandboxInstance`s here, keyed
+    /// by language, with each instance's own counter summed for this number
+    /// -- but reusing an `Instance` (and its linear memory) across
+    /// executions let one tenant's guest heap leak into the next tenant's,
+    /// since only the WASI context/fuel/epoch were reset on checkout, never
+    /// the memory. The engine's `PoolingAllocationStrategy` configured in
+    /// `new()` already pre-allocates and recycles `max_instances` worth of
+    /// mmap'd, zeroed memory slots, so a fresh `Store`/`Instance` per
+    /// execution is exactly as cheap as popping one off a hand-rolled pool
+    /// would have been, without the cross-tenant leak.
+    execution_count: Arc<AtomicU64>,
     semaphore: Arc<Semaphore>,
+    /// Interpreter fallback `execute_wasm` dispatches to when
+    /// `config.backend == Backend::Wasmi`. Built unconditionally — a bare
+    /// `wasmi::Engine` is cheap enough that gating its construction on the
+    /// selected backend isn't worth the `Option` it would cost every other
+    /// field access.
+    wasmi_backend: WasmiExecutionBackend,
+    /// Shared with `SecurityManager::validate_network_access`'s callers;
+    /// used here only for its egress CIDR/private-range policy, so the
+    /// WASI `socket_addr_check` in `create_wasi_context` enforces the same
+    /// safety floor as every other egress-control point in the crate
+    /// instead of re-deriving a narrower one.
+    security: Arc<SecurityManager>,
 }
 
-/// Represents a single sandbox instance
+/// A freshly instantiated Store+Instance for one execution. Not reused
+/// across executions (see [`WASISandbox::execution_count`]'s doc comment for
+/// why) -- this just bundles together what `execute_pooled` needs to hand to
+/// `execute_in_instance`.
 pub struct SandboxInstance {
-    pub id: String,
-    pub store: Store<WasiCtx>,
+    pub store: Store<StoreData>,
     pub instance: Instance,
-    pub created_at: Instant,
-    pub last_used: Instant,
-    pub execution_count: u64,
+}
+
+/// Per-store payload for every `Store<StoreData>` this module creates: the
+/// guest's WASI context plus the `StoreLimits` that back `memory_limit`
+/// enforcement. `StoreLimits::memory_growing` alone only ever reports the
+/// instance's *current* size, which a trap unwinds away from before
+/// `get_memory_usage` can read it back out — `memory_high_water` keeps the
+/// peak requested size around so the caller still sees it after a denied
+/// grow, and `memory_growth_failed` lets the trap handler map that denial to
+/// `ExecutionStatus::MemoryLimit` instead of a generic runtime error.
+pub struct StoreData {
+    wasi: WasiCtx,
+    limits: StoreLimits,
+    memory_high_water: usize,
+    memory_growth_failed: bool,
+    network: NetworkAccounting,
+}
+
+impl StoreData {
+    fn new(wasi: WasiCtx, memory_limit: u64, network: NetworkAccounting) -> Self {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(memory_limit as usize)
+            .instances(1)
+            .build();
+
+        Self {
+            wasi,
+            limits,
+            memory_high_water: 0,
+            memory_growth_failed: false,
+            network,
+        }
+    }
+}
+
+/// Counters shared between the `SocketAddrCheck` closure `create_wasi_context`
+/// installs (called from deep inside `wasmtime_wasi` on every guest
+/// `connect`, with no access to the `Store`) and the result-classification
+/// code in `execute_in_instance`/`execute_wasm_instance` after the call
+/// returns.
+#[derive(Clone, Default)]
+struct NetworkAccounting {
+    permitted: Arc<std::sync::atomic::AtomicU64>,
+    denied: Arc<AtomicBool>,
+}
+
+impl NetworkAccounting {
+    fn permitted_count(&self) -> u64 {
+        self.permitted.load(Ordering::SeqCst)
+    }
+
+    fn was_denied(&self) -> bool {
+        self.denied.load(Ordering::SeqCst)
+    }
+}
+
+/// Resolve `allowed_hosts` entries (`host` or `host:port`) to concrete
+/// `(addresses, port)` pairs once, before the guest runs. The
+/// `SocketAddrCheck` closure installed by `create_wasi_context` only ever
+/// sees the already-resolved `SocketAddr` the guest is connecting to, with
+/// no access to the hostname it started from, so it has no opportunity to
+/// do its own DNS lookup -- a hostname entry that isn't resolved here can
+/// never match anything, silently dropping it from the allowlist. `host` is
+/// taken as a literal IP, `"localhost"` (both loopback families), or failing
+/// both, a DNS name resolved with the system resolver.
+async fn resolve_allowed_hosts(allowed_hosts: &[String]) -> Vec<(Vec<std::net::IpAddr>, Option<u16>)> {
+    let mut resolved = Vec::with_capacity(allowed_hosts.len());
+    for entry in allowed_hosts {
+        let (host, port) = match entry.rsplit_once(':') {
+            Some((host, port_str)) => match port_str.parse::<u16>() {
+                Ok(port) => (host, Some(port)),
+                Err(_) => (entry.as_str(), None),
+            },
+            None => (entry.as_str(), None),
+        };
+
+        let addresses = if host == "localhost" {
+            vec![
+                std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
+                std::net::IpAddr::V6(std::net::Ipv6Addr::LOCALHOST),
+            ]
+        } else if let Ok(ip) = host.parse::<std::net::IpAddr>() {
+            vec![ip]
+        } else {
+            tokio::net::lookup_host((host, port.unwrap_or(0)))
+                .await
+                .map(|addrs| addrs.map(|a| a.ip()).collect())
+                .unwrap_or_else(|e| {
+                    warn!("Failed to resolve allowed_hosts entry '{}': {}", entry, e);
+                    Vec::new()
+                })
+        };
+
+        resolved.push((addresses, port));
+    }
+    resolved
+}
+
+/// Check whether `addr` matches one of the pre-resolved `allowed_hosts`
+/// entries on both address and (if specified) port, *and* clears
+/// `security`'s shared egress firewall at the IP level (CIDR/private-range
+/// policy). The firewall check applies even to an explicit per-execution
+/// entry -- e.g. an `allowed_hosts: ["localhost"]` execution can no longer
+/// reach loopback unless the operator has also allowlisted it at the
+/// `SecurityConfig` level (`allowed_egress_cidrs`), closing the gap where
+/// this allowlist used to be a narrower, independent check that didn't
+/// apply the same private-address protections as `EgressFirewall`.
+fn socket_addr_allowed(
+    security: &SecurityManager,
+    resolved_hosts: &[(Vec<std::net::IpAddr>, Option<u16>)],
+    addr: &std::net::SocketAddr,
+) -> bool {
+    if !security.ip_is_safe_for_egress(addr.ip()) {
+        return false;
+    }
+    resolved_hosts.iter().any(|(addresses, port)| {
+        port.map(|p| p == addr.port()).unwrap_or(true) && addresses.contains(&addr.ip())
+    })
+}
+
+impl ResourceLimiter for StoreData {
+    fn memory_growing(&mut self, current: usize, desired: usize, maximum: Option<usize>) -> Result<bool> {
+        self.memory_high_water = self.memory_high_water.max(desired);
+        let allowed = self.limits.memory_growing(current, desired, maximum)?;
+        if !allowed {
+            self.memory_growth_failed = true;
+        }
+        Ok(allowed)
+    }
+
+    fn table_growing(&mut self, current: u32, desired: u32, maximum: Option<u32>) -> Result<bool> {
+        self.limits.table_growing(current, desired, maximum)
+    }
+}
+
+/// Interpreter fallback for [`WASISandbox::execute_wasm`], selected by
+/// `SandboxConfig::backend == Backend::Wasmi`. Runs the same gas/stack
+/// instrumented module Wasmtime would, mapping wasmi's own fuel counter to
+/// `cpu_limit` and a `wasmi::StoreLimits` to `memory_limit` so
+/// `ExecutionResult`/`ExecutionMetrics` read the same regardless of which
+/// engine produced them. Unlike the Wasmtime path, it does not wire up WASI:
+/// a wasmi-executed module sees only its own linear memory and the injected
+/// gas global, not `ExecutionContext::environment`/`allowed_hosts` — those
+/// still require `Backend::Wasmtime`.
+struct WasmiExecutionBackend {
+    engine: wasmi::Engine,
+}
+
+impl WasmiExecutionBackend {
+    fn new() -> Self {
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        Self { engine: wasmi::Engine::new(&config) }
+    }
+}
+
+#[tonic::async_trait]
+impl WasmExecutionBackend for WasmiExecutionBackend {
+    async fn execute(
+        &self,
+        instrumented: &[u8],
+        context: ExecutionContext,
+        start_time: Instant,
+    ) -> Result<ExecutionResult> {
+        let execution_id = context.execution_id.clone();
+        let initial_gas = context.cpu_limit;
+
+        let module = wasmi::Module::new(&self.engine, instrumented)
+            .context("Failed to compile instrumented WASM module for wasmi")?;
+
+        let limits = wasmi::StoreLimitsBuilder::new()
+            .memory_size(context.memory_limit as usize)
+            .build();
+        let mut store = wasmi::Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+
+        store.set_fuel(initial_gas)
+            .context("Failed to set wasmi fuel limit")?;
+
+        let linker = wasmi::Linker::new(&self.engine);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .context("Failed to instantiate WASM module on wasmi")?
+            .start(&mut store)
+            .context("Failed to run wasmi module start function")?;
+
+        if let Some(wasmi::Extern::Global(gas_global)) = instance.get_export(&store, GAS_GLOBAL_EXPORT) {
+            gas_global.set(&mut store, wasmi::Val::I64(initial_gas as i64))
+                .context("Failed to seed gas global")?;
+        }
+
+        let entry = instance
+            .get_typed_func::<(), ()>(&store, "_start")
+            .context("Failed to get _start function")?;
+
+        let call_result = entry.call(&mut store, ());
+        let duration = start_time.elapsed();
+        let fuel_consumed = initial_gas.saturating_sub(store.get_fuel().unwrap_or(0));
+
+        match call_result {
+            Ok(()) => {
+                let output = Self::read_output(&mut store, &instance).unwrap_or_default();
+                let memory_used = instance
+                    .get_memory(&store, "memory")
+                    .map(|m| m.data(&store).len() as u64)
+                    .unwrap_or(0);
+
+                Ok(ExecutionResult {
+                    execution_id,
+                    status: ExecutionStatus::Success,
+                    output,
+                    error_message: None,
+                    metrics: ExecutionMetrics {
+                        memory_used,
+                        cpu_time: Duration::from_nanos(fuel_consumed),
+                        syscalls_count: 0,
+                        file_operations: 0,
+                        network_requests: 0,
+                        network_bytes_sent: 0,
+                        network_bytes_received: 0,
+                        gas_consumed: fuel_consumed,
+                        trap_reason: None,
+                    },
+                    duration,
+                    profile_path: None,
+                })
+            }
+            Err(e) => {
+                // wasmi's `Error` doesn't expose the same `Trap` enum
+                // Wasmtime does, so fuel exhaustion — the one case worth
+                // telling apart from a generic trap — is detected by the
+                // remaining fuel hitting zero rather than by matching the
+                // error variant.
+                let (status, error_message) = if fuel_consumed >= initial_gas {
+                    (ExecutionStatus::CpuLimit, "Execution unit budget exhausted".to_string())
+                } else {
+                    (ExecutionStatus::RuntimeError, format!("Runtime error: {}", e))
+                };
+
+                Ok(ExecutionResult {
+                    execution_id,
+                    status,
+                    output: String::new(),
+                    error_message: Some(error_message.clone()),
+                    metrics: ExecutionMetrics {
+                        memory_used: 0,
+                        cpu_time: duration,
+                        syscalls_count: 0,
+                        file_operations: 0,
+                        network_requests: 0,
+                        network_bytes_sent: 0,
+                        network_bytes_received: 0,
+                        gas_consumed: fuel_consumed,
+                        trap_reason: Some(error_message),
+                    },
+                    duration,
+                    profile_path: None,
+                })
+            }
+        }
+    }
+}
+
+impl WasmiExecutionBackend {
+    /// Read the guest's `get_output_ptr()`/`get_output_len()`-reported
+    /// region out of its `memory` export, mirroring
+    /// `WASISandbox::get_execution_output`'s contract but synchronously,
+    /// since wasmi has no host imports to await on mid-call.
+    fn read_output(store: &mut wasmi::Store<wasmi::StoreLimits>, instance: &wasmi::Instance) -> Option<String> {
+        let memory = instance.get_memory(&mut *store, "memory")?;
+        let ptr = instance
+            .get_typed_func::<(), i32>(&mut *store, "get_output_ptr")
+            .ok()?
+            .call(&mut *store, ())
+            .ok()?;
+        let len = instance
+            .get_typed_func::<(), i32>(&mut *store, "get_output_len")
+            .ok()?
+            .call(&mut *store, ())
+            .ok()?;
+
+        if len < 0 {
+            return None;
+        }
+
+        let start = ptr as usize;
+        let end = start.checked_add(len as usize)?;
+        let data = memory.data(&mut *store);
+        if end > data.len() {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&data[start..end]).into_owned())
+    }
+}
+
+/// Failure reading or writing a guest module's `malloc`/`memory`/
+/// `get_output_ptr`/`get_output_len` ABI, kept distinct from a plain trap so
+/// callers can classify it as `ExecutionStatus::CompilationError`/
+/// `ExecutionStatus::MemoryLimit` instead of a crashed execution.
+#[derive(Debug, thiserror::Error)]
+enum GuestAbiError {
+    #[error("WASM module does not export required ABI symbol: {0}")]
+    MissingAbi(String),
+    #[error("{0}")]
+    MemoryLimit(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Map a [`GuestAbiError`] to the `(status, message)` pair an
+/// `ExecutionResult` should report, propagating anything else (a genuine
+/// trap while calling `malloc`/`get_output_ptr`/`get_output_len`) as an error
+/// since it isn't specific to the guest ABI contract.
+fn guest_abi_status(err: GuestAbiError) -> Result<(ExecutionStatus, String)> {
+    match err {
+        GuestAbiError::MissingAbi(what) => {
+            Ok((ExecutionStatus::CompilationError, format!("WASM module does not export required ABI symbol: {}", what)))
+        }
+        GuestAbiError::MemoryLimit(msg) => Ok((ExecutionStatus::MemoryLimit, msg)),
+        GuestAbiError::Other(e) => Err(e),
+    }
 }
 
 /// Execution context for sandbox operations
@@ -42,6 +437,15 @@ pub struct ExecutionContext {
     pub timeout: Duration,
     pub allowed_hosts: Vec<String>,
     pub environment: HashMap<String, String>,
+    /// "python"/"javascript"/"webassembly" — carried through purely for the
+    /// `SandboxConfig::trace_execution` log line, not used to pick a
+    /// backend (the caller already chose one by calling `execute_python`/
+    /// `execute_javascript`/`execute_wasm`).
+    pub language: String,
+    /// Flips to `true` to ask the running instance to abort early. Polled
+    /// alongside the timeout deadline so a caller can reclaim sandbox
+    /// resources without waiting for `timeout` to elapse.
+    pub cancel: watch::Receiver<bool>,
 }
 
 /// Result of code execution in sandbox
@@ -53,6 +457,10 @@ pub struct ExecutionResult {
     pub error_message: Option<String>,
     pub metrics: ExecutionMetrics,
     pub duration: Duration,
+    /// Firefox-profiler-format flamegraph written under the execution's
+    /// sandbox temp dir, present only when `SandboxConfig::enable_profiling`
+    /// was set and the module ran on the Wasmtime backend.
+    pub profile_path: Option<PathBuf>,
 }
 
 /// Execution status enumeration
@@ -65,6 +473,9 @@ pub enum ExecutionStatus {
     SecurityViolation,
     RuntimeError,
     CompilationError,
+    /// The caller cancelled the run via `ExecutionContext::cancel` before it
+    /// finished, as opposed to it running past its `timeout`.
+    Cancelled,
 }
 
 /// Execution metrics
@@ -74,12 +485,66 @@ pub struct ExecutionMetrics {
     pub cpu_time: Duration,
     pub syscalls_count: u64,
     pub file_operations: u64,
+    /// Connections permitted by the `ExecutionContext::allowed_hosts`
+    /// allowlist check installed in `create_wasi_context`. A denied attempt
+    /// is not counted here; it instead surfaces as
+    /// `ExecutionStatus::SecurityViolation`.
     pub network_requests: u64,
+    /// Bytes sent to `ExecutionContext::allowed_hosts` over the lifetime of
+    /// this execution. Not yet metered by any sandbox path below; always `0`
+    /// until byte-level egress accounting is added.
+    pub network_bytes_sent: u64,
+    /// Bytes received from `ExecutionContext::allowed_hosts`; see
+    /// `network_bytes_sent`
+    pub network_bytes_received: u64,
+    /// Execution units consumed against the instrumented `gas_left` global,
+    /// as tracked by `WASISandbox::execute_wasm`. Always `0` for the
+    /// Python/JS interpreter paths, which don't instrument their guest
+    /// modules and fall back to the byte/CPU-time heuristic instead.
+    pub gas_consumed: u64,
+    /// Human-readable reason for the trap that ended execution, if any.
+    pub trap_reason: Option<String>,
+}
+
+/// Tunables for [`WASISandbox::execute_wasm_sliced`]: how much fuel the
+/// guest burns between yields back to the executor, and the total fuel
+/// budget across the whole run. `slice_fuel` does not set when the caller
+/// is checked in with; that happens on each epoch tick.
+#[derive(Debug, Clone, Copy)]
+pub struct SliceConfig {
+    pub slice_fuel: u64,
+    pub max_cumulative_fuel: u64,
+}
+
+/// Progress reported at each epoch-tick checkpoint of a sliced execution.
+#[derive(Debug, Clone)]
+pub struct SliceExecutionOutput {
+    pub slice_fuel_used: u64,
+    pub cumulative_fuel: u64,
+    pub wall_time: Duration,
+}
+
+/// What the caller wants to do after inspecting a [`SliceExecutionOutput`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceDecision {
+    Continue,
+    TopUp(u64),
+    Cancel,
+}
+
+/// Bookkeeping threaded between slice checkpoints so each
+/// `SliceExecutionOutput` can report a per-slice delta rather than a
+/// cumulative total — the store/instance themselves stay on the stack of
+/// `execute_wasm_sliced`, this just tracks what's changed since the last
+/// checkpoint.
+struct PausedExecution {
+    fuel_used: u64,
+    last_checkpoint: Instant,
 }
 
 impl WASISandbox {
     /// Create a new WASI sandbox
-    pub async fn new(config: &SandboxConfig) -> Result<Self> {
+    pub async fn new(config: &SandboxConfig, security: Arc<SecurityManager>) -> Result<Self> {
         info!("Initializing WASI sandbox with config: {:?}", config);
 
         // Create Wasmtime engine with security configurations
@@ -91,11 +556,21 @@ impl WASISandbox {
         engine_config.consume_fuel(true);
         engine_config.epoch_interruption(true);
         engine_config.max_wasm_stack(1024 * 1024); // 1MB stack limit
-        
+
         // Memory configurations
         engine_config.static_memory_maximum_size(config.memory_limit);
         engine_config.dynamic_memory_guard_size(65536); // 64KB guard
-        
+
+        // Pre-size the allocator for exactly the pool this sandbox will
+        // hold (at most `max_instances` live Python/JS instances, each
+        // capped at `memory_limit`) so steady-state instantiation reuses
+        // mmap'd slots instead of going back to the kernel each time.
+        let mut pooling_config = PoolingAllocationConfig::new();
+        pooling_config.total_core_instances(config.max_instances as u32);
+        pooling_config.total_memories(config.max_instances as u32);
+        pooling_config.max_memory_size(config.memory_limit as usize);
+        engine_config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling_config));
+
         let engine = Engine::new(&engine_config)
             .context("Failed to create Wasmtime engine")?;
 
@@ -105,13 +580,22 @@ impl WASISandbox {
                 .context("Failed to create sandbox temp directory")?;
         }
 
+        // Compile the bundled interpreter modules once; `execute_python`/
+        // `execute_javascript` only ever instantiate them from here on.
+        let python_module = Self::compile_python_wasm_module(&engine)?;
+        let javascript_module = Self::compile_javascript_wasm_module(&engine)?;
+
         let semaphore = Arc::new(Semaphore::new(config.max_instances));
 
         Ok(Self {
             config: config.clone(),
             engine,
-            instances: Arc::new(Mutex::new(HashMap::new())),
+            python_module,
+            javascript_module,
+            execution_count: Arc::new(AtomicU64::new(0)),
             semaphore,
+            wasmi_backend: WasmiExecutionBackend::new(),
+            security,
         })
     }
 
@@ -121,47 +605,7 @@ impl WASISandbox {
         code: &str,
         context: ExecutionContext,
     ) -> Result<ExecutionResult> {
-        let start_time = Instant::now();
-        
-        debug!("Executing Python code in sandbox: {}", context.execution_id);
-
-        // Acquire semaphore permit
-        let _permit = self.semaphore.acquire().await
-            .context("Failed to acquire sandbox permit")?;
-
-        // Create WASI context with restrictions
-        let wasi_ctx = self.create_wasi_context(&context)?;
-        
-        // Create store with resource limits
-        let mut store = Store::new(&self.engine, wasi_ctx);
-        
-        // Set fuel limit (CPU time approximation)
-        store.set_fuel(context.cpu_limit)
-            .context("Failed to set fuel limit")?;
-        
-        // Set epoch deadline for timeout
-        store.set_epoch_deadline(1);
-        
-        // Create Python WASM module (this would be a pre-compiled Python interpreter)
-        let python_wasm = self.get_python_wasm_module().await?;
-        
-        // Instantiate the module
-        let instance = Instance::new_async(&mut store, &python_wasm, &[]).await
-            .context("Failed to instantiate Python WASM module")?;
-
-        // Execute the code
-        let result = self.execute_in_instance(
-            &mut store,
-            &instance,
-            code,
-            &context,
-            start_time,
-        ).await;
-
-        // Clean up and return result
-        self.cleanup_instance(&context.execution_id).await;
-        
-        result
+        self.execute_pooled("python", self.python_module.clone(), code, context).await
     }
 
     /// Execute JavaScript code in WASI sandbox
@@ -169,66 +613,99 @@ impl WASISandbox {
         &self,
         code: &str,
         context: ExecutionContext,
+    ) -> Result<ExecutionResult> {
+        self.execute_pooled("javascript", self.javascript_module.clone(), code, context).await
+    }
+
+    /// Shared body of `execute_python`/`execute_javascript`: instantiate the
+    /// precompiled `module` fresh for this execution, run the code, and tear
+    /// the instance down afterward rather than handing its Store/Instance
+    /// (and the linear memory underneath them) off to a later, unrelated
+    /// execution. A fresh `Store<StoreData>` means fresh `memory_high_water`/
+    /// `memory_growth_failed` too -- there's no stale resource-limiter state
+    /// left over from whatever ran here last.
+    async fn execute_pooled(
+        &self,
+        language: &str,
+        module: Module,
+        code: &str,
+        context: ExecutionContext,
     ) -> Result<ExecutionResult> {
         let start_time = Instant::now();
-        
-        debug!("Executing JavaScript code in sandbox: {}", context.execution_id);
 
-        // Similar implementation to Python but with JavaScript runtime
+        debug!("Executing {} code in sandbox: {}", language, context.execution_id);
+
         let _permit = self.semaphore.acquire().await
             .context("Failed to acquire sandbox permit")?;
 
-        let wasi_ctx = self.create_wasi_context(&context)?;
-        let mut store = Store::new(&self.engine, wasi_ctx);
-        
-        store.set_fuel(context.cpu_limit)
-            .context("Failed to set fuel limit")?;
-        store.set_epoch_deadline(1);
-
-        // Get JavaScript WASM module (QuickJS or similar)
-        let js_wasm = self.get_javascript_wasm_module().await?;
-        
-        let instance = Instance::new_async(&mut store, &js_wasm, &[]).await
-            .context("Failed to instantiate JavaScript WASM module")?;
+        let mut instance = self.new_instance(language, &module, &context).await?;
 
         let result = self.execute_in_instance(
-            &mut store,
-            &instance,
+            &mut instance.store,
+            &instance.instance,
             code,
             &context,
             start_time,
         ).await;
 
+        self.execution_count.fetch_add(1, Ordering::SeqCst);
+
         self.cleanup_instance(&context.execution_id).await;
+
         result
     }
 
+    /// Instantiate `module` against a brand-new `Store`, with a WASI context
+    /// scoped to this execution's `allowed_hosts`/`environment`/fuel/epoch
+    /// deadline. The engine's `PoolingAllocationStrategy` (configured in
+    /// `new()`) recycles the underlying mmap'd memory slots across calls, so
+    /// this is about as cheap as the hand-rolled instance pool it replaced,
+    /// without that pool's cross-tenant memory reuse.
+    async fn new_instance(
+        &self,
+        language: &str,
+        module: &Module,
+        context: &ExecutionContext,
+    ) -> Result<SandboxInstance> {
+        let (wasi_ctx, network) = self.create_wasi_context(context).await?;
+
+        let mut store = Store::new(&self.engine, StoreData::new(wasi_ctx, context.memory_limit, network));
+        store.limiter(|d| d);
+        store.set_fuel(context.cpu_limit).context("Failed to set fuel limit")?;
+        store.set_epoch_deadline(1);
+
+        let instance = Instance::new_async(&mut store, module, &[]).await
+            .with_context(|| format!("Failed to instantiate {} WASM module", language))?;
+
+        Ok(SandboxInstance { store, instance })
+    }
+
     /// Create WASI context with security restrictions
-    fn create_wasi_context(&self, context: &ExecutionContext) -> Result<WasiCtx> {
+    async fn create_wasi_context(&self, context: &ExecutionContext) -> Result<(WasiCtx, NetworkAccounting)> {
         let mut builder = WasiCtxBuilder::new();
-        
+
         // Set up stdio
         builder.inherit_stdio();
-        
+
         // Set up environment variables (filtered)
         for (key, value) in &context.environment {
             if self.is_safe_env_var(key) {
                 builder.env(key, value)?;
             }
         }
-        
+
         // Set up file system access (restricted)
         let sandbox_dir = self.config.temp_dir.join(&context.execution_id);
         std::fs::create_dir_all(&sandbox_dir)
             .context("Failed to create execution directory")?;
-            
+
         builder.preopened_dir(
             &sandbox_dir,
             "/sandbox",
             cap_std::fs::DirPerms::all(),
             cap_std::fs::FilePerms::all(),
         )?;
-        
+
         // Add read-only access to system libraries if needed
         builder.preopened_dir(
             "/usr/lib",
@@ -237,25 +714,73 @@ impl WASISandbox {
             cap_std::fs::FilePerms::READ,
         )?;
 
-        Ok(builder.build())
+        // Outbound network is denied by default; an empty `allowed_hosts`
+        // leaves the guest fully offline. A non-empty list installs a
+        // socket check permitting only `connect`s to those `host`/`host:port`
+        // entries -- pre-resolved to concrete addresses here, since the
+        // check itself only ever sees the already-resolved `SocketAddr` and
+        // can't do its own DNS lookup -- AND gated by `self.security`'s
+        // shared egress firewall, so a per-execution entry can't be used to
+        // reach a private/loopback/link-local/denied-CIDR address the
+        // firewall would otherwise block. Counts every permitted attempt
+        // for `ExecutionMetrics.network_requests` and flags the first
+        // denied one so the caller can report
+        // `ExecutionStatus::SecurityViolation` instead of treating the trap
+        // as a generic runtime error.
+        let network = NetworkAccounting::default();
+        let resolved_hosts = resolve_allowed_hosts(&context.allowed_hosts).await;
+        let security = self.security.clone();
+        let permitted = network.permitted.clone();
+        let denied = network.denied.clone();
+        builder.socket_addr_check(move |addr, _reason| {
+            let allowed = socket_addr_allowed(&security, &resolved_hosts, addr);
+            if allowed {
+                permitted.fetch_add(1, Ordering::SeqCst);
+            } else {
+                denied.store(true, Ordering::SeqCst);
+            }
+            allowed
+        });
+
+        Ok((builder.build(), network))
     }
 
     /// Execute code within a WASM instance
     async fn execute_in_instance(
         &self,
-        store: &mut Store<WasiCtx>,
+        store: &mut Store<StoreData>,
         instance: &Instance,
         code: &str,
         context: &ExecutionContext,
         start_time: Instant,
     ) -> Result<ExecutionResult> {
         let execution_id = context.execution_id.clone();
-        
-        // Start epoch thread for timeout handling
+
+        // Start epoch thread for timeout/cancellation handling. Whichever
+        // fires first interrupts the instance via `increment_epoch`; the
+        // `cancelled` flag lets the result handler below tell a cancellation
+        // apart from a plain timeout once the resulting trap is classified.
         let engine = store.engine().clone();
-        let timeout_handle = tokio::spawn(async move {
-            tokio::time::sleep(context.timeout).await;
-            engine.increment_epoch();
+        let mut cancel = context.cancel.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let timeout_handle = tokio::spawn({
+            let cancelled = cancelled.clone();
+            let timeout = context.timeout;
+            async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(timeout) => {}
+                    _ = async {
+                        while !*cancel.borrow() {
+                            if cancel.changed().await.is_err() {
+                                break;
+                            }
+                        }
+                    } => {
+                        cancelled.store(true, Ordering::SeqCst);
+                    }
+                }
+                engine.increment_epoch();
+            }
         });
 
         // Get the main execution function
@@ -263,131 +788,861 @@ impl WASISandbox {
             .get_typed_func::<(i32, i32), i32>(store, "execute_code")
             .context("Failed to get execute_code function")?;
 
-        // Prepare code input (this is simplified - real implementation would handle memory management)
-        let code_ptr = self.allocate_string_in_wasm(store, instance, code).await?;
+        // Copy the code into guest memory via its malloc/memory ABI
+        let code_ptr = match self.allocate_string_in_wasm(store, instance, code).await {
+            Ok(ptr) => ptr,
+            Err(e) => {
+                timeout_handle.abort();
+                let (status, error_message) = guest_abi_status(e)?;
+                return Ok(Self::abi_error_result(execution_id, status, error_message, start_time.elapsed()));
+            }
+        };
         let code_len = code.len() as i32;
 
         // Execute the code
         let execution_result = main_func.call_async(store, (code_ptr, code_len)).await;
-        
+
         // Cancel timeout
         timeout_handle.abort();
-        
+
         let duration = start_time.elapsed();
-        
+
         // Process execution result
         match execution_result {
             Ok(result_code) => {
-                let output = self.get_execution_output(store, instance, result_code).await?;
-                
+                let output = match self.get_execution_output(store, instance, result_code).await {
+                    Ok(output) => output,
+                    Err(e) => {
+                        let (status, error_message) = guest_abi_status(e)?;
+                        return Ok(Self::abi_error_result(execution_id, status, error_message, duration));
+                    }
+                };
+
                 let metrics = ExecutionMetrics {
                     memory_used: self.get_memory_usage(store)?,
                     cpu_time: self.get_cpu_time(store)?,
                     syscalls_count: 0, // Would be tracked by WASI implementation
                     file_operations: 0,
-                    network_requests: 0,
+                    network_requests: store.data().network.permitted_count(),
+                    network_bytes_sent: 0,
+                    network_bytes_received: 0,
+                    gas_consumed: 0,
+                    trap_reason: None,
+                };
+
+                self.trace_execution(context, &metrics, duration);
+
+                let status = if store.data().network.was_denied() {
+                    ExecutionStatus::SecurityViolation
+                } else if result_code == 0 {
+                    ExecutionStatus::Success
+                } else {
+                    ExecutionStatus::RuntimeError
                 };
 
                 Ok(ExecutionResult {
                     execution_id,
-                    status: if result_code == 0 { ExecutionStatus::Success } else { ExecutionStatus::RuntimeError },
+                    status,
                     output,
                     error_message: None,
                     metrics,
                     duration,
+                    profile_path: None,
                 })
             }
             Err(trap) => {
-                let (status, error_message) = self.classify_trap(&trap);
-                
+                let (status, error_message) = if cancelled.load(Ordering::SeqCst) {
+                    (ExecutionStatus::Cancelled, "Execution cancelled".to_string())
+                } else if store.data().memory_growth_failed {
+                    (ExecutionStatus::MemoryLimit, "Memory limit exceeded".to_string())
+                } else if store.data().network.was_denied() {
+                    (ExecutionStatus::SecurityViolation, "Outbound connection blocked by allowlist".to_string())
+                } else {
+                    self.classify_trap(&trap)
+                };
+
+                let metrics = ExecutionMetrics {
+                    memory_used: self.get_memory_usage(store).unwrap_or(0),
+                    cpu_time: self.get_cpu_time(store).unwrap_or(duration),
+                    syscalls_count: 0,
+                    file_operations: 0,
+                    network_requests: store.data().network.permitted_count(),
+                    network_bytes_sent: 0,
+                    network_bytes_received: 0,
+                    gas_consumed: 0,
+                    trap_reason: Some(error_message.clone()),
+                };
+
+                self.trace_execution(context, &metrics, duration);
+
                 Ok(ExecutionResult {
                     execution_id,
                     status,
                     output: String::new(),
                     error_message: Some(error_message),
+                    metrics,
+                    duration,
+                    profile_path: None,
+                })
+            }
+        }
+    }
+
+    /// Execute a user-supplied WebAssembly module in the WASI sandbox.
+    ///
+    /// Unlike `execute_python`/`execute_javascript`, which invoke a fixed
+    /// `execute_code` entry point on a bundled interpreter module, here the
+    /// guest module itself *is* the program, so it's instrumented with
+    /// deterministic gas/stack-height metering and its own `_start` is run
+    /// directly rather than going through `execute_in_instance`.
+    ///
+    /// Runs on Wasmtime's JIT by default; `config.backend == Backend::Wasmi`
+    /// instead hands the instrumented module to [`WasmiExecutionBackend`],
+    /// trading JIT throughput for portability onto hosts that can't grant a
+    /// JIT executable memory and for lower per-call startup cost on short
+    /// snippets.
+    pub async fn execute_wasm(
+        &self,
+        code: &[u8],
+        context: ExecutionContext,
+    ) -> Result<ExecutionResult> {
+        let start_time = Instant::now();
+
+        debug!("Executing WebAssembly module in sandbox: {}", context.execution_id);
+
+        let _permit = self.semaphore.acquire().await
+            .context("Failed to acquire sandbox permit")?;
+
+        let instrumented = self.instrument_wasm(code)?;
+
+        if self.config.backend == Backend::Wasmi {
+            return self.wasmi_backend.execute(&instrumented, context, start_time).await;
+        }
+
+        let (wasi_ctx, network) = self.create_wasi_context(&context).await?;
+        let mut store = Store::new(&self.engine, StoreData::new(wasi_ctx, context.memory_limit, network));
+        store.limiter(|d| d);
+
+        // Fuel still backstops the instrumented gas trap in case
+        // instrumentation under- or over-counts a construct.
+        store.set_fuel(context.cpu_limit)
+            .context("Failed to set fuel limit")?;
+        store.set_epoch_deadline(1);
+
+        let module = Module::new(&self.engine, &instrumented)
+            .context("Failed to compile instrumented WASM module")?;
+
+        let instance = Instance::new_async(&mut store, &module, &[]).await
+            .context("Failed to instantiate WASM module")?;
+
+        // Seed the injected gas global from the task's CPU budget so the
+        // instrumented trap fires on the weighted cost model rather than
+        // leaving accounting entirely to Wasmtime's coarser fuel counter.
+        if let Some(Extern::Global(gas_global)) = instance.get_export(&mut store, GAS_GLOBAL_EXPORT) {
+            gas_global.set(&mut store, Val::I64(context.cpu_limit as i64))
+                .context("Failed to seed gas global")?;
+        }
+
+        let result = self.execute_wasm_instance(
+            &mut store,
+            &instance,
+            &module,
+            &context,
+            start_time,
+        ).await;
+
+        self.cleanup_instance(&context.execution_id).await;
+
+        result
+    }
+
+    /// Run `code` the same way [`Self::execute_wasm`] does, but in
+    /// checkpoints instead of one uninterrupted call: at each checkpoint,
+    /// `on_slice` is handed a [`SliceExecutionOutput`] and decides whether
+    /// to keep going, top up the remaining budget, or stop early.
+    /// Checkpoints are driven by the engine's epoch clock, which a ticker
+    /// spawned here bumps every 10 ms; they do not fall on
+    /// `slice.slice_fuel` boundaries. `store.fuel_async_yield_interval`
+    /// only makes the guest call yield back to the executor every
+    /// `slice.slice_fuel` units of fuel, so the ticker keeps running while
+    /// the guest does, and no call is unwound and re-entered. Only exhausting
+    /// `slice.max_cumulative_fuel` across every slice — as opposed to a
+    /// single slice's fuel — is reported as `ExecutionStatus::CpuLimit`.
+    pub async fn execute_wasm_sliced(
+        &self,
+        code: &[u8],
+        context: ExecutionContext,
+        slice: SliceConfig,
+        mut on_slice: impl FnMut(SliceExecutionOutput) -> SliceDecision + Send + 'static,
+    ) -> Result<ExecutionResult> {
+        let start_time = Instant::now();
+        let execution_id = context.execution_id.clone();
+
+        debug!("Executing sliced WebAssembly module in sandbox: {}", execution_id);
+
+        let _permit = self.semaphore.acquire().await
+            .context("Failed to acquire sandbox permit")?;
+
+        let (wasi_ctx, network) = self.create_wasi_context(&context).await?;
+        let mut store = Store::new(&self.engine, StoreData::new(wasi_ctx, context.memory_limit, network));
+        store.limiter(|d| d);
+
+        store.fuel_async_yield_interval(Some(slice.slice_fuel))
+            .context("Failed to configure fuel yield interval")?;
+        store.set_fuel(slice.max_cumulative_fuel)
+            .context("Failed to set cumulative fuel budget")?;
+        store.set_epoch_deadline(1);
+
+        let instrumented = self.instrument_wasm(code)?;
+        let module = Module::new(&self.engine, &instrumented)
+            .context("Failed to compile instrumented WASM module")?;
+
+        let instance = Instance::new_async(&mut store, &module, &[]).await
+            .context("Failed to instantiate WASM module")?;
+
+        if let Some(Extern::Global(gas_global)) = instance.get_export(&mut store, GAS_GLOBAL_EXPORT) {
+            gas_global.set(&mut store, Val::I64(slice.max_cumulative_fuel as i64))
+                .context("Failed to seed gas global")?;
+        }
+
+        let max_cumulative_fuel = slice.max_cumulative_fuel;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let budget_exhausted = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(Mutex::new(PausedExecution {
+            fuel_used: 0,
+            last_checkpoint: start_time,
+        }));
+
+        {
+            let cancelled = cancelled.clone();
+            let budget_exhausted = budget_exhausted.clone();
+            let paused = paused.clone();
+            store.epoch_deadline_callback(move |mut store_ctx| {
+                let consumed_total = store_ctx.fuel_consumed().unwrap_or(0);
+                let (slice_fuel_used, wall_time) = {
+                    let mut paused = paused.blocking_lock();
+                    let slice_fuel_used = consumed_total.saturating_sub(paused.fuel_used);
+                    let now = Instant::now();
+                    let wall_time = now.duration_since(paused.last_checkpoint);
+                    paused.fuel_used = consumed_total;
+                    paused.last_checkpoint = now;
+                    (slice_fuel_used, wall_time)
+                };
+
+                if consumed_total >= max_cumulative_fuel {
+                    budget_exhausted.store(true, Ordering::SeqCst);
+                    return Err(anyhow::anyhow!("cumulative fuel budget exhausted across slices"));
+                }
+
+                let output = SliceExecutionOutput {
+                    slice_fuel_used,
+                    cumulative_fuel: consumed_total,
+                    wall_time,
+                };
+
+                match on_slice(output) {
+                    SliceDecision::Continue => Ok(UpdateDeadline::Continue(1)),
+                    SliceDecision::TopUp(extra) => {
+                        let remaining = store_ctx.get_fuel().unwrap_or(0);
+                        store_ctx.set_fuel(remaining.saturating_add(extra))?;
+                        Ok(UpdateDeadline::Continue(1))
+                    }
+                    SliceDecision::Cancel => {
+                        cancelled.store(true, Ordering::SeqCst);
+                        Err(anyhow::anyhow!("execution cancelled between slices"))
+                    }
+                }
+            });
+        }
+
+        // `fuel_async_yield_interval` only hands the future back to the
+        // executor at each slice boundary; it doesn't advance the epoch that
+        // `epoch_deadline_callback` above fires on, so something has to tick
+        // it — the same `engine.increment_epoch()` the timeout path in
+        // `execute_wasm_instance` uses, just on a repeating interval here
+        // instead of a single deadline.
+        let engine = store.engine().clone();
+        let ticking = Arc::new(AtomicBool::new(true));
+        let ticker_handle = tokio::spawn({
+            let ticking = ticking.clone();
+            async move {
+                let mut interval = tokio::time::interval(Duration::from_millis(10));
+                while ticking.load(Ordering::SeqCst) {
+                    interval.tick().await;
+                    engine.increment_epoch();
+                }
+            }
+        });
+
+        let mut cancel = context.cancel.clone();
+        let cancel_watcher = tokio::spawn({
+            let engine = store.engine().clone();
+            async move {
+                while !*cancel.borrow() {
+                    if cancel.changed().await.is_err() {
+                        return;
+                    }
+                }
+                engine.increment_epoch();
+            }
+        });
+
+        let entry = instance.get_typed_func::<(), ()>(&mut store, "_start")
+            .context("Failed to get _start function")?;
+
+        let execution_result = entry.call_async(&mut store, ()).await;
+
+        ticking.store(false, Ordering::SeqCst);
+        ticker_handle.abort();
+        cancel_watcher.abort();
+
+        let duration = start_time.elapsed();
+        let gas_consumed = self.gas_consumed(&mut store, &instance, max_cumulative_fuel);
+
+        let result = match execution_result {
+            Ok(()) => {
+                let output = match self.get_execution_output(&mut store, &instance, 0).await {
+                    Ok(output) => output,
+                    Err(e) => {
+                        let (status, error_message) = guest_abi_status(e)?;
+                        return Ok(Self::abi_error_result(execution_id, status, error_message, duration));
+                    }
+                };
+
+                let status = if store.data().network.was_denied() {
+                    ExecutionStatus::SecurityViolation
+                } else {
+                    ExecutionStatus::Success
+                };
+
+                ExecutionResult {
+                    execution_id,
+                    status,
+                    output,
+                    error_message: None,
+                    metrics: ExecutionMetrics {
+                        memory_used: self.get_memory_usage(&store)?,
+                        cpu_time: self.get_cpu_time(&store)?,
+                        syscalls_count: 0,
+                        file_operations: 0,
+                        network_requests: store.data().network.permitted_count(),
+                        network_bytes_sent: 0,
+                        network_bytes_received: 0,
+                        gas_consumed,
+                        trap_reason: None,
+                    },
+                    duration,
+                    profile_path: None,
+                }
+            }
+            Err(trap) => {
+                let (status, error_message) = if cancelled.load(Ordering::SeqCst) {
+                    (ExecutionStatus::Cancelled, "Execution cancelled between slices".to_string())
+                } else if budget_exhausted.load(Ordering::SeqCst) {
+                    (ExecutionStatus::CpuLimit, "Cumulative fuel budget exhausted across slices".to_string())
+                } else if store.data().memory_growth_failed {
+                    (ExecutionStatus::MemoryLimit, "Memory limit exceeded".to_string())
+                } else if store.data().network.was_denied() {
+                    (ExecutionStatus::SecurityViolation, "Outbound connection blocked by allowlist".to_string())
+                } else {
+                    self.classify_trap(&trap)
+                };
+
+                ExecutionResult {
+                    execution_id,
+                    status,
+                    output: String::new(),
+                    error_message: Some(error_message.clone()),
+                    metrics: ExecutionMetrics {
+                        memory_used: self.get_memory_usage(&store).unwrap_or(0),
+                        cpu_time: duration,
+                        syscalls_count: 0,
+                        file_operations: 0,
+                        network_requests: store.data().network.permitted_count(),
+                        network_bytes_sent: 0,
+                        network_bytes_received: 0,
+                        gas_consumed,
+                        trap_reason: Some(error_message),
+                    },
+                    duration,
+                    profile_path: None,
+                }
+            }
+        };
+
+        self.cleanup_instance(&context.execution_id).await;
+
+        Ok(result)
+    }
+
+    /// Parse `wasm`, inject a mutable gas-accounting global that is
+    /// decremented by each basic block's precomputed cost and traps on
+    /// going negative, and inject a stack-height counter that traps past
+    /// `STACK_HEIGHT_LIMIT`, modeled on the instrument-then-meter approach.
+    /// Returns the re-serialized, instrumented module bytes.
+    fn instrument_wasm(&self, wasm: &[u8]) -> Result<Vec<u8>> {
+        let module: PwasmModule = deserialize_buffer(wasm)
+            .context("Failed to parse WASM module for instrumentation")?;
+
+        let backend = gas_metering::mutable_global::Injector::new(GAS_GLOBAL_EXPORT);
+        let module = gas_metering::inject(module, backend, &gas_metering::ConstantCostRules::default())
+            .map_err(|_| anyhow::anyhow!("Failed to inject gas metering into WASM module"))?;
+
+        let module = wasm_instrument::stack_limiter::inject(module, STACK_HEIGHT_LIMIT)
+            .map_err(|_| anyhow::anyhow!("Failed to inject stack-height limiter into WASM module"))?;
+
+        serialize(module).context("Failed to re-serialize instrumented WASM module")
+    }
+
+    /// Run an instrumented module's own `_start` entry point, tracking the
+    /// same timeout/cancellation epoch machinery as `execute_in_instance`
+    /// but reading the consumed gas back out of the injected global rather
+    /// than estimating execution units from output size.
+    ///
+    /// When `config.enable_profiling` is set, a `GuestProfiler` is attached
+    /// and driven by a second, periodic epoch ticker running alongside the
+    /// one-shot timeout ticker above — each tick samples the call stack
+    /// instead of tripping the epoch deadline, so profiling adds no extra
+    /// interruption of its own. The collected profile is written to the
+    /// execution's sandbox temp dir once the call returns.
+    async fn execute_wasm_instance(
+        &self,
+        store: &mut Store<StoreData>,
+        instance: &Instance,
+        module: &Module,
+        context: &ExecutionContext,
+        start_time: Instant,
+    ) -> Result<ExecutionResult> {
+        let execution_id = context.execution_id.clone();
+        let initial_gas = context.cpu_limit;
+
+        let engine = store.engine().clone();
+        let mut cancel = context.cancel.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let timeout_handle = tokio::spawn({
+            let cancelled = cancelled.clone();
+            let timed_out = timed_out.clone();
+            let timeout = context.timeout;
+            let engine = engine.clone();
+            async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(timeout) => {
+                        timed_out.store(true, Ordering::SeqCst);
+                    }
+                    _ = async {
+                        while !*cancel.borrow() {
+                            if cancel.changed().await.is_err() {
+                                break;
+                            }
+                        }
+                    } => {
+                        cancelled.store(true, Ordering::SeqCst);
+                    }
+                }
+                engine.increment_epoch();
+            }
+        });
+
+        let (profiler, ticker_handle) = if self.config.enable_profiling {
+            let sample_interval = self.config.profiling_sample_interval;
+            let profiler = Arc::new(std::sync::Mutex::new(GuestProfiler::new(
+                &execution_id,
+                sample_interval,
+                vec![("guest".to_string(), module.clone())],
+            )));
+
+            // The epoch deadline is now shared between this ticker and the
+            // timeout task above, so the callback has to tell them apart:
+            // a tick while `cancelled` and `timed_out` are both still false
+            // is just a sample point; once either flips, the next tick
+            // interrupts the guest the way the no-profiling default would.
+            //
+            // `sample` wants the real wall-clock time elapsed since the
+            // *previous* sample, not a constant, or every frame in the
+            // resulting profile carries zero weight and the flamegraph is
+            // meaningless. Track it in `last_sample`, seeded to now so the
+            // first tick's delta is measured from when profiling started.
+            let last_sample = Arc::new(std::sync::Mutex::new(Instant::now()));
+            store.epoch_deadline_callback({
+                let profiler = profiler.clone();
+                let cancelled = cancelled.clone();
+                let timed_out = timed_out.clone();
+                let last_sample = last_sample.clone();
+                move |store_ctx| {
+                    if cancelled.load(Ordering::SeqCst) || timed_out.load(Ordering::SeqCst) {
+                        return Err(anyhow::anyhow!("execution cancelled or timed out"));
+                    }
+                    let now = Instant::now();
+                    let elapsed = {
+                        let mut last_sample = last_sample.lock().unwrap();
+                        let elapsed = now.duration_since(*last_sample);
+                        *last_sample = now;
+                        elapsed
+                    };
+                    profiler.lock().unwrap().sample(&store_ctx, elapsed);
+                    Ok(UpdateDeadline::Continue(1))
+                }
+            });
+            store.set_epoch_deadline(1);
+
+            let ticker_engine = engine.clone();
+            let ticker_handle = tokio::spawn(async move {
+                let mut interval = tokio::time::interval(sample_interval.max(Duration::from_millis(1)));
+                loop {
+                    interval.tick().await;
+                    ticker_engine.increment_epoch();
+                }
+            });
+
+            (Some(profiler), Some(ticker_handle))
+        } else {
+            (None, None)
+        };
+
+        let entry = instance
+            .get_typed_func::<(), ()>(&mut *store, "_start")
+            .context("Failed to get _start function")?;
+
+        let execution_result = entry.call_async(&mut *store, ()).await;
+
+        timeout_handle.abort();
+        if let Some(ticker_handle) = ticker_handle {
+            ticker_handle.abort();
+        }
+
+        let duration = start_time.elapsed();
+        let gas_consumed = self.gas_consumed(store, instance, initial_gas);
+
+        let profile_path = match profiler {
+            Some(profiler) => {
+                // Drop the epoch_deadline_callback's own `Arc` clone first --
+                // it's still installed on `store` and would otherwise keep
+                // `write_profile`'s `Arc::try_unwrap` from ever succeeding.
+                store.epoch_deadline_callback(|_store_ctx| Ok(UpdateDeadline::Continue(1)));
+                self.write_profile(&execution_id, profiler).unwrap_or_else(|e| {
+                    warn!("Failed to write guest profile for {}: {}", execution_id, e);
+                    None
+                })
+            }
+            None => None,
+        };
+
+        match execution_result {
+            Ok(()) => {
+                let output = match self.get_execution_output(store, instance, 0).await {
+                    Ok(output) => output,
+                    Err(e) => {
+                        let (status, error_message) = guest_abi_status(e)?;
+                        return Ok(Self::abi_error_result(execution_id, status, error_message, duration));
+                    }
+                };
+
+                let metrics = ExecutionMetrics {
+                    memory_used: self.get_memory_usage(store)?,
+                    cpu_time: self.get_cpu_time(store)?,
+                    syscalls_count: 0,
+                    file_operations: 0,
+                    network_requests: store.data().network.permitted_count(),
+                    network_bytes_sent: 0,
+                    network_bytes_received: 0,
+                    gas_consumed,
+                    trap_reason: None,
+                };
+
+                let status = if store.data().network.was_denied() {
+                    ExecutionStatus::SecurityViolation
+                } else {
+                    ExecutionStatus::Success
+                };
+
+                Ok(ExecutionResult {
+                    execution_id,
+                    status,
+                    output,
+                    error_message: None,
+                    metrics,
+                    duration,
+                    profile_path,
+                })
+            }
+            Err(trap) => {
+                let (status, error_message) = if cancelled.load(Ordering::SeqCst) {
+                    (ExecutionStatus::Cancelled, "Execution cancelled".to_string())
+                } else if timed_out.load(Ordering::SeqCst) {
+                    (ExecutionStatus::Timeout, "Execution timeout".to_string())
+                } else if store.data().memory_growth_failed {
+                    (ExecutionStatus::MemoryLimit, "Memory limit exceeded".to_string())
+                } else if store.data().network.was_denied() {
+                    (ExecutionStatus::SecurityViolation, "Outbound connection blocked by allowlist".to_string())
+                } else if gas_consumed >= initial_gas {
+                    // The injected gas check traps via a plain `unreachable`,
+                    // indistinguishable from an ordinary one by trap kind
+                    // alone, so an exhausted budget is the tell.
+                    (ExecutionStatus::CpuLimit, "Execution unit budget exhausted".to_string())
+                } else {
+                    self.classify_trap(&trap)
+                };
+
+                Ok(ExecutionResult {
+                    execution_id,
+                    status,
+                    output: String::new(),
+                    error_message: Some(error_message.clone()),
                     metrics: ExecutionMetrics {
                         memory_used: self.get_memory_usage(store).unwrap_or(0),
                         cpu_time: duration,
                         syscalls_count: 0,
                         file_operations: 0,
-                        network_requests: 0,
+                        network_requests: store.data().network.permitted_count(),
+                        network_bytes_sent: 0,
+                        network_bytes_received: 0,
+                        gas_consumed,
+                        trap_reason: Some(error_message),
                     },
                     duration,
+                    profile_path,
                 })
             }
         }
     }
 
-    /// Get Python WASM module (placeholder - would load actual compiled module)
-    async fn get_python_wasm_module(&self) -> Result<Module> {
-        // In a real implementation, this would load a pre-compiled Python interpreter
-        // For now, we'll create a simple mock module
+    /// Read the injected gas global back out of the instance and compute
+    /// how many units were consumed against the seeded budget. Falls back
+    /// to reporting the full budget as consumed if the global is missing,
+    /// which only happens if instrumentation failed to attach it.
+    fn gas_consumed(&self, store: &mut Store<StoreData>, instance: &Instance, initial_gas: u64) -> u64 {
+        match instance.get_export(&mut *store, GAS_GLOBAL_EXPORT) {
+            Some(Extern::Global(global)) => match global.get(&mut *store) {
+                Val::I64(remaining) => initial_gas.saturating_sub(remaining.max(0) as u64),
+                _ => initial_gas,
+            },
+            _ => initial_gas,
+        }
+    }
+
+    /// Serialize a completed `GuestProfiler` to the execution's sandbox temp
+    /// dir as a Firefox-profiler-format JSON file, returning its path. The
+    /// profiler is only reachable here once its last `Arc` clone (the one
+    /// held by the sampling `epoch_deadline_callback`, which the caller
+    /// replaces before calling this) has been dropped, so a
+    /// failure to unwrap it is treated as a bug rather than a expected race.
+    fn write_profile(&self, execution_id: &str, profiler: Arc<std::sync::Mutex<GuestProfiler>>) -> Result<Option<PathBuf>> {
+        let profiler = Arc::try_unwrap(profiler)
+            .map_err(|_| anyhow::anyhow!("guest profiler still has outstanding references"))?
+            .into_inner()
+            .map_err(|_| anyhow::anyhow!("guest profiler mutex poisoned"))?;
+
+        let sandbox_dir = self.config.temp_dir.join(execution_id);
+        std::fs::create_dir_all(&sandbox_dir)
+            .context("Failed to create sandbox directory for guest profile")?;
+        let path = sandbox_dir.join("profile.json");
+        let file = std::fs::File::create(&path).context("Failed to create guest profile file")?;
+        profiler.finish(file).context("Failed to serialize guest profile")?;
+
+        Ok(Some(path))
+    }
+
+    /// Compile the Python WASM module once, at sandbox startup (placeholder
+    /// - would load an actual pre-compiled Python interpreter). For now
+    /// this is a simple mock module exporting the
+    /// malloc/get_output_ptr/get_output_len ABI `allocate_string_in_wasm`/
+    /// `get_execution_output` drive.
+    fn compile_python_wasm_module(engine: &Engine) -> Result<Module> {
         let wat = r#"
             (module
                 (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
                 (memory (export "memory") 1)
+                (func (export "malloc") (param $len i32) (result i32)
+                    i32.const 2048
+                )
+                (func (export "get_output_ptr") (result i32)
+                    i32.const 1024
+                )
+                (func (export "get_output_len") (result i32)
+                    i32.const 0
+                )
                 (func (export "execute_code") (param $code_ptr i32) (param $code_len i32) (result i32)
                     i32.const 0
                 )
             )
         "#;
-        
-        Module::new(&self.engine, wat)
+
+        Module::new(engine, wat)
             .context("Failed to create Python WASM module")
     }
 
-    /// Get JavaScript WASM module (placeholder)
-    async fn get_javascript_wasm_module(&self) -> Result<Module> {
-        // Similar to Python module but for JavaScript runtime
+    /// Compile the JavaScript WASM module once, at sandbox startup
+    /// (placeholder - would load an actual QuickJS-or-similar interpreter).
+    fn compile_javascript_wasm_module(engine: &Engine) -> Result<Module> {
         let wat = r#"
             (module
                 (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
                 (memory (export "memory") 1)
+                (func (export "malloc") (param $len i32) (result i32)
+                    i32.const 2048
+                )
+                (func (export "get_output_ptr") (result i32)
+                    i32.const 1024
+                )
+                (func (export "get_output_len") (result i32)
+                    i32.const 0
+                )
                 (func (export "execute_code") (param $code_ptr i32) (param $code_len i32) (result i32)
                     i32.const 0
                 )
             )
         "#;
-        
-        Module::new(&self.engine, wat)
+
+        Module::new(engine, wat)
             .context("Failed to create JavaScript WASM module")
     }
 
-    /// Allocate string in WASM memory (simplified implementation)
+    /// Copy `s` into guest memory via the module's exported `malloc(len)`,
+    /// returning the pointer `execute_code` should be called with. Fails with
+    /// `GuestAbiError::MissingAbi` if the module is missing either its
+    /// `memory` or its `malloc` export, and `GuestAbiError::MemoryLimit` if the write would run past
+    /// memory that can't be grown to fit.
     async fn allocate_string_in_wasm(
         &self,
-        _store: &mut Store<WasiCtx>,
-        _instance: &Instance,
-        _s: &str,
-    ) -> Result<i32> {
-        // Simplified - real implementation would manage WASM memory
-        Ok(0)
+        store: &mut Store<StoreData>,
+        instance: &Instance,
+        s: &str,
+    ) -> Result<i32, GuestAbiError> {
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| GuestAbiError::MissingAbi("memory".to_string()))?;
+        let malloc = instance
+            .get_typed_func::<i32, i32>(&mut *store, "malloc")
+            .map_err(|_| GuestAbiError::MissingAbi("malloc".to_string()))?;
+
+        let ptr = malloc
+            .call_async(&mut *store, s.len() as i32)
+            .await
+            .context("malloc call failed")?;
+
+        let end = (ptr as usize).checked_add(s.len()).ok_or_else(|| {
+            GuestAbiError::MemoryLimit("allocated pointer overflows guest address space".to_string())
+        })?;
+
+        if end > memory.data_size(&mut *store) {
+            let page_size = 65536usize;
+            let shortfall = end - memory.data_size(&mut *store);
+            let additional_pages = (shortfall + page_size - 1) / page_size;
+            memory.grow(&mut *store, additional_pages as u64).map_err(|_| {
+                GuestAbiError::MemoryLimit("guest memory limit exceeded while writing code input".to_string())
+            })?;
+        }
+
+        memory
+            .write(&mut *store, ptr as usize, s.as_bytes())
+            .context("failed to write code into guest memory")?;
+
+        Ok(ptr)
     }
 
-    /// Get execution output from WASM instance
+    /// Read the output region the guest reports via its exported
+    /// `get_output_ptr()`/`get_output_len()`, decoding it as UTF-8. Fails the
+    /// same way as [`Self::allocate_string_in_wasm`] when the ABI is missing
+    /// or the reported region runs past the end of guest memory.
     async fn get_execution_output(
         &self,
-        _store: &mut Store<WasiCtx>,
-        _instance: &Instance,
+        store: &mut Store<StoreData>,
+        instance: &Instance,
         _result_code: i32,
-    ) -> Result<String> {
-        // Simplified - real implementation would read from WASM memory
-        Ok("Execution completed successfully".to_string())
+    ) -> Result<String, GuestAbiError> {
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| GuestAbiError::MissingAbi("memory".to_string()))?;
+        let output_ptr = instance
+            .get_typed_func::<(), i32>(&mut *store, "get_output_ptr")
+            .map_err(|_| GuestAbiError::MissingAbi("get_output_ptr".to_string()))?
+            .call_async(&mut *store, ())
+            .await
+            .context("get_output_ptr call failed")?;
+        let output_len = instance
+            .get_typed_func::<(), i32>(&mut *store, "get_output_len")
+            .map_err(|_| GuestAbiError::MissingAbi("get_output_len".to_string()))?
+            .call_async(&mut *store, ())
+            .await
+            .context("get_output_len call failed")?;
+
+        if output_len < 0 {
+            return Err(anyhow::anyhow!("guest reported a negative output length").into());
+        }
+
+        let start = output_ptr as usize;
+        let end = start.checked_add(output_len as usize).ok_or_else(|| {
+            GuestAbiError::MemoryLimit("output region overflows guest address space".to_string())
+        })?;
+
+        if end > memory.data_size(&mut *store) {
+            return Err(GuestAbiError::MemoryLimit(
+                "guest reported an output region past the end of its memory".to_string(),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&memory.data(&mut *store)[start..end]).into_owned())
     }
 
-    /// Get memory usage from store
-    fn get_memory_usage(&self, store: &Store<WasiCtx>) -> Result<u64> {
-        // Get memory usage from store data
-        Ok(store.data().memory_consumed() as u64)
+    /// Peak memory (bytes) the guest has requested via `memory.grow`, whether
+    /// or not the grow was allowed — tracked by `StoreData`'s `ResourceLimiter`
+    /// impl since `wasmtime_wasi::WasiCtx` exposes no memory accounting of
+    /// its own.
+    fn get_memory_usage(&self, store: &Store<StoreData>) -> Result<u64> {
+        Ok(store.data().memory_high_water as u64)
     }
 
     /// Get CPU time from store
-    fn get_cpu_time(&self, store: &Store<WasiCtx>) -> Result<Duration> {
+    fn get_cpu_time(&self, store: &Store<StoreData>) -> Result<Duration> {
         // Calculate CPU time based on fuel consumed
         let fuel_consumed = store.fuel_consumed().unwrap_or(0);
         Ok(Duration::from_nanos(fuel_consumed))
     }
 
+    /// Build the `ExecutionResult` for a guest ABI failure (missing export or
+    /// an out-of-bounds output region) caught before the guest ever ran far
+    /// enough to trap on its own.
+    fn abi_error_result(execution_id: String, status: ExecutionStatus, error_message: String, duration: Duration) -> ExecutionResult {
+        ExecutionResult {
+            execution_id,
+            status,
+            output: String::new(),
+            error_message: Some(error_message.clone()),
+            metrics: ExecutionMetrics {
+                memory_used: 0,
+                cpu_time: duration,
+                syscalls_count: 0,
+                file_operations: 0,
+                network_requests: 0,
+                network_bytes_sent: 0,
+                network_bytes_received: 0,
+                gas_consumed: 0,
+                trap_reason: Some(error_message),
+            },
+            duration,
+            profile_path: None,
+        }
+    }
+
+    /// Log a structured `instructions=/memory_peak=/duration=` line for an
+    /// `execute_in_instance` call, gated on `SandboxConfig::trace_execution`
+    /// since it runs on every execution. Called on both success and trap so
+    /// timeouts and memory kills still get billed/accounted for.
+    fn trace_execution(&self, context: &ExecutionContext, metrics: &ExecutionMetrics, duration: Duration) {
+        if !self.config.trace_execution {
+            return;
+        }
+        info!(
+            "Executed {} ({}): instructions={}, memory_peak={}, duration={}",
+            context.execution_id,
+            context.language,
+            metrics.cpu_time.as_nanos(),
+            metrics.memory_used,
+            duration.as_millis(),
+        );
+    }
+
     /// Classify trap error
     fn classify_trap(&self, trap: &Trap) -> (ExecutionStatus, String) {
         match trap {
@@ -408,12 +1663,10 @@ impl WASISandbox {
         SAFE_VARS.contains(&key) || key.starts_with("AGENT_")
     }
 
-    /// Clean up sandbox instance
+    /// Clean up the per-execution temp directory. The Store/Instance itself
+    /// is simply dropped by the caller at the end of `execute_pooled` rather
+    /// than torn down here.
     async fn cleanup_instance(&self, execution_id: &str) {
-        let mut instances = self.instances.lock().await;
-        instances.remove(execution_id);
-        
-        // Clean up temp directory
         let sandbox_dir = self.config.temp_dir.join(execution_id);
         if sandbox_dir.exists() {
             if let Err(e) = std::fs::remove_dir_all(&sandbox_dir) {
@@ -422,20 +1675,35 @@ impl WASISandbox {
         }
     }
 
-    /// Get sandbox statistics
+    /// Get sandbox statistics. `active_instances` now reflects
+    /// currently-executing instances (semaphore permits in use) rather than
+    /// idle pooled ones, since there's no longer an idle pool to report on.
     pub async fn get_stats(&self) -> SandboxStats {
-        let instances = self.instances.lock().await;
-        
         SandboxStats {
-            active_instances: instances.len(),
+            active_instances: self.config.max_instances - self.semaphore.available_permits(),
             max_instances: self.config.max_instances,
-            total_executions: instances.values().map(|i| i.execution_count).sum(),
+            total_executions: self.execution_count.load(Ordering::SeqCst),
             memory_limit: self.config.memory_limit,
             cpu_limit: self.config.cpu_limit,
         }
     }
 }
 
+#[tonic::async_trait]
+impl SandboxBackend for WASISandbox {
+    async fn execute_python(&self, code: &str, context: ExecutionContext) -> Result<ExecutionResult> {
+        WASISandbox::execute_python(self, code, context).await
+    }
+
+    async fn execute_javascript(&self, code: &str, context: ExecutionContext) -> Result<ExecutionResult> {
+        WASISandbox::execute_javascript(self, code, context).await
+    }
+
+    async fn execute_wasm(&self, code: &[u8], context: ExecutionContext) -> Result<ExecutionResult> {
+        WASISandbox::execute_wasm(self, code, context).await
+    }
+}
+
 /// Sandbox statistics
 #[derive(Debug, Clone)]
 pub struct SandboxStats {
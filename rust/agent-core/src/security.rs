@@ -1,11 +1,28 @@
 use anyhow::{Context, Result};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::digest;
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
 use crate::config::SecurityConfig;
+use crate::merkle_audit::MerkleAuditLog;
+
+/// Hash recorded as `prev_hash` for the very first event in a fresh chain
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Envelope format version byte. Bump this if the serialized layout below
+/// ever changes so old ciphertexts can still be rejected cleanly rather than
+/// silently misparsed.
+const ENVELOPE_VERSION: u8 = 1;
 
 /// Security manager for code validation and policy enforcement
 pub struct SecurityManager {
@@ -13,6 +30,350 @@ pub struct SecurityManager {
     policy_engine: PolicyEngine,
     code_analyzer: CodeAnalyzer,
     audit_logger: AuditLogger,
+    key_ring: KeyRing,
+    firewall: EgressFirewall,
+    auth_validator: AuthTokenValidator,
+}
+
+/// Outcome of validating an outbound network request, carrying enough detail
+/// for both the caller's allow/deny decision and the audit trail
+#[derive(Debug, Clone)]
+pub struct NetworkAccessResult {
+    pub allowed: bool,
+    pub resolved_ip: Option<IpAddr>,
+    pub matched_rule: String,
+}
+
+/// SSRF-hardened egress firewall. Replaces a naive `host.contains(allowed)`
+/// substring check with exact-suffix domain matching, explicit CIDR
+/// allow/deny lists, and IP-level classification of resolved addresses so a
+/// domain that resolves to a private/loopback/link-local address can't be
+/// used to reach internal services.
+struct EgressFirewall {
+    allowed_domains: Vec<String>,
+    allowed_cidrs: Vec<IpCidr>,
+    denied_cidrs: Vec<IpCidr>,
+    allowed_port_ranges: Vec<(u16, u16)>,
+}
+
+impl EgressFirewall {
+    fn new(config: &SecurityConfig) -> Result<Self> {
+        let allowed_cidrs = config
+            .allowed_egress_cidrs
+            .iter()
+            .map(|s| IpCidr::parse(s))
+            .collect::<Result<Vec<_>>>()?;
+        let denied_cidrs = config
+            .denied_egress_cidrs
+            .iter()
+            .map(|s| IpCidr::parse(s))
+            .collect::<Result<Vec<_>>>()?;
+        let allowed_port_ranges = config
+            .allowed_egress_ports
+            .iter()
+            .map(|s| parse_port_range(s))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            allowed_domains: config.allowed_egress_domains.clone(),
+            allowed_cidrs,
+            denied_cidrs,
+            allowed_port_ranges,
+        })
+    }
+
+    fn port_allowed(&self, port: u16) -> bool {
+        self.allowed_port_ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&port))
+    }
+
+    /// Exact-suffix match on DNS labels: `api.openai.com` matches itself and
+    /// `foo.api.openai.com`, but not `evil-api.openai.com.attacker.net` or
+    /// `notapi.openai.com`.
+    fn domain_allowed(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_lowercase();
+        self.allowed_domains.iter().any(|domain| {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            host == domain || host.ends_with(&format!(".{domain}"))
+        })
+    }
+
+    /// Classify a single resolved address, independent of the domain check.
+    /// Returns the matched rule name for the audit log.
+    fn evaluate_ip(&self, ip: IpAddr) -> (bool, &'static str) {
+        if self.denied_cidrs.iter().any(|net| net.contains(ip)) {
+            return (false, "denied_cidr");
+        }
+        if self.allowed_cidrs.iter().any(|net| net.contains(ip)) {
+            return (true, "allowed_cidr");
+        }
+        if is_disallowed_private_address(ip) {
+            return (false, "private_address_blocked");
+        }
+        (true, "public_address")
+    }
+
+    /// Full pre-connect decision for a host that has already been resolved
+    /// to one or more addresses. Every resolved address must independently
+    /// clear the IP-level check, and the host itself must either match the
+    /// domain allowlist or resolve entirely into an explicitly allowed CIDR.
+    fn evaluate(&self, host: &str, port: u16, resolved: &[IpAddr]) -> NetworkAccessResult {
+        if !self.port_allowed(port) {
+            return NetworkAccessResult { allowed: false, resolved_ip: resolved.first().copied(), matched_rule: "port_not_allowed".to_string() };
+        }
+
+        let domain_ok = self.domain_allowed(host);
+
+        for &ip in resolved {
+            let (ip_ok, rule) = self.evaluate_ip(ip);
+            if !ip_ok {
+                return NetworkAccessResult { allowed: false, resolved_ip: Some(ip), matched_rule: rule.to_string() };
+            }
+            if !domain_ok && rule != "allowed_cidr" {
+                return NetworkAccessResult { allowed: false, resolved_ip: Some(ip), matched_rule: "domain_not_allowlisted".to_string() };
+            }
+        }
+
+        match resolved.first() {
+            Some(&ip) => NetworkAccessResult {
+                allowed: true,
+                resolved_ip: Some(ip),
+                matched_rule: if domain_ok { "domain_allowlist".to_string() } else { "allowed_cidr".to_string() },
+            },
+            None => NetworkAccessResult { allowed: false, resolved_ip: None, matched_rule: "no_resolved_address".to_string() },
+        }
+    }
+}
+
+/// A parsed IPv4 or IPv6 CIDR block
+#[derive(Debug, Clone, Copy)]
+enum IpCidr {
+    V4 { network: Ipv4Addr, prefix: u32 },
+    V6 { network: Ipv6Addr, prefix: u32 },
+}
+
+impl IpCidr {
+    fn parse(spec: &str) -> Result<Self> {
+        let (addr_part, prefix_part) = spec
+            .split_once('/')
+            .with_context(|| format!("CIDR '{}' is missing a /prefix", spec))?;
+        let prefix: u32 = prefix_part
+            .parse()
+            .with_context(|| format!("invalid prefix length in CIDR '{}'", spec))?;
+
+        match addr_part.parse::<IpAddr>()? {
+            IpAddr::V4(addr) => {
+                if prefix > 32 {
+                    return Err(anyhow::anyhow!("IPv4 prefix out of range in '{}'", spec));
+                }
+                Ok(IpCidr::V4 { network: mask_v4(addr, prefix), prefix })
+            }
+            IpAddr::V6(addr) => {
+                if prefix > 128 {
+                    return Err(anyhow::anyhow!("IPv6 prefix out of range in '{}'", spec));
+                }
+                Ok(IpCidr::V6 { network: mask_v6(addr, prefix), prefix })
+            }
+        }
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self, ip) {
+            (IpCidr::V4 { network, prefix }, IpAddr::V4(ip)) => mask_v4(ip, *prefix) == *network,
+            (IpCidr::V6 { network, prefix }, IpAddr::V6(ip)) => mask_v6(ip, *prefix) == *network,
+            _ => false,
+        }
+    }
+}
+
+fn mask_v4(addr: Ipv4Addr, prefix: u32) -> Ipv4Addr {
+    let bits = u32::from(addr);
+    let mask = if prefix == 0 { 0 } else { u32::MAX << (32 - prefix) };
+    Ipv4Addr::from(bits & mask)
+}
+
+fn mask_v6(addr: Ipv6Addr, prefix: u32) -> Ipv6Addr {
+    let bits = u128::from(addr);
+    let mask = if prefix == 0 { 0 } else { u128::MAX << (128 - prefix) };
+    Ipv6Addr::from(bits & mask)
+}
+
+fn parse_port_range(spec: &str) -> Result<(u16, u16)> {
+    if let Some((lo, hi)) = spec.split_once('-') {
+        Ok((lo.trim().parse()?, hi.trim().parse()?))
+    } else {
+        let port: u16 = spec.trim().parse()?;
+        Ok((port, port))
+    }
+}
+
+/// True for loopback, link-local, RFC1918 private, CGNAT (100.64.0.0/10), and
+/// IPv6 unique-local addresses -- every range an SSRF attempt could use to
+/// reach internal infrastructure unless it was explicitly allowlisted.
+fn is_disallowed_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_link_local() || v4.is_private() || is_cgnat_v4(v4),
+        IpAddr::V6(v6) => v6.is_loopback() || is_unicast_link_local_v6(v6) || is_unique_local_v6(v6),
+    }
+}
+
+fn is_cgnat_v4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    octets[0] == 100 && (octets[1] & 0b1100_0000) == 0b0100_0000
+}
+
+fn is_unicast_link_local_v6(ip: Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xffc0) == 0xfe80
+}
+
+fn is_unique_local_v6(ip: Ipv6Addr) -> bool {
+    (ip.segments()[0] & 0xfe00) == 0xfc00
+}
+
+/// Holds every loaded KEK (key-encryption-key) by id, so payloads sealed
+/// under an older KEK can still be decrypted after the active one rotates.
+struct KeyRing {
+    keys: HashMap<String, [u8; 32]>,
+    active_kek_id: String,
+}
+
+impl KeyRing {
+    /// Load every `<id>.key` file (32 raw bytes each) found alongside
+    /// `key_path`, keyed by filename stem. Falls back to a process-local
+    /// ephemeral key if none are found, so unconfigured dev environments
+    /// still work, at the cost of encrypted data not surviving a restart.
+    async fn new(key_path: &PathBuf, active_kek_id: &str) -> Result<Self> {
+        let mut keys = HashMap::new();
+        let dir = key_path.parent().filter(|p| !p.as_os_str().is_empty());
+
+        if let Some(dir) = dir {
+            if dir.exists() {
+                let mut entries = fs::read_dir(dir).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    let path = entry.path();
+                    if path.extension().and_then(|s| s.to_str()) != Some("key") {
+                        continue;
+                    }
+                    let kek_id = path
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    let raw = fs::read(&path).await?;
+                    if raw.len() != 32 {
+                        warn!("KEK file {:?} is not 32 bytes, skipping", path);
+                        continue;
+                    }
+                    let mut key = [0u8; 32];
+                    key.copy_from_slice(&raw);
+                    keys.insert(kek_id, key);
+                }
+            }
+        }
+
+        if keys.is_empty() {
+            warn!(
+                "No KEK files found under {:?}; generating an ephemeral master key for this \
+                 process only. Data encrypted now will not decrypt after a restart.",
+                dir
+            );
+            let rng = SystemRandom::new();
+            let mut key = [0u8; 32];
+            rng.fill(&mut key)?;
+            keys.insert(active_kek_id.to_string(), key);
+        }
+
+        Ok(Self {
+            keys,
+            active_kek_id: active_kek_id.to_string(),
+        })
+    }
+
+    fn active_key(&self) -> Result<(&str, &[u8; 32])> {
+        self.keys
+            .get(self.active_kek_id.as_str())
+            .map(|key| (self.active_kek_id.as_str(), key))
+            .ok_or_else(|| anyhow::anyhow!("active KEK id '{}' is not loaded", self.active_kek_id))
+    }
+
+    fn key_by_id(&self, kek_id: &str) -> Result<&[u8; 32]> {
+        self.keys
+            .get(kek_id)
+            .ok_or_else(|| anyhow::anyhow!("unknown KEK id: {}", kek_id))
+    }
+}
+
+/// Identity extracted from a verified bearer token, to be trusted in place
+/// of the `user_id`/`tenant_id` fields a caller puts in a request body
+#[derive(Debug, Clone)]
+pub struct AuthenticatedIdentity {
+    pub tenant_id: String,
+    pub user_id: String,
+}
+
+/// Verifies bearer tokens of the form `<tenant_id>.<user_id>.<hex hmac>`,
+/// where the HMAC-SHA256 signature covers `<tenant_id>.<user_id>` under a
+/// shared secret. Real deployments would issue these tokens from a proper
+/// identity provider; this only verifies them.
+struct AuthTokenValidator {
+    key: hmac::Key,
+}
+
+impl AuthTokenValidator {
+    /// Load the shared secret from `secret_path`, falling back to an
+    /// ephemeral process-local secret if none is configured, mirroring
+    /// `KeyRing`'s dev-environment fallback
+    async fn new(secret_path: &PathBuf) -> Result<Self> {
+        let secret = if secret_path.exists() {
+            fs::read(secret_path).await?
+        } else {
+            warn!(
+                "No auth token secret found at {:?}; generating an ephemeral secret for this \
+                 process only. Previously issued bearer tokens will not validate.",
+                secret_path
+            );
+            let rng = SystemRandom::new();
+            let mut secret = vec![0u8; 32];
+            rng.fill(&mut secret)?;
+            secret
+        };
+
+        Ok(Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, &secret),
+        })
+    }
+
+    fn verify(&self, token: &str) -> Result<AuthenticatedIdentity> {
+        let mut parts = token.splitn(3, '.');
+        let tenant_id = parts.next().filter(|s| !s.is_empty());
+        let user_id = parts.next().filter(|s| !s.is_empty());
+        let signature_hex = parts.next().filter(|s| !s.is_empty());
+
+        let (tenant_id, user_id, signature_hex) = match (tenant_id, user_id, signature_hex) {
+            (Some(t), Some(u), Some(s)) => (t, u, s),
+            _ => return Err(anyhow::anyhow!("malformed bearer token")),
+        };
+
+        let signature = hex_decode(signature_hex).context("malformed bearer token signature")?;
+        let message = format!("{tenant_id}.{user_id}");
+        hmac::verify(&self.key, message.as_bytes(), &signature)
+            .map_err(|_| anyhow::anyhow!("bearer token signature does not match"))?;
+
+        Ok(AuthenticatedIdentity {
+            tenant_id: tenant_id.to_string(),
+            user_id: user_id.to_string(),
+        })
+    }
+}
+
+/// Decode a lowercase or uppercase hex string into bytes
+fn hex_decode(s: &str) -> Result<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return Err(anyhow::anyhow!("hex string has odd length"));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).context("invalid hex digit"))
+        .collect()
 }
 
 /// Result of security validation
@@ -24,9 +385,35 @@ pub struct SecurityValidationResult {
     pub recommendations: Vec<String>,
 }
 
-/// Policy engine for OPA integration
+/// Policy engine for OPA integration. Operator policies are authored as
+/// `.rego` files under `opa_policy_path`, compiled once into a `regorus`
+/// engine, and re-evaluated per request against a structured input document.
 struct PolicyEngine {
-    policies: HashMap<String, String>,
+    policy_path: PathBuf,
+    allow_query: String,
+    violations_query: String,
+    state: RwLock<PolicyEngineState>,
+}
+
+/// The compiled policy set, reloaded whenever a `.rego` file's mtime
+/// advances so operators can edit policies without a process restart
+struct PolicyEngineState {
+    engine: Option<regorus::Engine>,
+    file_versions: HashMap<PathBuf, std::time::SystemTime>,
+    compile_error: Option<String>,
+}
+
+/// Structured input document handed to the Rego evaluator, mirroring what
+/// `CodeAnalyzer` already extracts so policies can reason about the same
+/// signals the built-in static checks do
+#[derive(Debug, Clone, serde::Serialize)]
+struct PolicyInput<'a> {
+    user_id: &'a str,
+    code: &'a str,
+    detected_imports: &'a [String],
+    detected_calls: &'a [String],
+    risk_score: f64,
+    network_target: Option<&'a str>,
 }
 
 /// Code analyzer for static analysis
@@ -57,17 +444,52 @@ enum Severity {
 struct AuditLogger {
     enabled: bool,
     log_path: PathBuf,
+    rotate_max_bytes: u64,
+    rotate_max_age: Duration,
+    chain: Mutex<AuditChainState>,
+    /// Tamper-evident Merkle tree over the same lines written to
+    /// `log_path`, so truncation or edits of the log can be detected even
+    /// if an attacker also patches up the hash chain. See
+    /// [`crate::merkle_audit`].
+    merkle: MerkleAuditLog,
 }
 
-/// Security audit event
-#[derive(Debug, Clone, serde::Serialize)]
+/// Mutable hash-chain and rotation bookkeeping for the audit logger, guarded
+/// by a single mutex so concurrent writers can't interleave hash links
+struct AuditChainState {
+    tip_hash: String,
+    current_size: u64,
+    segment_started_at: Instant,
+}
+
+/// Security audit event. `prev_hash`/`hash` form a tamper-evident chain:
+/// `hash = SHA-256(prev_hash || canonical_json(event with hash=""))`, so
+/// altering or deleting any past entry breaks every hash after it.
+/// `metadata` is a `BTreeMap`, not a `HashMap`, specifically so that
+/// `canonical_json` actually is canonical -- `serde_json` serializes map
+/// keys in iteration order, and `HashMap`'s iteration order is randomized
+/// per-process, which would make the recomputed hash depend on which
+/// process re-serializes the event rather than only on its content.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 struct AuditEvent {
     timestamp: chrono::DateTime<chrono::Utc>,
     user_id: String,
     event_type: String,
     severity: String,
     description: String,
-    metadata: HashMap<String, String>,
+    metadata: BTreeMap<String, String>,
+    prev_hash: String,
+    #[serde(default)]
+    hash: String,
+}
+
+/// The first broken link found while replaying an audit chain, identifying
+/// exactly where tampering or corruption occurred
+#[derive(Debug, Clone)]
+pub struct AuditChainBreak {
+    pub file: PathBuf,
+    pub line: usize,
+    pub reason: String,
 }
 
 impl SecurityManager {
@@ -75,18 +497,40 @@ impl SecurityManager {
     pub async fn new(config: &SecurityConfig) -> Result<Self> {
         info!("Initializing security manager");
 
-        let policy_engine = PolicyEngine::new(&config.opa_policy_path).await?;
+        let policy_engine = PolicyEngine::new(
+            &config.opa_policy_path,
+            &config.opa_allow_query,
+            &config.opa_violations_query,
+        )
+        .await?;
         let code_analyzer = CodeAnalyzer::new().await?;
-        let audit_logger = AuditLogger::new(config.enable_audit_log, &config.audit_log_path)?;
+        let audit_logger = AuditLogger::new(
+            config.enable_audit_log,
+            &config.audit_log_path,
+            config.audit_log_rotate_max_bytes,
+            config.audit_log_rotate_max_age,
+        )?;
+        let key_ring = KeyRing::new(&config.encryption_key_path, &config.encryption_key_id).await?;
+        let firewall = EgressFirewall::new(config)?;
+        let auth_validator = AuthTokenValidator::new(&config.auth_token_secret_path).await?;
 
         Ok(Self {
             config: config.clone(),
             policy_engine,
             code_analyzer,
             audit_logger,
+            key_ring,
+            firewall,
+            auth_validator,
         })
     }
 
+    /// Verify a bearer token extracted from gRPC request metadata and return
+    /// the identity it authenticates, for use by the gRPC `AuthInterceptor`
+    pub fn authenticate_bearer_token(&self, token: &str) -> Result<AuthenticatedIdentity> {
+        self.auth_validator.verify(token)
+    }
+
     /// Validate code for security issues
     pub async fn validate_code(&self, code: &str, user_id: &str) -> Result<SecurityValidationResult> {
         debug!("Validating code for user: {}", user_id);
@@ -101,10 +545,24 @@ impl SecurityManager {
         risk_score += analysis_result.risk_score;
         recommendations.extend(analysis_result.recommendations);
 
-        // 2. Policy evaluation
-        let policy_result = self.policy_engine.evaluate_code_policy(code, user_id).await?;
+        // 2. Policy evaluation against the operator-authored Rego policies
+        let policy_input = PolicyInput {
+            user_id,
+            code,
+            detected_imports: &analysis_result.detected_imports,
+            detected_calls: &analysis_result.detected_calls,
+            risk_score,
+            network_target: None,
+        };
+        let policy_result = self.policy_engine.evaluate_code_policy(&policy_input).await?;
         if !policy_result.allowed {
-            violations.push(format!("Policy violation: {}", policy_result.reason));
+            if policy_result.violations.is_empty() {
+                violations.push(format!("Policy violation: {}", policy_result.reason));
+            } else {
+                for violation in &policy_result.violations {
+                    violations.push(format!("Policy violation: {}", violation));
+                }
+            }
             risk_score += 50.0; // High penalty for policy violations
         }
 
@@ -127,150 +585,349 @@ impl SecurityManager {
         })
     }
 
-    /// Validate network access request
+    /// Validate an outbound network access request. Resolves the host and
+    /// evaluates every resolved address against the egress firewall, so a
+    /// domain that resolves to a private/internal address is rejected even
+    /// if the domain itself is allowlisted.
     pub async fn validate_network_access(
         &self,
         host: &str,
         port: u16,
         user_id: &str,
-    ) -> Result<bool> {
+    ) -> Result<NetworkAccessResult> {
         debug!("Validating network access to {}:{} for user {}", host, port, user_id);
 
-        // Check against allowed hosts
-        let allowed = self.is_host_allowed(host) && self.is_port_allowed(port);
+        let resolved = self.resolve_host(host, port).await.unwrap_or_default();
+        let result = self.firewall.evaluate(host, port, &resolved);
 
-        // Log audit event
         self.audit_logger.log_network_access_event(
             user_id,
             host,
             port,
-            allowed,
+            &result,
         ).await?;
 
-        Ok(allowed)
+        Ok(result)
     }
 
-    /// Check if host is allowed
-    fn is_host_allowed(&self, host: &str) -> bool {
-        // Allow localhost and specific whitelisted hosts
-        if host == "localhost" || host == "127.0.0.1" || host == "::1" {
-            return true;
-        }
-
-        // Check against configuration (would be loaded from config)
-        let allowed_hosts = vec![
-            "api.openai.com",
-            "api.anthropic.com",
-            "api.cohere.ai",
-            "httpbin.org", // For testing
-        ];
-
-        allowed_hosts.iter().any(|&allowed| host.contains(allowed))
+    /// Re-validate a single resolved address immediately before connecting,
+    /// closing the window a DNS-rebinding attack would otherwise exploit
+    /// between `validate_network_access` and the actual connection.
+    pub fn validate_resolved_address(&self, host: &str, ip: IpAddr, port: u16) -> NetworkAccessResult {
+        self.firewall.evaluate(host, port, std::slice::from_ref(&ip))
     }
 
-    /// Check if port is allowed
-    fn is_port_allowed(&self, port: u16) -> bool {
-        // Allow standard HTTP/HTTPS ports and some common API ports
-        matches!(port, 80 | 443 | 8000..=8999)
+    /// IP-level safety classification only -- no domain or port check,
+    /// just "is this a denied CIDR or a private/loopback/link-local/CGNAT
+    /// address that wasn't explicitly allowlisted". Exposed so other
+    /// egress-control points in the crate (currently the WASI sandbox's
+    /// per-execution `allowed_hosts` check) can fall back on the same
+    /// CIDR/private-range policy instead of re-implementing it narrower
+    /// and without those protections.
+    pub(crate) fn ip_is_safe_for_egress(&self, ip: IpAddr) -> bool {
+        self.firewall.evaluate_ip(ip).0
     }
 
-    /// Encrypt sensitive data
-    pub fn encrypt_data(&self, data: &str) -> Result<String> {
-        // Simple encryption implementation (would use proper crypto in production)
-        use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
-        use ring::rand::{SecureRandom, SystemRandom};
+    async fn resolve_host(&self, host: &str, port: u16) -> Result<Vec<IpAddr>> {
+        let addrs = tokio::net::lookup_host((host, port)).await?;
+        Ok(addrs.map(|addr| addr.ip()).collect())
+    }
 
-        let rng = SystemRandom::new();
-        let mut key_bytes = [0u8; 32];
-        rng.fill(&mut key_bytes)?;
+    /// Replay the audit log's hash chain (including any sealed, rotated
+    /// segments) and return the first broken link found, or `None` if the
+    /// entire chain verifies cleanly.
+    pub async fn verify_audit_chain(&self) -> Result<Option<AuditChainBreak>> {
+        self.audit_logger.verify_audit_chain().await
+    }
 
-        let unbound_key = UnboundKey::new(&AES_256_GCM, &key_bytes)?;
-        let key = LessSafeKey::new(unbound_key);
+    /// Current root of the audit log's Merkle tree (see [`crate::merkle_audit`]),
+    /// for operators to compare against an externally pinned or signed value.
+    pub fn audit_merkle_root(&self) -> String {
+        self.audit_logger.merkle.root()
+    }
 
-        let mut nonce_bytes = [0u8; 12];
-        rng.fill(&mut nonce_bytes)?;
-        let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+    /// Sibling path proving `leaf_index`'s membership in the audit log,
+    /// rebuilt by replaying every segment on disk.
+    pub fn prove_audit_entry(&self, leaf_index: u64) -> Result<Vec<(String, bool)>> {
+        let lines = self.audit_logger.all_lines()?;
+        MerkleAuditLog::prove(leaf_index, lines.into_iter()).map_err(anyhow::Error::from)
+    }
 
-        let mut in_out = data.as_bytes().to_vec();
-        key.seal_in_place_append_tag(nonce, Aad::empty(), &mut in_out)?;
+    /// Replay the audit log against the last persisted Merkle root and
+    /// confirm they still match. `Ok(false)` means the log was truncated or
+    /// edited after that root was signed.
+    pub fn verify_audit_merkle_root(&self) -> Result<bool> {
+        let lines = self.audit_logger.all_lines()?;
+        MerkleAuditLog::verify_root(&self.audit_logger.log_path, lines.into_iter()).map_err(anyhow::Error::from)
+    }
 
-        // Combine nonce and ciphertext
-        let mut result = nonce_bytes.to_vec();
-        result.extend_from_slice(&in_out);
+    /// Force-persist the audit log's current Merkle root, so a clean
+    /// shutdown never leaves the signed anchor more than a few appends
+    /// stale. Mirrors `MetricsCollector::flush`.
+    pub fn flush_audit_merkle_root(&self) -> Result<()> {
+        self.audit_logger.merkle.flush().map_err(anyhow::Error::from)
+    }
 
-        Ok(base64::encode(result))
+    /// Encrypt sensitive data using envelope encryption: a fresh random DEK
+    /// encrypts the payload, and the DEK itself is sealed under the active
+    /// KEK so the master key is never directly exposed to payload-sized data.
+    pub fn encrypt_data(&self, data: &str) -> Result<String> {
+        let rng = SystemRandom::new();
+        let (kek_id, kek_bytes) = self.key_ring.active_key()?;
+        let kek = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, kek_bytes)?);
+
+        // Generate a fresh DEK for this payload
+        let mut dek_bytes = [0u8; 32];
+        rng.fill(&mut dek_bytes)?;
+
+        // Wrap the DEK under the KEK
+        let mut dek_nonce_bytes = [0u8; 12];
+        rng.fill(&mut dek_nonce_bytes)?;
+        let mut wrapped_dek = dek_bytes.to_vec();
+        kek.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(dek_nonce_bytes),
+            Aad::empty(),
+            &mut wrapped_dek,
+        )?;
+
+        // Encrypt the payload under the DEK
+        let dek = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &dek_bytes)?);
+        let mut payload_nonce_bytes = [0u8; 12];
+        rng.fill(&mut payload_nonce_bytes)?;
+        let mut ciphertext = data.as_bytes().to_vec();
+        dek.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(payload_nonce_bytes),
+            Aad::empty(),
+            &mut ciphertext,
+        )?;
+
+        // version || kek_id_len || kek_id || wrapped_dek_len || wrapped_dek || dek_nonce || payload_nonce || ciphertext
+        let kek_id_bytes = kek_id.as_bytes();
+        let mut out = Vec::with_capacity(
+            1 + 1 + kek_id_bytes.len() + 2 + wrapped_dek.len() + 12 + 12 + ciphertext.len(),
+        );
+        out.push(ENVELOPE_VERSION);
+        out.push(kek_id_bytes.len() as u8);
+        out.extend_from_slice(kek_id_bytes);
+        out.extend_from_slice(&(wrapped_dek.len() as u16).to_be_bytes());
+        out.extend_from_slice(&wrapped_dek);
+        out.extend_from_slice(&dek_nonce_bytes);
+        out.extend_from_slice(&payload_nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+
+        Ok(base64::encode(out))
     }
 
-    /// Decrypt sensitive data
+    /// Reverse `encrypt_data`: unwrap the DEK with the KEK named in the
+    /// envelope (so rotated-out KEKs still decrypt older payloads), then
+    /// decrypt the payload with the recovered DEK.
     pub fn decrypt_data(&self, encrypted_data: &str) -> Result<String> {
-        // Simple decryption implementation
         let data = base64::decode(encrypted_data)?;
-        
-        if data.len() < 12 {
-            return Err(anyhow::anyhow!("Invalid encrypted data"));
+        let mut cursor = 0usize;
+
+        let version = *data.get(cursor).context("envelope truncated: version byte")?;
+        cursor += 1;
+        if version != ENVELOPE_VERSION {
+            return Err(anyhow::anyhow!("unsupported envelope version: {}", version));
         }
 
-        // In a real implementation, we would properly manage keys
-        // For now, just return a placeholder
-        Ok("decrypted_data".to_string())
+        let kek_id_len = *data.get(cursor).context("envelope truncated: kek id length")? as usize;
+        cursor += 1;
+        let kek_id = std::str::from_utf8(
+            data.get(cursor..cursor + kek_id_len)
+                .context("envelope truncated: kek id")?,
+        )?;
+        cursor += kek_id_len;
+
+        let wrapped_dek_len = u16::from_be_bytes(
+            data.get(cursor..cursor + 2)
+                .context("envelope truncated: wrapped dek length")?
+                .try_into()?,
+        ) as usize;
+        cursor += 2;
+        let mut wrapped_dek = data
+            .get(cursor..cursor + wrapped_dek_len)
+            .context("envelope truncated: wrapped dek")?
+            .to_vec();
+        cursor += wrapped_dek_len;
+
+        let dek_nonce_bytes: [u8; 12] = data
+            .get(cursor..cursor + 12)
+            .context("envelope truncated: dek nonce")?
+            .try_into()?;
+        cursor += 12;
+        let payload_nonce_bytes: [u8; 12] = data
+            .get(cursor..cursor + 12)
+            .context("envelope truncated: payload nonce")?
+            .try_into()?;
+        cursor += 12;
+        let mut ciphertext = data
+            .get(cursor..)
+            .context("envelope truncated: ciphertext")?
+            .to_vec();
+
+        let kek_bytes = self.key_ring.key_by_id(kek_id)?;
+        let kek = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, kek_bytes)?);
+        let dek_bytes = kek
+            .open_in_place(Nonce::assume_unique_for_key(dek_nonce_bytes), Aad::empty(), &mut wrapped_dek)
+            .map_err(|_| anyhow::anyhow!("failed to unwrap DEK: {}", kek_id))?;
+
+        let dek = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, dek_bytes)?);
+        let plaintext = dek
+            .open_in_place(Nonce::assume_unique_for_key(payload_nonce_bytes), Aad::empty(), &mut ciphertext)
+            .map_err(|_| anyhow::anyhow!("failed to decrypt payload"))?;
+
+        Ok(String::from_utf8(plaintext.to_vec())?)
     }
 }
 
 impl PolicyEngine {
-    async fn new(policy_path: &PathBuf) -> Result<Self> {
-        let mut policies = HashMap::new();
+    async fn new(policy_path: &PathBuf, allow_query: &str, violations_query: &str) -> Result<Self> {
+        let state = Self::load(policy_path).await;
+
+        Ok(Self {
+            policy_path: policy_path.clone(),
+            allow_query: allow_query.to_string(),
+            violations_query: violations_query.to_string(),
+            state: RwLock::new(state),
+        })
+    }
+
+    /// Compile every `.rego` file under `policy_path` into a fresh engine.
+    /// A file that fails to read or compile sets `compile_error` instead of
+    /// returning `Err`, so the engine can still be constructed and fail
+    /// closed (deny everything) until the policy is fixed, rather than
+    /// taking down the whole security manager at startup.
+    async fn load(policy_path: &PathBuf) -> PolicyEngineState {
+        let mut engine = regorus::Engine::new();
+        let mut file_versions = HashMap::new();
+        let mut compile_error = None;
+        let mut loaded_any = false;
 
-        // Load policy files
         if policy_path.exists() {
-            let mut entries = fs::read_dir(policy_path).await?;
-            while let Some(entry) = entries.next_entry().await? {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("rego") {
-                    let policy_name = path.file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("unknown")
-                        .to_string();
-                    let policy_content = fs::read_to_string(&path).await?;
-                    policies.insert(policy_name, policy_content);
+            match fs::read_dir(policy_path).await {
+                Ok(mut entries) => loop {
+                    let next = entries.next_entry().await;
+                    let entry = match next {
+                        Ok(Some(entry)) => entry,
+                        Ok(None) => break,
+                        Err(e) => {
+                            compile_error = Some(format!("failed to read policy directory: {e}"));
+                            break;
+                        }
+                    };
+
+                    let path = entry.path();
+                    if path.extension().and_then(|s| s.to_str()) != Some("rego") {
+                        continue;
+                    }
+
+                    let content = match fs::read_to_string(&path).await {
+                        Ok(content) => content,
+                        Err(e) => {
+                            compile_error = Some(format!("failed to read {}: {e}", path.display()));
+                            break;
+                        }
+                    };
+
+                    if let Err(e) = engine.add_policy(path.display().to_string(), content) {
+                        compile_error = Some(format!("failed to compile {}: {e}", path.display()));
+                        break;
+                    }
+
+                    loaded_any = true;
+                    if let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) {
+                        file_versions.insert(path, modified);
+                    }
+                },
+                Err(e) => {
+                    compile_error = Some(format!("failed to read policy directory {}: {e}", policy_path.display()));
                 }
             }
         }
 
-        Ok(Self { policies })
+        PolicyEngineState {
+            engine: if loaded_any { Some(engine) } else { None },
+            file_versions,
+            compile_error,
+        }
     }
 
-    async fn evaluate_code_policy(&self, code: &str, user_id: &str) -> Result<PolicyResult> {
-        // Simplified policy evaluation
-        // In a real implementation, this would use OPA's Rego engine
+    /// Snapshot the mtime of every `.rego` file currently on disk, used to
+    /// detect whether the loaded policy set is stale
+    async fn current_file_versions(policy_path: &Path) -> HashMap<PathBuf, std::time::SystemTime> {
+        let mut versions = HashMap::new();
+        if let Ok(mut entries) = fs::read_dir(policy_path).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                if path.extension().and_then(|s| s.to_str()) != Some("rego") {
+                    continue;
+                }
+                if let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) {
+                    versions.insert(path, modified);
+                }
+            }
+        }
+        versions
+    }
 
-        // Check for dangerous imports
-        if code.contains("import os") && code.contains("system") {
-            return Ok(PolicyResult {
-                allowed: false,
-                reason: "System command execution not allowed".to_string(),
-            });
+    async fn reload_if_changed(&self) {
+        let latest = Self::current_file_versions(&self.policy_path).await;
+        let needs_reload = {
+            let state = self.state.read().await;
+            state.compile_error.is_some() || state.file_versions != latest
+        };
+
+        if needs_reload {
+            let new_state = Self::load(&self.policy_path).await;
+            *self.state.write().await = new_state;
         }
+    }
+
+    async fn evaluate_code_policy(&self, input: &PolicyInput<'_>) -> Result<PolicyResult> {
+        self.reload_if_changed().await;
+
+        let state = self.state.read().await;
 
-        // Check for file system access
-        if code.contains("open(") && (code.contains("'w'") || code.contains("'a'")) {
+        if let Some(compile_error) = &state.compile_error {
+            warn!("Rego policy set failed to compile, failing closed: {}", compile_error);
             return Ok(PolicyResult {
                 allowed: false,
-                reason: "File write access not allowed".to_string(),
+                reason: format!("policy compilation failed, denying by default: {compile_error}"),
+                violations: vec![compile_error.clone()],
             });
         }
 
-        // Check for network access
-        if code.contains("requests.") || code.contains("urllib") || code.contains("socket") {
+        let Some(engine) = &state.engine else {
             return Ok(PolicyResult {
-                allowed: false,
-                reason: "Direct network access not allowed".to_string(),
+                allowed: true,
+                reason: "no policies loaded".to_string(),
+                violations: Vec::new(),
             });
-        }
+        };
 
-        Ok(PolicyResult {
-            allowed: true,
-            reason: "Code passed policy evaluation".to_string(),
-        })
+        let mut engine = engine.clone();
+        let input_json = serde_json::to_string(input)?;
+        engine.set_input(regorus::Value::from_json_str(&input_json)?);
+
+        let allowed = engine.eval_bool_query(self.allow_query.clone(), false).unwrap_or(false);
+
+        let violations: Vec<String> = engine
+            .eval_rule(self.violations_query.clone())
+            .ok()
+            .and_then(|value| serde_json::to_value(value).ok())
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+
+        let reason = if allowed {
+            "code passed policy evaluation".to_string()
+        } else if !violations.is_empty() {
+            violations.join("; ")
+        } else {
+            "denied by policy".to_string()
+        };
+
+        Ok(PolicyResult { allowed, reason, violations })
     }
 }
 
@@ -278,6 +935,7 @@ impl PolicyEngine {
 struct PolicyResult {
     allowed: bool,
     reason: String,
+    violations: Vec<String>,
 }
 
 impl CodeAnalyzer {
@@ -343,6 +1001,8 @@ impl CodeAnalyzer {
         let mut violations = Vec::new();
         let mut risk_score = 0.0;
         let mut recommendations = Vec::new();
+        let mut detected_imports = Vec::new();
+        let mut detected_calls = Vec::new();
 
         // Check for dangerous patterns
         for pattern in &self.dangerous_patterns {
@@ -356,7 +1016,7 @@ impl CodeAnalyzer {
 
                 violations.push(format!("{}: {}", pattern.severity_str(), pattern.description));
                 risk_score += severity_score;
-                
+
                 recommendations.push(format!("Remove or replace: {}", pattern.description));
             }
         }
@@ -366,6 +1026,7 @@ impl CodeAnalyzer {
             if code.contains(func) {
                 violations.push(format!("Blocked function usage: {}", func));
                 risk_score += 30.0;
+                detected_calls.push(func.clone());
             }
         }
 
@@ -373,6 +1034,7 @@ impl CodeAnalyzer {
         for line in code.lines() {
             let trimmed = line.trim();
             if trimmed.starts_with("import ") || trimmed.starts_with("from ") {
+                detected_imports.push(trimmed.to_string());
                 if !self.is_import_allowed(trimmed) {
                     violations.push(format!("Disallowed import: {}", trimmed));
                     risk_score += 20.0;
@@ -384,6 +1046,8 @@ impl CodeAnalyzer {
             violations,
             risk_score,
             recommendations,
+            detected_imports,
+            detected_calls,
         })
     }
 
@@ -403,6 +1067,8 @@ struct CodeAnalysisResult {
     violations: Vec<String>,
     risk_score: f64,
     recommendations: Vec<String>,
+    detected_imports: Vec<String>,
+    detected_calls: Vec<String>,
 }
 
 impl Severity {
@@ -417,7 +1083,7 @@ impl Severity {
 }
 
 impl AuditLogger {
-    fn new(enabled: bool, log_path: &PathBuf) -> Result<Self> {
+    fn new(enabled: bool, log_path: &PathBuf, rotate_max_bytes: u64, rotate_max_age: Duration) -> Result<Self> {
         if enabled {
             // Ensure log directory exists
             if let Some(parent) = log_path.parent() {
@@ -425,12 +1091,64 @@ impl AuditLogger {
             }
         }
 
+        let tip_hash = Self::load_tip(log_path).unwrap_or_else(|| GENESIS_HASH.to_string());
+        let current_size = std::fs::metadata(log_path).map(|m| m.len()).unwrap_or(0);
+
+        let mut segments = sealed_segments_for(log_path).unwrap_or_default();
+        segments.push(log_path.clone());
+        let existing_lines: Vec<String> = segments
+            .into_iter()
+            .filter_map(|file| std::fs::read_to_string(file).ok())
+            .flat_map(|contents| {
+                contents
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| line.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let merkle = MerkleAuditLog::new(log_path, existing_lines.into_iter());
+
         Ok(Self {
             enabled,
             log_path: log_path.clone(),
+            rotate_max_bytes,
+            rotate_max_age,
+            chain: Mutex::new(AuditChainState {
+                tip_hash,
+                current_size,
+                segment_started_at: Instant::now(),
+            }),
+            merkle,
         })
     }
 
+    /// Recover the chain tip from the last line of the most recent segment
+    /// that has one, so a restart continues the chain instead of starting a
+    /// new one. Must walk sealed segments the same way
+    /// `verify_audit_chain`/`all_lines` do and not just the live log file --
+    /// a restart landing right after a rotation would otherwise see a fresh,
+    /// possibly still-empty live file and silently reset the tip to
+    /// `GENESIS_HASH`, breaking the very first event appended after restart
+    /// off from everything rotated out before it.
+    fn load_tip(log_path: &PathBuf) -> Option<String> {
+        let mut segments = sealed_segments_for(log_path).unwrap_or_default();
+        segments.push(log_path.clone());
+
+        for file in segments.into_iter().rev() {
+            let Ok(contents) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            if let Some(last_line) = contents.lines().rev().find(|line| !line.trim().is_empty()) {
+                if let Ok(event) = serde_json::from_str::<AuditEvent>(last_line) {
+                    return Some(event.hash);
+                }
+            }
+        }
+
+        None
+    }
+
     async fn log_validation_event(
         &self,
         user_id: &str,
@@ -442,20 +1160,22 @@ impl AuditLogger {
             return Ok(());
         }
 
-        let event = AuditEvent {
+        let mut event = AuditEvent {
             timestamp: chrono::Utc::now(),
             user_id: user_id.to_string(),
             event_type: "code_validation".to_string(),
             severity: if is_safe { "INFO".to_string() } else { "WARNING".to_string() },
             description: format!("Code validation result: safe={}, risk_score={:.2}", is_safe, risk_score),
-            metadata: HashMap::from([
+            metadata: BTreeMap::from([
                 ("risk_score".to_string(), risk_score.to_string()),
                 ("violations_count".to_string(), violations.len().to_string()),
                 ("violations".to_string(), violations.join("; ")),
             ]),
+            prev_hash: String::new(),
+            hash: String::new(),
         };
 
-        self.write_audit_event(&event).await
+        self.write_audit_event(&mut event).await
     }
 
     async fn log_network_access_event(
@@ -463,31 +1183,51 @@ impl AuditLogger {
         user_id: &str,
         host: &str,
         port: u16,
-        allowed: bool,
+        result: &NetworkAccessResult,
     ) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
 
-        let event = AuditEvent {
+        let mut event = AuditEvent {
             timestamp: chrono::Utc::now(),
             user_id: user_id.to_string(),
             event_type: "network_access".to_string(),
-            severity: if allowed { "INFO".to_string() } else { "WARNING".to_string() },
-            description: format!("Network access request: {}:{} - {}", host, port, if allowed { "ALLOWED" } else { "DENIED" }),
-            metadata: HashMap::from([
+            severity: if result.allowed { "INFO".to_string() } else { "WARNING".to_string() },
+            description: format!(
+                "Network access request: {}:{} - {} ({})",
+                host, port, if result.allowed { "ALLOWED" } else { "DENIED" }, result.matched_rule
+            ),
+            metadata: BTreeMap::from([
                 ("host".to_string(), host.to_string()),
                 ("port".to_string(), port.to_string()),
-                ("allowed".to_string(), allowed.to_string()),
+                ("allowed".to_string(), result.allowed.to_string()),
+                ("matched_rule".to_string(), result.matched_rule.clone()),
+                ("resolved_ip".to_string(), result.resolved_ip.map(|ip| ip.to_string()).unwrap_or_default()),
             ]),
+            prev_hash: String::new(),
+            hash: String::new(),
         };
 
-        self.write_audit_event(&event).await
+        self.write_audit_event(&mut event).await
     }
 
-    async fn write_audit_event(&self, event: &AuditEvent) -> Result<()> {
-        let log_line = serde_json::to_string(event)? + "\n";
-        
+    /// Append `event` to the chain: fill in `prev_hash` from the current
+    /// tip, compute this event's own hash, persist it, then advance the tip.
+    /// Rotates the segment first if it's grown past the configured size/age.
+    async fn write_audit_event(&self, event: &mut AuditEvent) -> Result<()> {
+        let mut chain = self.chain.lock().await;
+
+        self.maybe_rotate(&mut chain).await?;
+
+        event.prev_hash = chain.tip_hash.clone();
+        event.hash = String::new();
+        let canonical = serde_json::to_string(event)?;
+        let hash = hash_chain_link(&event.prev_hash, &canonical);
+        event.hash = hash.clone();
+
+        let line = serde_json::to_string(event)?;
+        let log_line = line.clone() + "\n";
         tokio::fs::OpenOptions::new()
             .create(true)
             .append(true)
@@ -496,6 +1236,184 @@ impl AuditLogger {
             .write_all(log_line.as_bytes())
             .await?;
 
+        chain.tip_hash = hash;
+        chain.current_size += log_line.len() as u64;
+
+        if let Err(e) = self.merkle.append(&line) {
+            warn!("Failed to update audit Merkle tree: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Seal the current segment with a terminal event carrying its final
+    /// hash, then rename it aside so the chain continues into a fresh file
+    /// at `log_path`. A no-op if neither the size nor age threshold is met.
+    async fn maybe_rotate(&self, chain: &mut AuditChainState) -> Result<()> {
+        if !self.log_path.exists() {
+            return Ok(());
+        }
+        let age_exceeded = chain.segment_started_at.elapsed() >= self.rotate_max_age;
+        let size_exceeded = chain.current_size >= self.rotate_max_bytes;
+        if !age_exceeded && !size_exceeded {
+            return Ok(());
+        }
+
+        let mut seal_event = AuditEvent {
+            timestamp: chrono::Utc::now(),
+            user_id: "system".to_string(),
+            event_type: "segment_sealed".to_string(),
+            severity: "INFO".to_string(),
+            description: "Audit log segment sealed and rotated".to_string(),
+            metadata: BTreeMap::new(),
+            prev_hash: chain.tip_hash.clone(),
+            hash: String::new(),
+        };
+        let canonical = serde_json::to_string(&seal_event)?;
+        let seal_hash = hash_chain_link(&seal_event.prev_hash, &canonical);
+        seal_event.hash = seal_hash.clone();
+
+        let seal_line = serde_json::to_string(&seal_event)?;
+        tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .await?
+            .write_all((seal_line.clone() + "\n").as_bytes())
+            .await?;
+
+        let rotated_path = self
+            .log_path
+            .with_extension(format!("{}.sealed", chrono::Utc::now().timestamp()));
+        tokio::fs::rename(&self.log_path, &rotated_path).await?;
+
+        info!("Rotated audit log segment to {:?}", rotated_path);
+
+        chain.tip_hash = seal_hash;
+        chain.current_size = 0;
+        chain.segment_started_at = Instant::now();
+
+        if let Err(e) = self.merkle.append(&seal_line) {
+            warn!("Failed to update audit Merkle tree: {}", e);
+        }
+
         Ok(())
     }
+
+    /// Every `<stem>.<unix-timestamp>.sealed` file next to `log_path`,
+    /// oldest first, so the live chain can be replayed across rotations
+    fn sealed_segments(&self) -> Result<Vec<PathBuf>> {
+        sealed_segments_for(&self.log_path)
+    }
+
+    /// Every line of every sealed segment followed by the live log file, in
+    /// order, for callers that need to replay the whole audit trail (chain
+    /// verification, Merkle proofs/root verification).
+    fn all_lines(&self) -> Result<Vec<String>> {
+        let mut files = self.sealed_segments()?;
+        files.push(self.log_path.clone());
+
+        let mut lines = Vec::new();
+        for file in files {
+            if let Ok(contents) = std::fs::read_to_string(&file) {
+                lines.extend(
+                    contents
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .map(|line| line.to_string()),
+                );
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Replay every sealed segment followed by the live log file, recomputing
+    /// each event's hash from its recorded `prev_hash` and comparing it
+    /// against the hash actually stored alongside the event. Returns the
+    /// first mismatch found, if any.
+    async fn verify_audit_chain(&self) -> Result<Option<AuditChainBreak>> {
+        let mut files = self.sealed_segments()?;
+        files.push(self.log_path.clone());
+
+        let mut expected_prev = GENESIS_HASH.to_string();
+
+        for file in files {
+            let Ok(contents) = tokio::fs::read_to_string(&file).await else {
+                continue;
+            };
+
+            for (line_no, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let mut event: AuditEvent = match serde_json::from_str(line) {
+                    Ok(event) => event,
+                    Err(_) => {
+                        return Ok(Some(AuditChainBreak {
+                            file,
+                            line: line_no + 1,
+                            reason: "malformed audit event".to_string(),
+                        }))
+                    }
+                };
+
+                if event.prev_hash != expected_prev {
+                    return Ok(Some(AuditChainBreak {
+                        file,
+                        line: line_no + 1,
+                        reason: "prev_hash does not match the chain tip".to_string(),
+                    }));
+                }
+
+                let recorded_hash = std::mem::take(&mut event.hash);
+                let canonical = serde_json::to_string(&event)?;
+                let recomputed = hash_chain_link(&expected_prev, &canonical);
+
+                if recomputed != recorded_hash {
+                    return Ok(Some(AuditChainBreak {
+                        file,
+                        line: line_no + 1,
+                        reason: "hash does not match recomputed value".to_string(),
+                    }));
+                }
+
+                expected_prev = recorded_hash;
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Every `<stem>.<unix-timestamp>.sealed` file next to `log_path`, oldest
+/// first. Free-standing (rather than a method) so it can run before an
+/// `AuditLogger` exists, while its initial Merkle tree is being rebuilt.
+fn sealed_segments_for(log_path: &Path) -> Result<Vec<PathBuf>> {
+    let dir = log_path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = log_path.file_stem().and_then(|s| s.to_str()).unwrap_or("audit");
+
+    let mut segments: Vec<(i64, PathBuf)> = Vec::new();
+    if dir.exists() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|s| s.to_str()) else { continue };
+            let Some(rest) = name.strip_prefix(&format!("{stem}.")) else { continue };
+            let Some(ts_str) = rest.strip_suffix(".sealed") else { continue };
+            if let Ok(ts) = ts_str.parse::<i64>() {
+                segments.push((ts, path));
+            }
+        }
+    }
+    segments.sort_by_key(|(ts, _)| *ts);
+    Ok(segments.into_iter().map(|(_, path)| path).collect())
+}
+
+/// `hash = SHA-256(prev_hash || canonical_json)`, hex-encoded
+fn hash_chain_link(prev_hash: &str, canonical_json: &str) -> String {
+    let mut ctx = digest::Context::new(&digest::SHA256);
+    ctx.update(prev_hash.as_bytes());
+    ctx.update(canonical_json.as_bytes());
+    let output = ctx.finish();
+    output.as_ref().iter().map(|b| format!("{:02x}", b)).collect()
 }
\ No newline at end of file